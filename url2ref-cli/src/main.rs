@@ -2,29 +2,46 @@
 
 use std::env;
 use std::env::VarError;
+use std::fs;
+use std::path::PathBuf;
 
-use clap::{Parser, ValueEnum};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, ValueEnum};
 
-use url2ref::generator::{TranslationOptions, ArchiveOptions};
+use url2ref::attribute::AttributeType;
 use url2ref::generator::attribute_config::{AttributeConfig, AttributePriority};
 use url2ref::*;
 
 mod env_vars {
     pub const DEEPL_API_KEY: &str = "DEEPL_API_KEY";
+    pub const GOOGLE_API_KEY: &str = "GOOGLE_API_KEY";
 }
 
 /// Supported command-line arguments.
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct CommandLineArgs {
-    #[clap(short, long)]
-    url: String,
+    #[clap(short, long, required_unless_present("urls_file"))]
+    url: Option<String>,
 
-    #[clap(short, long, value_enum, default_value_t=CitationFormat::Wiki)]
+    /// File listing URLs to cite, one per line, instead of a single URL
+    #[clap(long, conflicts_with("url"))]
+    urls_file: Option<PathBuf>,
+
+    /// How many pages are fetched at once when citing the URLs of a file
+    #[clap(long, default_value_t=8)]
+    concurrency: usize,
+
+    /// TOML file with the generation options to use, overridden by those given on the command line
+    #[clap(long)]
+    config: Option<PathBuf>,
+
+    #[clap(short, long, default_value_t=CitationFormat::Wiki)]
     format: CitationFormat,
 
+    /// Only use metadata of this type: opengraph, schema_org, doi, html_meta, dublin_core, rdfa or zotero
     #[clap(short, long, default_value=None)]
-    metadata_priority: Option<MetadataType>,
+    metadata_priority: Option<generator::MetadataType>,
 
     #[clap(short, long, default_value=None, requires("target_lang"))]
     source_lang: Option<String>,
@@ -32,25 +49,98 @@ struct CommandLineArgs {
     #[clap(short, long, default_value=None)]
     target_lang: Option<String>,
 
+    /// Service used to translate the title, whose API key is read from the environment
+    #[clap(long, value_enum, default_value_t=TranslationProvider::Deepl)]
+    translation_provider: TranslationProvider,
+
+    /// Translate the site name along with the title, e.g. for sites in non-Latin scripts
+    #[clap(long, requires("target_lang"))]
+    translate_site: bool,
+
     #[clap(short, long, default_value_t=true)]
     include_archived: bool,
+
+    #[clap(long, value_enum, default_value_t=ArchiveProvider::Wayback)]
+    archive_provider: ArchiveProvider,
+
+    /// Status of the cited URL, given along with the archived URL
+    #[clap(long, value_enum, default_value_t=UrlStatus::Live)]
+    url_status: UrlStatus,
+
+    /// Omit publication dates in the future or before the web existed
+    #[clap(long)]
+    drop_implausible_dates: bool,
+
+    /// Follow the redirects of shortened URLs (e.g. t.co, bit.ly) and cite the final URL
+    #[clap(long)]
+    expand_short_urls: bool,
+
+    /// Extract the place the article was reported from out of its dateline (e.g. "LONDON —")
+    #[clap(long)]
+    extract_dateline: bool,
+
+    /// Take the publisher from the copyright notice in the page footer (e.g. "© 2024 Example Media Ltd.") if missing
+    #[clap(long)]
+    extract_copyright_publisher: bool,
+
+    /// Derive the site from the host of the URL (e.g. "bbc.co.uk") if the metadata names none
+    #[clap(long)]
+    derive_site_from_host: bool,
+
+    /// Record the SHA-256 hash of the fetched page in formats having notes (e.g. BibTeX)
+    #[clap(long)]
+    include_content_hash: bool,
+
+    /// Ask CrossRef whether the DOI of a scholarly article has been retracted or corrected
+    #[clap(long)]
+    check_retractions: bool,
+
+    /// Fail rather than generate a reference lacking a title or URL
+    #[clap(long)]
+    strict: bool,
+
+    /// Ask the server for the JSON-LD representation of the page rather than its HTML
+    #[clap(long)]
+    request_json_ld: bool,
+
+    /// Render dates of the last week relative to now (e.g. "3 days ago") in plain citations
+    #[clap(long)]
+    relative_dates: bool,
+
+    /// Truncate titles longer than this many characters at a word boundary in plain, Vancouver and Harvard citations
+    #[clap(long)]
+    max_title_chars: Option<usize>,
+}
+
+/// Supported translation providers.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum TranslationProvider {
+    /// DeepL, using the key in DEEPL_API_KEY
+    Deepl,
+    /// Google Cloud Translation, using the key in GOOGLE_API_KEY
+    Google,
 }
 
-/// Supported citation formats.
-/// [`clap`] is capable of automatically converting the variants
-/// into appropriate command-line arguments via the [`ValueEnum`] macro.
+/// Supported archive providers.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum CitationFormat {
-    /// Using {{cite web}} MediaWiki markup
-    Wiki,
-    /// Using BibTeX markup
-    Bibtex,
+enum ArchiveProvider {
+    /// The Internet Archive's Wayback Machine
+    Wayback,
+    /// archive.today
+    ArchiveToday,
 }
 
+/// Statuses of cited URLs, as given in Wikipedia citations.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum MetadataType {
-    Opengraph,
-    Schemaorg
+enum UrlStatus {
+    /// The page is still online
+    Live,
+    /// The page is offline, so the archived copy is linked
+    Dead,
+    /// The page is online but unfit to be linked, e.g. spam
+    Unfit,
+    /// The domain has been taken over by someone else
+    Usurped,
 }
 
 fn load_deepl_key() -> Result<String, VarError> {
@@ -58,43 +148,117 @@ fn load_deepl_key() -> Result<String, VarError> {
     Ok(deepl_key)
 }
 
-fn main() {
-    let args = CommandLineArgs::parse();
-    let query = args.url;
+fn load_google_key() -> Result<String, VarError> {
+    let google_key = env::var(env_vars::GOOGLE_API_KEY)?;
+    Ok(google_key)
+}
+
+/// Warnings are printed to stderr, so that they do not end up in the citations.
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("Warning: {warning}");
+    }
+}
 
-    let deepl_key = load_deepl_key().ok();
+fn main() {
+    let matches = CommandLineArgs::command().get_matches();
+    let args = CommandLineArgs::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    // Options given on the command line override those of the config file.
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
 
-    let translation_options = TranslationOptions {
-        source: args.source_lang,
-        target: args.target_lang,
-        deepl_key: deepl_key
+    let mut generation_options = match &args.config {
+        Some(path) => GenerationOptions::from_toml_str(&fs::read_to_string(path).unwrap()).unwrap(),
+        None => GenerationOptions::default(),
     };
 
-    let attribute_config = if args.metadata_priority.is_some() {
-        let metadata_type = match args.metadata_priority.unwrap() {
-            MetadataType::Opengraph => generator::MetadataType::OpenGraph,
-            MetadataType::Schemaorg => generator::MetadataType::SchemaOrg,
+    // API keys are only read from the environment, never from the config file.
+    let translation_options = &mut generation_options.translation_options;
+    translation_options.deepl_key = load_deepl_key().ok();
+    translation_options.google_key = load_google_key().ok();
+    if let Some(source_lang) = args.source_lang {
+        translation_options.source = Some(source_lang);
+    }
+    if let Some(target_lang) = args.target_lang {
+        translation_options.target = Some(target_lang);
+    }
+    if given("translation_provider") {
+        translation_options.provider = match args.translation_provider {
+            TranslationProvider::Deepl => generator::TranslationProvider::DeepL,
+            TranslationProvider::Google => generator::TranslationProvider::Google,
         };
+    }
+    if args.translate_site && !translation_options.attributes.contains(&AttributeType::Site) {
+        translation_options.attributes.push(AttributeType::Site);
+    }
+
+    if let Some(metadata_type) = args.metadata_priority {
         let attribute_priorities = AttributePriority::new(&[metadata_type]);
-        AttributeConfig::new(attribute_priorities)
-    } else {
-        AttributeConfig::default()
-    };
+        generation_options.attribute_config = AttributeConfig::new(attribute_priorities);
+    }
+
+    let archive_options = &mut generation_options.archive_options;
+    if given("include_archived") {
+        archive_options.include_archived = args.include_archived;
+    }
+    if given("archive_provider") {
+        archive_options.provider = match args.archive_provider {
+            ArchiveProvider::Wayback => generator::ArchiveProvider::WaybackMachine,
+            ArchiveProvider::ArchiveToday => generator::ArchiveProvider::ArchiveToday,
+        };
+    }
+    if given("url_status") {
+        archive_options.url_status = match args.url_status {
+            UrlStatus::Live => attribute::UrlStatus::Live,
+            UrlStatus::Dead => attribute::UrlStatus::Dead,
+            UrlStatus::Unfit => attribute::UrlStatus::Unfit,
+            UrlStatus::Usurped => attribute::UrlStatus::Usurped,
+        };
+    }
 
-    let archive_options = ArchiveOptions::default();
+    generation_options.drop_implausible_dates |= args.drop_implausible_dates;
+    generation_options.expand_short_urls |= args.expand_short_urls;
+    generation_options.extract_dateline |= args.extract_dateline;
+    generation_options.extract_copyright_publisher |= args.extract_copyright_publisher;
+    generation_options.derive_site_from_host |= args.derive_site_from_host;
+    generation_options.include_content_hash |= args.include_content_hash;
+    generation_options.check_doi_updates |= args.check_retractions;
+    generation_options.strict |= args.strict;
+    generation_options.request_json_ld |= args.request_json_ld;
+    if given("concurrency") {
+        generation_options.request_options.batch_concurrency = args.concurrency;
+    }
 
-    let generation_options = GenerationOptions {
-        attribute_config,
-        translation_options,
-        archive_options
+    let format_options = FormatOptions {
+        relative_dates: args.relative_dates,
+        max_title_chars: args.max_title_chars,
+        ..Default::default()
     };
 
-    let reference = generate(&query, &generation_options).unwrap();
-
-    let output = match args.format {
-        CitationFormat::Wiki => reference.wiki(),
-        CitationFormat::Bibtex => reference.bibtex(),
+    let Some(urls_file) = args.urls_file else {
+        let result = generate_all(&args.url.unwrap(), &generation_options).unwrap();
+        print_warnings(&result.warnings);
+        println!("{}", result.reference.format_with(args.format, &format_options));
+        return;
     };
 
-    println!("{}", output);
+    // Failures are reported without stopping the generation of the remaining
+    // references, which are printed as one document, e.g. a single JSON array.
+    let contents = fs::read_to_string(&urls_file).unwrap();
+    let urls: Vec<&str> = contents.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    let references: Vec<Reference> = generate_batch_all(&urls, &generation_options)
+        .into_iter()
+        .zip(&urls)
+        .filter_map(|(result, url)| match result {
+            Ok(result) => {
+                print_warnings(&result.warnings);
+                Some(result.reference)
+            }
+            Err(error) => {
+                eprintln!("Failed to generate a reference to {url}: {error}");
+                None
+            }
+        })
+        .collect();
+
+    println!("{}", format_references(&references, args.format, &format_options));
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+//! Tests of the output of the CLI binary, run against pages served locally.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::thread;
+
+use serde_json::Value;
+
+/// Serves the page at every path of a local server, returning its base URL.
+fn serve(page: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+                page.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    format!("http://{address}")
+}
+
+/// Config file disabling archived snapshots, so that no requests leave the machine.
+fn offline_config() -> &'static PathBuf {
+    static CONFIG: OnceLock<PathBuf> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("offline.toml");
+        fs::write(&path, "[archive_options]\ninclude_archived = false\n").unwrap();
+        path
+    })
+}
+
+/// Runs the CLI using only Open Graph metadata and parses its output as JSON.
+fn run_json(args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_url2ref-cli"))
+        .args(["--metadata-priority", "opengraph", "--config"])
+        .arg(offline_config())
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).expect("Output is not valid JSON")
+}
+
+const ARTICLE: &str = r#"<html><head>
+    <meta property="og:type" content="article">
+    <meta property="og:title" content="Harbour expansion approved">
+    <meta property="og:site_name" content="Coastal Times">
+    <meta property="article:published_time" content="2024-10-01T08:30:00+00:00">
+    </head><body><p>The council approved the expansion.</p></body></html>"#;
+
+#[test]
+fn csl_json_is_a_bibliography() {
+    let url = format!("{}/news/harbour", serve(ARTICLE));
+    let csl = run_json(&["--url", &url, "--format", "csl-json"]);
+
+    let items = csl.as_array().expect("CSL-JSON bibliographies are arrays");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["title"], "Harbour expansion approved");
+    assert_eq!(items[0]["URL"], url);
+}
+
+#[test]
+fn urls_file_gives_one_json_array() {
+    let base = serve(ARTICLE);
+    let urls_file = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("urls.txt");
+    fs::write(&urls_file, format!("{base}/news/harbour\n\n{base}/news/ferry\n")).unwrap();
+    let urls_file = urls_file.to_str().unwrap();
+
+    let csl = run_json(&["--urls-file", urls_file, "--format", "csl-json"]);
+    let ids: Vec<&str> = csl.as_array().unwrap().iter().filter_map(|item| item["id"].as_str()).collect();
+    assert_eq!(ids, ["url2ref-1", "url2ref-2"]);
+
+    let json = run_json(&["--urls-file", urls_file, "--format", "json"]);
+    assert_eq!(json.as_array().map(Vec::len), Some(2));
+    assert_eq!(json[1]["type"], "NewsArticle");
+}
@@ -0,0 +1,66 @@
+//! Support for extracting metadata from the [AMP] version of a web page.
+//!
+//! AMP pages, advertised using `<link rel="amphtml">`, frequently carry
+//! cleaner and more complete structured data than the page they mirror.
+//!
+//! [AMP]: https://amp.dev/
+
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::attribute::AttributeType;
+use crate::parser::{AttributeCollection, ParseInfo};
+use crate::GenerationOptions;
+
+/// Attributes whose absence makes a page count as metadata-poor.
+const CORE_ATTRIBUTES: &[AttributeType] = &[
+    AttributeType::Title,
+    AttributeType::Author,
+    AttributeType::Date,
+];
+
+/// Whether any of the core attributes are missing from the collection.
+pub fn is_metadata_poor(attributes: &AttributeCollection) -> bool {
+    CORE_ATTRIBUTES
+        .iter()
+        .any(|attribute_type| attributes.get(*attribute_type).is_none())
+}
+
+/// Finds the absolute URL of the AMP version of the page, if advertised.
+pub fn find_amp_url(document: &Html, base: Option<&str>) -> Option<String> {
+    let selector = Selector::parse("link[rel~=\"amphtml\"][href]").unwrap();
+    let href = document.select(&selector).next()?.value().attr("href")?;
+
+    let url = match base.and_then(|b| Url::parse(b).ok()) {
+        Some(base) => base.join(href),
+        None => Url::parse(href),
+    };
+    url.ok().map(String::from)
+}
+
+/// Fetches the AMP version of the page and parses its attributes.
+/// The URL of the AMP page itself is left out, as the canonical
+/// URL should always be cited.
+pub fn fetch_attributes(parse_info: &ParseInfo, options: &GenerationOptions) -> Option<AttributeCollection> {
+    let amp_url = find_amp_url(&parse_info.document, parse_info.url)?;
+    let amp_info = ParseInfo::from_url(&amp_url, options).ok()?;
+
+    let mut attributes = AttributeCollection::initialize(&options.attribute_config, &amp_info);
+    attributes.attributes.remove(&AttributeType::Url);
+    Some(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::find_amp_url;
+
+    #[test]
+    fn relative_amp_url() {
+        let html = r#"<html><head><link rel="amphtml" href="/amp/story"></head></html>"#;
+
+        let result = find_amp_url(&Html::parse_document(html), Some("https://example.com/story"));
+        assert_eq!(result, Some("https://example.com/amp/story".to_string()));
+    }
+}
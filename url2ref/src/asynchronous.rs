@@ -0,0 +1,98 @@
+//! Asynchronous variants of the generation functions, for use in async web
+//! services. Generation runs on the blocking thread pool of Tokio, so that
+//! it does not hold up the async workers. Requests are made using the client
+//! of [`crate::generator::RequestOptions::http_client`] as for the sync
+//! functions, and the independent lookups (DOI and Zotero, archive and
+//! translation) are made concurrently in either case.
+//!
+//! Only the generation entry points are async. There is no async variant
+//! of `ParseInfo::from_url`, as the parsed document of the page is
+//! neither `Send` nor owned, so it cannot be returned from the thread pool.
+
+use std::panic;
+
+use tokio::task;
+
+use crate::generator::{self, FullResult, ReferenceGenerationError};
+use crate::{GenerationOptions, Reference};
+
+type Result<T> = std::result::Result<T, ReferenceGenerationError>;
+
+/// Runs the generation on the blocking thread pool, resuming its panics.
+async fn run_blocking<T, F>(generate: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    match task::spawn_blocking(generate).await {
+        Ok(result) => result,
+        Err(error) => panic::resume_unwind(error.into_panic()),
+    }
+}
+
+/// Asynchronous variant of [`crate::generate`].
+pub async fn generate_async(url: &str, options: &GenerationOptions) -> Result<Reference> {
+    let (url, options) = (url.to_string(), options.clone());
+    run_blocking(move || generator::from_url(&url, &options)).await
+}
+
+/// Asynchronous variant of [`crate::generate_all`].
+pub async fn generate_all_async(url: &str, options: &GenerationOptions) -> Result<FullResult> {
+    let (url, options) = (url.to_string(), options.clone());
+    run_blocking(move || generator::from_url_all(&url, &options)).await
+}
+
+/// Asynchronous variant of [`crate::generate_batch`].
+pub async fn generate_batch_async(urls: &[&str], options: &GenerationOptions) -> Vec<Result<Reference>> {
+    let urls: Vec<String> = urls.iter().map(|url| url.to_string()).collect();
+    let options = options.clone();
+    run_blocking(move || {
+        let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+        crate::generate_batch(&urls, &options)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use tokio::runtime::Builder;
+
+    use super::{generate_all_async, generate_async};
+    use crate::generator::{ArchiveOptions, RequestOptions};
+    use crate::mock_client::MockClient;
+    use crate::GenerationOptions;
+
+    #[test]
+    fn generations_do_not_block_runtime() {
+        let delay = Duration::from_millis(300);
+        let client = Arc::new(MockClient::new(&[
+            ("https://harbourpost.example.dk/", r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#),
+        ]).with_delay(delay));
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        // A single-threaded runtime only finishes both generations in
+        // the time of one if neither holds up its only worker.
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let started = Instant::now();
+        let (first, second) = runtime.block_on(async {
+            let first = tokio::spawn({
+                let options = options.clone();
+                async move { generate_async("https://harbourpost.example.dk/news/harbour", &options).await }
+            });
+            let second = generate_all_async("https://harbourpost.example.dk/news/ferry", &options).await;
+            (first.await.unwrap(), second)
+        });
+
+        assert!(started.elapsed() < delay * 2, "Took {:?}", started.elapsed());
+        assert_eq!(first.unwrap().title(), Some("Harbour expansion approved"));
+        assert_eq!(second.unwrap().reference.title(), Some("Harbour expansion approved"));
+        assert_eq!(client.requested().len(), 2);
+    }
+}
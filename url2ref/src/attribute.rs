@@ -1,62 +1,198 @@
 //! Definitions for attributes and the types used for mapping them to
 //! their corresponding keys in different metadata formats.
 
-use chrono::{NaiveDate, DateTime, Utc};
+use std::cmp::Ordering;
+
+use chrono::{Datelike, NaiveDate, DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
+use url::Url;
 
 /// Types of attributes contained in a [`crate::reference::Reference`].
 /// Allows for mapping to specific keys which denote the same
 /// attribute types in various metadata formats.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, EnumIter, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, EnumIter, Debug, Serialize, Deserialize)]
 pub enum AttributeType {
    Title,
    Author,
+   Affiliations,
    Locale,
    Language,
    Site,
+   Section,
    Date,
+   OriginalDate,
    ArchiveDate,
+   AccessDate,
    Url,
    ArchiveUrl,
    Type,
    Journal,
    Publisher,
    Institution,
-   Volume
+   Volume,
+   Issue,
+   Pages,
+   Duration,
+   Series,
+   Place,
+   ImageCredit
 }
 
 /// Wrapper for the internal representation for attributes
 /// used in a [`crate::reference::Reference`].
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Serialized as e.g. `{"type": "title", "value": "..."}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum Attribute {
     Title(String),
     TranslatedTitle(Translation),
     Authors(Vec<Author>),
+    /// Institutions the authors are affiliated with, e.g. universities
+    Affiliations(Vec<Affiliation>),
     Date(Date),
+    OriginalDate(Date),
     ArchiveDate(Date),
+    /// Date on which the cited page was accessed
+    AccessDate(Date),
     Language(String),
     Locale(String),
     Site(String),
+    TranslatedSite(Translation),
+    /// Section of the site, e.g. a newspaper department
+    Section(String),
     Url(String),
     ArchiveUrl(String),
     Type(String),
     Journal(String),
     Publisher(String),
     Institution(String),
-    Volume(String)
+    Volume(String),
+    Issue(String),
+    /// Page range, e.g. `635-641`
+    Pages(String),
+    PageCount(u32),
+    /// ISO 8601 duration, e.g. `PT4M13S`
+    Duration(String),
+    /// Series the work is part of, e.g. the show of a podcast episode
+    Series(String),
+    /// Place the work was reported from, e.g. the dateline of a news article
+    Place(String),
+    /// Caption or credit of the main image, e.g. `Photo: Jane Doe/Reuters`
+    ImageCredit(String),
+    /// Hexadecimal SHA-256 hash of the HTML of the page as fetched
+    ContentHash(String),
+    /// Status of the cited URL, given along with its archived copy
+    UrlStatus(UrlStatus),
+    /// Status of the work given by a notice updating it, e.g. `Retracted`
+    Status(String),
+}
+
+/// Schemes of URLs which may appear in citations.
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https"];
+
+impl Attribute {
+    /// Whether the attribute may appear in a citation. URLs and archive
+    /// URLs with a scheme other than HTTP(S), e.g. `javascript:` or `data:`
+    /// URLs from malformed metadata, may not. URLs lacking a scheme are
+    /// allowed, as some sites leave it out.
+    pub fn is_allowed(&self) -> bool {
+        match self {
+            Attribute::Url(url) | Attribute::ArchiveUrl(url) => match Url::parse(url.trim()) {
+                Ok(url) => ALLOWED_URL_SCHEMES.contains(&url.scheme()),
+                Err(_) => true,
+            },
+            _ => true,
+        }
+    }
+
+    /// Returns the type of the attribute. Translations, page counts, content
+    /// hashes and statuses, which are not parsed from metadata, have no type.
+    pub fn attribute_type(&self) -> Option<AttributeType> {
+        let attribute_type = match self {
+            Attribute::Title(_)        => AttributeType::Title,
+            Attribute::Authors(_)      => AttributeType::Author,
+            Attribute::Affiliations(_) => AttributeType::Affiliations,
+            Attribute::Date(_)         => AttributeType::Date,
+            Attribute::OriginalDate(_) => AttributeType::OriginalDate,
+            Attribute::ArchiveDate(_)  => AttributeType::ArchiveDate,
+            Attribute::AccessDate(_)   => AttributeType::AccessDate,
+            Attribute::Language(_)     => AttributeType::Language,
+            Attribute::Locale(_)       => AttributeType::Locale,
+            Attribute::Site(_)         => AttributeType::Site,
+            Attribute::Section(_)      => AttributeType::Section,
+            Attribute::Url(_)          => AttributeType::Url,
+            Attribute::ArchiveUrl(_)   => AttributeType::ArchiveUrl,
+            Attribute::Type(_)         => AttributeType::Type,
+            Attribute::Journal(_)      => AttributeType::Journal,
+            Attribute::Publisher(_)    => AttributeType::Publisher,
+            Attribute::Institution(_)  => AttributeType::Institution,
+            Attribute::Volume(_)       => AttributeType::Volume,
+            Attribute::Issue(_)        => AttributeType::Issue,
+            Attribute::Pages(_)        => AttributeType::Pages,
+            Attribute::Duration(_)     => AttributeType::Duration,
+            Attribute::Series(_)       => AttributeType::Series,
+            Attribute::Place(_)        => AttributeType::Place,
+            Attribute::ImageCredit(_)  => AttributeType::ImageCredit,
+            Attribute::TranslatedTitle(_)
+            | Attribute::TranslatedSite(_)
+            | Attribute::PageCount(_)
+            | Attribute::ContentHash(_)
+            | Attribute::UrlStatus(_)
+            | Attribute::Status(_) => return None,
+        };
+        Some(attribute_type)
+    }
+}
+
+/// Status of a cited URL which has been archived, as given by the
+/// `url-status` parameter of the Wikipedia citation templates.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UrlStatus {
+    /// The page is still online, so the original URL is linked
+    #[default]
+    Live,
+    /// The page is offline, so the archived copy is linked
+    Dead,
+    /// The page is online but no longer fit to be linked, e.g. spam
+    Unfit,
+    /// The domain has been taken over by someone else
+    Usurped,
+}
+impl UrlStatus {
+    /// Name of the status, as used by the `url-status` parameter.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UrlStatus::Live => "live",
+            UrlStatus::Dead => "dead",
+            UrlStatus::Unfit => "unfit",
+            UrlStatus::Usurped => "usurped",
+        }
+    }
 }
 
 /// Author enum to make handling of authors in [`crate::citation`] easier.
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Serialized as e.g. `{"kind": "person", "name": "Jane Doe"}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
 pub enum Author {
     Person(String),
     Organization(String),
     Generic(String)
 }
 
+/// Institutions an author, given by their name as in [`Attribute::Authors`],
+/// is affiliated with.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Affiliation {
+    pub author: String,
+    pub institutions: Vec<String>,
+}
+
 /// Translation containing translated text as well as
 /// the language it's in as an ISO 639 language code.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Translation {
     pub text: String,
     pub language: String,
@@ -64,7 +200,10 @@ pub struct Translation {
 
 /// Date enum that can hold both fully complete
 /// DateTimes and partially complete dates.
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Serialized as an ISO 8601 string along with its precision,
+/// e.g. `{"precision": "year_month", "value": "2023-12"}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(into = "IsoDate", try_from = "IsoDate")]
 pub enum Date {
     DateTime(DateTime<Utc>),
     YearMonthDay(NaiveDate),
@@ -74,3 +213,98 @@ pub enum Date {
     },
     Year(i32),
 }
+
+impl Date {
+    /// Returns the year, which every date has.
+    pub fn year(&self) -> i32 {
+        self.sort_key().0
+    }
+
+    /// Returns the date in ISO 8601 format to its precision, e.g. `2024-03`.
+    /// The time of dates with one is left out.
+    pub fn to_iso_date(&self) -> String {
+        match self {
+            Date::DateTime(dt) => dt.format("%Y-%m-%d").to_string(),
+            date => IsoDate::from(date.clone()).value,
+        }
+    }
+
+    /// Components by which dates are ordered. Dates are ordered
+    /// chronologically, and a less precise date (e.g. a year) comes
+    /// before the more precise dates it covers.
+    fn sort_key(&self) -> (i32, Option<i32>, Option<u32>, Option<DateTime<Utc>>) {
+        match self {
+            Date::DateTime(dt) => (dt.year(), Some(dt.month() as i32), Some(dt.day()), Some(*dt)),
+            Date::YearMonthDay(nd) => (nd.year(), Some(nd.month() as i32), Some(nd.day()), None),
+            Date::YearMonth { year, month } => (*year, Some(*month), None, None),
+            Date::Year(year) => (*year, None, None, None),
+        }
+    }
+}
+
+impl Ord for Date {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Precision of a serialized [`Date`].
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DatePrecision {
+    DateTime,
+    Date,
+    YearMonth,
+    Year,
+}
+
+/// Serialized form of a [`Date`].
+#[derive(Serialize, Deserialize)]
+struct IsoDate {
+    precision: DatePrecision,
+    value: String,
+}
+
+impl From<Date> for IsoDate {
+    fn from(date: Date) -> Self {
+        let (precision, value) = match date {
+            Date::DateTime(dt) => (DatePrecision::DateTime, dt.to_rfc3339()),
+            Date::YearMonthDay(nd) => (DatePrecision::Date, nd.format("%Y-%m-%d").to_string()),
+            Date::YearMonth { year, month } => (DatePrecision::YearMonth, format!("{:04}-{:02}", year, month)),
+            Date::Year(year) => (DatePrecision::Year, format!("{:04}", year)),
+        };
+        Self { precision, value }
+    }
+}
+
+impl TryFrom<IsoDate> for Date {
+    type Error = String;
+
+    fn try_from(iso_date: IsoDate) -> Result<Self, Self::Error> {
+        let invalid = || format!("Invalid date: {}", iso_date.value);
+        let value = iso_date.value.as_str();
+
+        match iso_date.precision {
+            DatePrecision::DateTime => DateTime::parse_from_rfc3339(value)
+                .map(|dt| Date::DateTime(dt.with_timezone(&Utc)))
+                .map_err(|_| invalid()),
+            DatePrecision::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Date::YearMonthDay)
+                .map_err(|_| invalid()),
+            DatePrecision::YearMonth => {
+                let (year, month) = value.split_once('-').ok_or_else(invalid)?;
+                Ok(Date::YearMonth {
+                    year: year.parse().map_err(|_| invalid())?,
+                    month: month.parse().map_err(|_| invalid())?,
+                })
+            }
+            DatePrecision::Year => value.parse().map(Date::Year).map_err(|_| invalid()),
+        }
+    }
+}
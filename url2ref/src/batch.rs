@@ -0,0 +1,105 @@
+//! Generation of references to several pages at once, whose
+//! requests are made concurrently by a pool of threads.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Applies `f` to every input using at most `concurrency` threads, which
+/// take the next unprocessed input when done with one. The outputs are
+/// returned in the order of the inputs.
+pub fn map_concurrently<I, T, F>(inputs: &[I], concurrency: usize, f: F) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let outputs: Mutex<Vec<Option<T>>> = Mutex::new(inputs.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, inputs.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input) = inputs.get(index) else {
+                    break;
+                };
+                let output = f(input);
+                outputs.lock().unwrap()[index] = Some(output);
+            });
+        }
+    });
+
+    outputs
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|output| output.expect("Every input is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::map_concurrently;
+    use crate::generator::{ArchiveOptions, RequestOptions};
+    use crate::mock_server::{MockServer, Response};
+    use crate::{generate_batch, GenerationOptions, ReferenceGenerationError};
+
+    #[test]
+    fn outputs_in_input_order() {
+        let inputs: Vec<u64> = (0..20).collect();
+        // Later inputs finish first, so the outputs are completed out of order.
+        let outputs = map_concurrently(&inputs, 4, |input| {
+            thread::sleep(Duration::from_millis(20 - input));
+            input * 2
+        });
+        assert_eq!(outputs, (0..20).map(|input| input * 2).collect::<Vec<u64>>());
+        assert!(map_concurrently(&[] as &[u64], 4, |input| *input).is_empty());
+    }
+
+    #[test]
+    fn batch_generation() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let most_in_flight = Arc::new(AtomicUsize::new(0));
+        let (current, most) = (in_flight.clone(), most_in_flight.clone());
+        let server = MockServer::start(move |request| {
+            let count = current.fetch_add(1, Ordering::SeqCst) + 1;
+            most.fetch_max(count, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(100));
+            current.fetch_sub(1, Ordering::SeqCst);
+
+            match request.path.strip_prefix("/news/") {
+                Some("missing") => Response::status(404),
+                Some(slug) => Response::ok(format!(r#"<html><head><meta property="og:title" content="{slug}"></head></html>"#)),
+                None => Response::status(404),
+            }
+        });
+
+        let urls: Vec<String> = ["harbour", "bridge", "missing", "ferry", "tides", "storm"]
+            .iter()
+            .map(|slug| server.url(&format!("/news/{slug}")))
+            .collect();
+        let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { retries: 0, batch_concurrency: 3, ..Default::default() },
+            ..Default::default()
+        };
+
+        let results = generate_batch(&urls, &options);
+        let titles: Vec<Option<&str>> = results
+            .iter()
+            .map(|result| result.as_ref().ok().and_then(|reference| reference.title()))
+            .collect();
+        assert_eq!(titles, [Some("harbour"), Some("bridge"), None, Some("ferry"), Some("tides"), Some("storm")]);
+        assert!(matches!(results[2], Err(ReferenceGenerationError::HttpStatus { status: 404, .. })));
+
+        let most_in_flight = most_in_flight.load(Ordering::SeqCst);
+        assert!((2..=3).contains(&most_in_flight), "{most_in_flight} requests were made at once");
+    }
+}
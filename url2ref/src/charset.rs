@@ -0,0 +1,70 @@
+//! Decoding of fetched and saved pages into UTF-8, as older sites in
+//! particular still serve their pages in e.g. ISO-8859-1 or windows-1252.
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use regex::bytes::Regex;
+
+/// Bytes of the page searched for a `<meta>` declaration of its charset,
+/// which belongs at the beginning of the `<head>`.
+const PRESCAN_LENGTH: usize = 4096;
+
+/// Encoding given by the `charset` parameter of a `Content-Type`
+/// header, e.g. `text/html; charset=ISO-8859-1`.
+fn charset_parameter(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        let value = value.trim().trim_matches(['"', '\'']);
+        name.trim().eq_ignore_ascii_case("charset").then(|| Encoding::for_label(value.as_bytes()))?
+    })
+}
+
+/// Encoding declared by a `<meta charset>` or `<meta http-equiv="Content-Type">`
+/// tag at the beginning of the page.
+fn declared_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let re = Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?([A-Za-z0-9_:.-]+)"#).unwrap();
+    let prescan = &bytes[..bytes.len().min(PRESCAN_LENGTH)];
+    let label = re.captures(prescan)?.get(1)?.as_bytes();
+    Encoding::for_label(label)
+}
+
+/// Decodes the page into UTF-8 using the encoding given by its byte order
+/// mark, the `Content-Type` header or the `<meta>` declaration, in that
+/// order. Undeclared pages are decoded as UTF-8 if valid and as
+/// windows-1252 otherwise. Malformed sequences are replaced.
+pub fn decode(bytes: &[u8], content_type: Option<&str>) -> String {
+    let declared = content_type.and_then(charset_parameter).or_else(|| declared_charset(bytes));
+    let encoding = declared.unwrap_or_else(|| match std::str::from_utf8(bytes) {
+        Ok(_) => UTF_8,
+        Err(_) => WINDOWS_1252,
+    });
+
+    // A page declaring UTF-16 in its markup cannot be ASCII-based, so the
+    // declaration is itself wrong; the byte order mark takes care of UTF-16.
+    let encoding = encoding.output_encoding();
+    let (decoded, _, _) = encoding.decode(bytes);
+    decoded.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn encodings_respected() {
+        let html = b"<html><head><title>K\xf8benhavns havn f\xe5r ny \xe6re</title></head></html>";
+        let expected = "<html><head><title>Københavns havn får ny ære</title></head></html>";
+
+        // The header, the declaration and the fallback for invalid UTF-8 are all used.
+        assert_eq!(decode(html, Some("text/html; charset=ISO-8859-1")), expected);
+        assert_eq!(decode(html, Some("text/html")), expected);
+        let declared = b"<meta charset=\"windows-1252\"><title>\xc6bler</title>";
+        assert_eq!(decode(declared, None), "<meta charset=\"windows-1252\"><title>Æbler</title>");
+        let http_equiv = b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-1\"><p>\xd8</p>";
+        assert_eq!(decode(http_equiv, None), "<meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-1\"><p>Ø</p>");
+
+        // The header takes precedence over the declaration.
+        let utf8 = "<meta charset=\"iso-8859-1\"><title>Ærø</title>";
+        assert_eq!(decode(utf8.as_bytes(), Some("text/html; charset=utf-8")), utf8);
+        assert_eq!(decode(b"\xef\xbb\xbf<title>\xc3\x86r\xc3\xb8</title>", None), "<title>Ærø</title>");
+    }
+}
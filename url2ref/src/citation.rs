@@ -1,19 +1,124 @@
 //! Module providing functionality for building up citations
 //! in various formats using the Builder pattern.
 
-use crate::attribute::{Attribute, Author, Date};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::attribute::{Affiliation, Attribute, Author, Date};
+use crate::person_name::PersonName;
+use crate::reference::ReferenceKind;
 
 pub trait CitationBuilder {
     fn new() -> Self;
+    /// Sets the kind of the cited work, which may determine
+    /// e.g. the template or entry type used.
+    fn kind(self, kind: ReferenceKind) -> Self;
     fn try_add(self, attribute_option: &Option<Attribute>) -> Self;
     fn add(self, attribute: &Attribute) -> Self;
     fn build(self) -> String;
 }
 
-/// Builds a citation using the [{{cite web}} template] from the English Wikipedia
+/// Escapes characters which would otherwise end a template parameter
+/// (`|`) or the template itself (`}}`) in a MediaWiki template.
+fn escape_wiki(value: &str) -> String {
+    value
+        .replace("{{", "&#123;&#123;")
+        .replace("}}", "&#125;&#125;")
+        .replace('|', "{{!}}")
+}
+
+/// URLs containing pipes are percent-encoded rather than escaped
+/// using a template, so that they remain valid links.
+fn escape_wiki_url(url: &str) -> String {
+    escape_wiki(&url.replace('|', "%7C"))
+}
+
+/// Escapes braces, which would otherwise unbalance the field, double
+/// quotes, which would otherwise end a quoted BibTeX field, and the
+/// characters special to LaTeX, e.g. `%`, which would start a comment.
+/// Quotes are given as `\textquotedbl`, as some parsers (e.g. [`biblatex`])
+/// end the field at the `{"}` accepted by BibTeX itself.
+fn escape_bibtex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '{' | '}' | '%' | '&' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '"' => escaped.push_str("{\\textquotedbl}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// URL fields are verbatim, so the characters which would end the field or
+/// unbalance it are percent-encoded rather than escaped.
+fn escape_bibtex_url(url: &str) -> String {
+    url.replace('"', "%22").replace('{', "%7B").replace('}', "%7D")
+}
+
+/// Transliterations of letters which have no single ASCII counterpart,
+/// e.g. the Danish `ø`, or whose diacritics are dropped.
+const KEY_TRANSLITERATIONS: &[(char, &str)] = &[
+    ('æ', "ae"), ('ø', "oe"), ('å', "aa"), ('ä', "ae"), ('ö', "oe"), ('ü', "ue"), ('ß', "ss"),
+    ('þ', "th"), ('ð', "d"), ('ł', "l"), ('đ', "d"), ('œ', "oe"),
+    ('á', "a"), ('à', "a"), ('â', "a"), ('ã', "a"), ('ą', "a"), ('ç', "c"), ('č', "c"), ('ć', "c"),
+    ('é', "e"), ('è', "e"), ('ê', "e"), ('ë', "e"), ('ę', "e"), ('ě', "e"), ('í', "i"), ('ì', "i"),
+    ('î', "i"), ('ï', "i"), ('ñ', "n"), ('ń', "n"), ('ň', "n"), ('ó', "o"), ('ò', "o"), ('ô', "o"),
+    ('õ', "o"), ('ő', "o"), ('ř', "r"), ('š', "s"), ('ś', "s"), ('ú', "u"), ('ù', "u"), ('û', "u"),
+    ('ů', "u"), ('ű', "u"), ('ý', "y"), ('ž', "z"), ('ź', "z"), ('ż', "z"),
+];
+
+/// Converts a name to the lowercase ASCII letters and digits allowed in
+/// BibTeX keys, e.g. `Ørsted-Sørensen` to `oerstedsoerensen`.
+fn key_part(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .flat_map(|c| match KEY_TRANSLITERATIONS.iter().find(|(letter, _)| *letter == c) {
+            Some((_, ascii)) => ascii.chars().collect::<Vec<char>>(),
+            None => vec![c],
+        })
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+/// Derives the key of a BibTeX entry from the family name of the first
+/// author and the year, e.g. `astrup2023`. Works without authors are keyed
+/// by the site or the host of the URL instead, e.g. `information2023`,
+/// and works lacking all of these by the default key.
+pub fn bibtex_key(authors: &[Author], year: Option<i32>, site: Option<&str>, url: Option<&str>) -> String {
+    let author = authors.first().map(|author| match author {
+        Author::Person(name) => PersonName::parse(name).map_or_else(|| name.clone(), |name| name.family),
+        Author::Organization(name) | Author::Generic(name) => name.clone(),
+    });
+    let host = url
+        .and_then(|url| url::Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(|host| host.trim_start_matches("www.").to_string()));
+
+    let name = [author.as_deref(), site, host.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(key_part)
+        .find(|part| !part.is_empty());
+    match (name, year) {
+        (Some(name), Some(year)) => format!("{name}{year}"),
+        (Some(name), None) => name,
+        (None, _) => BibTeXCitation::DEFAULT_KEY.to_string(),
+    }
+}
+
+/// Builds a citation using the [{{cite web}} template] from the English Wikipedia,
+/// the [{{cite AV media}} template] for videos or the [{{cite podcast}} template]
+/// for podcast episodes.
 ///
 /// [{{cite web}} template]: https://en.wikipedia.org/wiki/Template:Cite_web
+/// [{{cite AV media}} template]: https://en.wikipedia.org/wiki/Template:Cite_AV_media
+/// [{{cite podcast}} template]: https://en.wikipedia.org/wiki/Template:Cite_podcast
 pub struct WikiCitation {
+    template: &'static str,
     formatted_string: String,
 }
 impl WikiCitation {
@@ -28,17 +133,15 @@ impl WikiCitation {
             // this must be done when there are multiple authors.
             let i = count.map(|v| v.to_string()).unwrap_or_default();
             // Trivial default case
-            let default = |a: &str| format!("|author{i}={}", a);
+            let default = |a: &str| format!("|author{i}={}", escape_wiki(a));
             match author {
-                Author::Person(str) => {
-                    let parts: Vec<&str> = str.split_whitespace().collect();
-                    match parts.as_slice() {
-                        [first_names @ .., last_name] => {
-                            let first_names = first_names.join(" ");
-                            format!("|last{i}={last_name} |first{i}={first_names}")
-                        }
-                        _ => default(str),
+                Author::Person(str) => match PersonName::parse(str) {
+                    Some(name) => {
+                        let first_names = escape_wiki(&name.given_with_suffix());
+                        let last_name = escape_wiki(&name.family);
+                        format!("|last{i}={last_name} |first{i}={first_names}")
                     }
+                    None => default(str),
                 },
                 Author::Organization(str) | Author::Generic(str) => default(str),
             }
@@ -63,15 +166,43 @@ impl WikiCitation {
         match date {
             Date::DateTime(dt) => format(dt.format(ymd_pattern).to_string()),
             Date::YearMonthDay(nd) => format(nd.format(ymd_pattern).to_string()),
-            Date::YearMonth { year, month } => format!("{}-{}", year, month),
+            Date::YearMonth { year, month } => format!("{}-{:02}", year, month),
             Date::Year(year) => format!("{}", year),
         }
     }
 
+    // Formats an ISO 8601 duration (e.g. PT1H2M3S) as h:mm:ss or m:ss.
+    // Durations which cannot be parsed are used verbatim.
+    fn handle_duration(&self, duration: &str) -> String {
+        let re = Regex::new(r"^PT(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)(?:\.\d+)?S)?$").unwrap();
+        let Some(captures) = re.captures(duration) else {
+            return duration.to_string();
+        };
+        let part = |i: usize| -> u64 {
+            captures.get(i).and_then(|m| m.as_str().parse().ok()).unwrap_or_default()
+        };
+
+        let total_minutes = part(1) * 60 + part(2);
+        let (hours, minutes, seconds) = (total_minutes / 60, total_minutes % 60, part(3));
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            format!("{minutes}:{seconds:02}")
+        }
+    }
 }
 impl CitationBuilder for WikiCitation {
     fn new() -> Self {
-        Self { formatted_string: String::from("") }
+        Self { template: "cite web", formatted_string: String::from("") }
+    }
+
+    fn kind(mut self, kind: ReferenceKind) -> Self {
+        self.template = match kind {
+            ReferenceKind::VideoObject => "cite AV media",
+            ReferenceKind::PodcastEpisode => "cite podcast",
+            _ => "cite web",
+        };
+        self
     }
 
     fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
@@ -83,17 +214,34 @@ impl CitationBuilder for WikiCitation {
 
     fn add(mut self,  attribute: &Attribute) -> Self {
         let result_option = match attribute {
-            Attribute::Title(val) => Some(format!("|title={}", val.to_string())),
-            Attribute::TranslatedTitle(trans) => Some(format!("|trans-title={} |language={}", trans.text, trans.language)),
+            Attribute::Title(val) => Some(format!("|title={}", escape_wiki(val))),
+            Attribute::TranslatedTitle(trans) => Some(format!("|trans-title={} |language={}", escape_wiki(&trans.text), trans.language)),
             Attribute::Authors(vals) => Some(self.handle_authors(vals)),
             Attribute::Date(val) => Some(format!("|date={}", self.handle_date(val))),
+            Attribute::OriginalDate(val) => Some(format!("|orig-date={}", self.handle_date(val))),
             Attribute::ArchiveDate(val) => Some(format!("|archive-date={}", self.handle_date(val))),
-            Attribute::Language(val) => Some(format!("|language={}", val.to_string())),
-            Attribute::Site(val) => Some(format!("|site={}", val.to_string())),
-            Attribute::Url(val) => Some(format!("|url={}", val.to_string())),
-            Attribute::ArchiveUrl(val) => Some(format!("|archive-url={}", val.to_string())),
-            Attribute::Journal(val) => Some(format!("|journal={}", val.to_string())),
-            Attribute::Publisher(val) => Some(format!("|publisher={}", val.to_string())),
+            Attribute::AccessDate(val) => Some(format!("|access-date={}", self.handle_date(val))),
+            Attribute::Language(val) => Some(format!("|language={}", escape_wiki(val))),
+            Attribute::Site(val) => Some(format!("|site={}", escape_wiki(val))),
+            // {{cite web}} has no parameter for it, so it is left as a note for editors.
+            Attribute::TranslatedSite(trans) => Some(format!("<!-- translated site: {} -->", escape_wiki(&trans.text))),
+            Attribute::Section(val) => Some(format!("|department={}", escape_wiki(val))),
+            Attribute::Place(val) => Some(format!("|place={}", escape_wiki(val))),
+            Attribute::Url(val) => Some(format!("|url={}", escape_wiki_url(val))),
+            Attribute::ArchiveUrl(val) => Some(format!("|archive-url={}", escape_wiki_url(val))),
+            // Required by the templates along with the archive URL.
+            Attribute::UrlStatus(val) => Some(format!("|url-status={}", val.name())),
+            Attribute::Journal(val) => Some(format!("|journal={}", escape_wiki(val))),
+            Attribute::Volume(val) => Some(format!("|volume={}", escape_wiki(val))),
+            // {{cite podcast}} calls the episode number simply the number.
+            Attribute::Issue(val) if self.template == "cite podcast" => Some(format!("|number={}", escape_wiki(val))),
+            Attribute::Issue(val) => Some(format!("|issue={}", escape_wiki(val))),
+            Attribute::Series(val) => Some(format!("|series={}", escape_wiki(val))),
+            Attribute::Pages(val) => Some(format!("|pages={}", escape_wiki(val))),
+            Attribute::Publisher(val) => Some(format!("|publisher={}", escape_wiki(val))),
+            Attribute::Duration(val) => Some(format!("|time={}", self.handle_duration(val))),
+            // Retractions are marked using e.g. {{Retracted}} next to the citation.
+            Attribute::Status(val) => Some(format!("<!-- status: {} -->", escape_wiki(val))),
             _ => None
         };
 
@@ -104,7 +252,7 @@ impl CitationBuilder for WikiCitation {
     }
 
     fn build(self) -> String {
-        format!("{{{{cite web{} }}}}", self.formatted_string)
+        format!("{{{{{}{} }}}}", self.template, self.formatted_string)
     }
 }
 
@@ -112,24 +260,55 @@ impl CitationBuilder for WikiCitation {
 ///
 /// [BibTeX entry template]: https://www.bibtex.org/Format/
 pub struct BibTeXCitation {
+    entry_type: &'static str,
+    key: Option<String>,
     formatted_string: String,
+    extra_fields: Vec<(String, String)>,
+    /// Attributes from which the key is derived, see [`bibtex_key`].
+    key_authors: Vec<Author>,
+    key_year: Option<i32>,
+    key_site: Option<String>,
+    key_url: Option<String>,
+    /// Notes, e.g. on affiliations, which share the single `note` field.
+    notes: Vec<String>,
 }
 impl BibTeXCitation {
+    /// Key of entries which lack the attributes to derive a key from.
+    const DEFAULT_KEY: &'static str = "url2ref";
+
+    /// Uses the given key for the entry instead of the one
+    /// derived from its author and year.
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Adds fields (e.g. `keywords` or `note`) to the entry after the
+    /// generated ones. Generated fields of the same name are replaced.
+    pub fn with_fields(mut self, fields: &[(&str, &str)]) -> Self {
+        self.extra_fields
+            .extend(fields.iter().map(|(name, value)| (name.to_string(), value.to_string())));
+        self
+    }
+
+
     fn handle_authors(&self, authors: &[Author]) -> String {
 
         // Creates a string representing an author in a style compatible with BibTeX markup
         fn stringify_author(author: &Author) -> String {
-            let default = |a: &str| format!("{{{}}}", a);
+            let default = |a: &str| format!("{{{}}}", escape_bibtex(a));
             match author {
-                Author::Person(str) => {
-                    let parts: Vec<&str> = str.split_whitespace().collect();
-                    match parts.as_slice() {
-                        [first_names @ .., last_name] => {
-                            let first_names = first_names.join(" ");
-                            format!("{last_name}, {first_names}")
+                // BibTeX names are given as `von Last, Jr, First`
+                Author::Person(str) => match PersonName::parse(str) {
+                    Some(name) => {
+                        let first_names = escape_bibtex(&name.given);
+                        let last_name = escape_bibtex(&name.family);
+                        match &name.suffix {
+                            Some(suffix) => format!("{last_name}, {}, {first_names}", escape_bibtex(suffix)),
+                            None => format!("{last_name}, {first_names}"),
                         }
-                        _ => default(str),
                     }
+                    None => default(str),
                 },
                 Author::Organization(str) | Author::Generic(str) => default(str),
             }
@@ -144,6 +323,17 @@ impl BibTeXCitation {
         output
     }
 
+    // BibTeX has no field for affiliations, so they are given in a note,
+    // e.g. `Affiliations: Henry S. Frank (University of Pittsburgh)`.
+    fn handle_affiliations(&self, affiliations: &[Affiliation]) -> String {
+        let affiliation_list = affiliations
+            .iter()
+            .map(|affiliation| format!("{} ({})", affiliation.author, affiliation.institutions.join(" and ")))
+            .collect::<Vec<String>>()
+            .join("; ");
+        format!("Affiliations: {}", affiliation_list)
+    }
+
     fn handle_date(&self, date: &Date) -> String {
         let ymd_pattern = "%Y-%m-%d";
 
@@ -158,11 +348,42 @@ impl BibTeXCitation {
             Date::Year(year) => format!("year = \"{}\"", year),
         }
     }
+
+    // Formats a (possibly partial) date as an ISO 8601 date,
+    // which is how BibLaTeX expects date fields other than `date`.
+    fn format_iso_date(&self, date: &Date) -> String {
+        let ymd_pattern = "%Y-%m-%d";
+
+        match date {
+            Date::DateTime(dt) => dt.format(ymd_pattern).to_string(),
+            Date::YearMonthDay(nd) => nd.format(ymd_pattern).to_string(),
+            Date::YearMonth { year, month } => format!("{}-{:02}", year, month),
+            Date::Year(year) => format!("{}", year),
+        }
+    }
 }
 
 impl CitationBuilder for BibTeXCitation {
     fn new() -> Self {
-        Self { formatted_string: String::from("") }
+        Self {
+            entry_type: "misc",
+            key: None,
+            formatted_string: String::from(""),
+            extra_fields: Vec::new(),
+            key_authors: Vec::new(),
+            key_year: None,
+            key_site: None,
+            key_url: None,
+            notes: Vec::new(),
+        }
+    }
+
+    fn kind(mut self, kind: ReferenceKind) -> Self {
+        self.entry_type = match kind {
+            ReferenceKind::ScholarlyArticle => "article",
+            _ => "misc",
+        };
+        self
     }
 
     fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
@@ -173,11 +394,32 @@ impl CitationBuilder for BibTeXCitation {
     }
 
     fn add(mut self,  attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Authors(vals) => self.key_authors = vals.clone(),
+            Attribute::Date(val) => self.key_year = Some(val.year()),
+            Attribute::Site(val) => self.key_site = Some(val.clone()),
+            Attribute::Url(val) => self.key_url = Some(val.clone()),
+            Attribute::Affiliations(vals) => self.notes.push(self.handle_affiliations(vals)),
+            Attribute::ContentHash(val) => self.notes.push(format!("SHA-256 of the cited page: {val}")),
+            Attribute::Status(val) => self.notes.push(val.clone()),
+            _ => (),
+        }
+
         let result_option = match attribute {
-            Attribute::Title(val)    => Some(format!("title = \"{}\"", val.to_string())),
+            Attribute::Title(val)    => Some(format!("title = \"{}\"", escape_bibtex(val))),
             Attribute::Authors(vals) => Some(self.handle_authors(vals)),
             Attribute::Date(val)     => Some(self.handle_date(val)),
-            Attribute::Url(val)      => Some(format!("url = \\url{{{}}}", val.to_string())),
+            Attribute::OriginalDate(val) => Some(format!("origdate = \"{}\"", self.format_iso_date(val))),
+            Attribute::AccessDate(val) => Some(format!("urldate = \"{}\"", self.format_iso_date(val))),
+            Attribute::ArchiveUrl(val) => Some(format!("archiveurl = \"{}\"", escape_bibtex_url(val))),
+            Attribute::ArchiveDate(val) => Some(format!("archivedate = \"{}\"", self.format_iso_date(val))),
+            Attribute::Url(val)      => Some(format!("url = \"{}\"", escape_bibtex_url(val))),
+            Attribute::PageCount(val) => Some(format!("pagetotal = \"{}\"", val)),
+            Attribute::Journal(val)  => Some(format!("journal = \"{}\"", escape_bibtex(val))),
+            Attribute::Volume(val)   => Some(format!("volume = \"{}\"", escape_bibtex(val))),
+            Attribute::Issue(val)    => Some(format!("number = \"{}\"", escape_bibtex(val))),
+            Attribute::Pages(val)    => Some(format!("pages = \"{}\"", escape_bibtex(val))),
+            Attribute::Series(val)   => Some(format!("series = \"{}\"", escape_bibtex(val))),
             _ => None
         };
 
@@ -187,14 +429,736 @@ impl CitationBuilder for BibTeXCitation {
         self
     }
 
+    fn build(mut self) -> String {
+        if !self.notes.is_empty() {
+            let note = format!("note = \"{}\",\n", escape_bibtex(&self.notes.join(". ")));
+            self.formatted_string.push_str(&note);
+        }
+
+        let is_replaced = |line: &&str| {
+            self.extra_fields.iter().any(|(name, _)| line.starts_with(&format!("{name} = ")))
+        };
+        let mut fields: String = self
+            .formatted_string
+            .lines()
+            .filter(|line| !is_replaced(line))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        for (name, value) in &self.extra_fields {
+            fields.push_str(&format!("{} = \"{}\",\n", name, escape_bibtex(value)));
+        }
+
+        let key = self.key.clone().unwrap_or_else(|| {
+            bibtex_key(&self.key_authors, self.key_year, self.key_site.as_deref(), self.key_url.as_deref())
+        });
+        format!("@{}{{ {},\n{}}}", self.entry_type, key, fields)
+    }
+}
+
+/// Checks that BibTeX generated by [`BibTeXCitation`] parses back into a
+/// single entry with the key and fields it was generated with, so that
+/// escaping mistakes (e.g. unbalanced braces) are caught by the tests.
+#[cfg(test)]
+pub(crate) fn validate_bibtex(bibtex: &str) -> Result<(), String> {
+    let header = Regex::new(r"^@\w+\{ ([^,]*),").unwrap();
+    let field = Regex::new(r"(?m)^(\w+) = ").unwrap();
+    let key = header.captures(bibtex).ok_or("Missing entry header")?[1].to_string();
+    let field_names: Vec<&str> = field.captures_iter(bibtex).map(|captures| captures.get(1).unwrap().as_str()).collect();
+
+    let bibliography = biblatex::Bibliography::parse(bibtex).map_err(|error| error.to_string())?;
+    let entries: Vec<&biblatex::Entry> = bibliography.iter().collect();
+    let [entry] = entries.as_slice() else {
+        return Err(format!("Expected a single entry, found {}", entries.len()));
+    };
+    if entry.key != key {
+        return Err(format!("Expected key {key}, found {}", entry.key));
+    }
+    for name in &field_names {
+        if !entry.fields.contains_key(*name) {
+            return Err(format!("Field {name} is missing"));
+        }
+    }
+    if entry.fields.len() != field_names.len() {
+        return Err(format!("Expected {} fields, found {}", field_names.len(), entry.fields.len()));
+    }
+    Ok(())
+}
+
+/// Marker appended to truncated titles.
+const TRUNCATION_MARKER: char = '…';
+
+/// Shortens the title to at most `max_chars` characters including the
+/// truncation marker, cutting it at the last word boundary which fits,
+/// e.g. `Harbour expansion…`. Titles of a single overlong word are cut
+/// within it.
+pub(crate) fn truncate_title(title: &str, max_chars: usize) -> String {
+    if title.chars().count() <= max_chars {
+        return title.to_string();
+    }
+
+    let budget = max_chars.saturating_sub(1);
+    let prefix: String = title.chars().take(budget).collect();
+    let cut_at_word = title.chars().nth(budget).is_some_and(char::is_whitespace);
+    let kept = match prefix.rfind(char::is_whitespace) {
+        Some(boundary) if !cut_at_word => &prefix[..boundary],
+        _ => prefix.as_str(),
+    };
+
+    let kept = kept.trim_end_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '–' | '—'));
+    format!("{kept}{TRUNCATION_MARKER}")
+}
+
+/// Period within which dates are considered recent enough
+/// to be rendered relative to the present.
+const RELATIVE_DATE_PERIOD: Duration = Duration::days(7);
+
+/// Renders the time elapsed from the date until now, e.g. `3 days ago`,
+/// if the date is recent. Dates in the future are not relative.
+pub(crate) fn relative_date(date: &DateTime<Utc>, now: &DateTime<Utc>) -> Option<String> {
+    let elapsed = now.signed_duration_since(*date);
+    if elapsed < Duration::zero() || elapsed >= RELATIVE_DATE_PERIOD {
+        return None;
+    }
+
+    let plural = |count: i64, unit: &str| match count {
+        1 => format!("1 {unit} ago"),
+        _ => format!("{count} {unit}s ago"),
+    };
+    let relative = match elapsed {
+        elapsed if elapsed.num_minutes() < 1 => "just now".to_string(),
+        elapsed if elapsed.num_hours() < 1 => plural(elapsed.num_minutes(), "minute"),
+        elapsed if elapsed.num_days() < 1 => plural(elapsed.num_hours(), "hour"),
+        elapsed => plural(elapsed.num_days(), "day"),
+    };
+    Some(relative)
+}
+
+/// Builds a plain-text citation without markup, suitable for footnotes in
+/// word processors, in the form `Author, "Title," Site, Date, URL.`
+#[derive(Default)]
+pub struct PlainCitation {
+    authors: Option<String>,
+    title: Option<String>,
+    site: Option<String>,
+    date: Option<String>,
+    url: Option<String>,
+    /// Present time against which recent dates are rendered relatively.
+    now: Option<DateTime<Utc>>,
+}
+impl PlainCitation {
+    /// Renders recent dates relative to `now`, e.g. `3 days ago`,
+    /// rather than as absolute dates.
+    pub fn with_relative_dates(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    fn handle_date(&self, date: &Date) -> String {
+        match date {
+            Date::DateTime(dt) => self.now
+                .and_then(|now| relative_date(dt, &now))
+                .unwrap_or_else(|| long_date(date)),
+            date => long_date(date),
+        }
+    }
+}
+
+/// Lists the authors in running text, e.g. `Jane Doe, Erik Aalto, and Mary
+/// Beck`. Persons are named in the `First Last` order, even if given as
+/// `Last, First`.
+fn natural_author_list(authors: &[Author]) -> String {
+    let names: Vec<String> = authors
+        .iter()
+        .map(|author| match author {
+            Author::Person(str) => PersonName::parse(str).map_or_else(|| str.clone(), |name| name.full_name()),
+            Author::Organization(str) | Author::Generic(str) => str.clone(),
+        })
+        .collect();
+
+    match names.as_slice() {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [first, second] => format!("{first} and {second}"),
+        [rest @ .., last] => format!("{}, and {last}", rest.join(", ")),
+    }
+}
+
+/// Formats the date in words to its precision, e.g. `March 8, 2024`.
+fn long_date(date: &Date) -> String {
+    let mdy_pattern = "%B %-d, %Y";
+
+    match date {
+        Date::DateTime(dt) => dt.format(mdy_pattern).to_string(),
+        Date::YearMonthDay(nd) => nd.format(mdy_pattern).to_string(),
+        Date::YearMonth { year, month } => NaiveDate::from_ymd_opt(*year, *month as u32, 1)
+            .map(|nd| nd.format("%B %Y").to_string())
+            .unwrap_or_else(|| format!("{}-{}", year, month)),
+        Date::Year(year) => format!("{}", year),
+    }
+}
+
+impl CitationBuilder for PlainCitation {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn kind(self, _kind: ReferenceKind) -> Self {
+        self
+    }
+
+    fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
+        match attribute_option {
+            Some(attribute) => self.add(attribute),
+            None => self,
+        }
+    }
+
+    fn add(mut self, attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Title(val)    => self.title = Some(val.to_string()),
+            Attribute::Authors(vals) => self.authors = Some(natural_author_list(vals)),
+            Attribute::Date(val)     => self.date = Some(self.handle_date(val)),
+            Attribute::Site(val)     => self.site = Some(val.to_string()),
+            Attribute::Url(val)      => self.url = Some(val.to_string()),
+            _ => ()
+        };
+        self
+    }
+
     fn build(self) -> String {
-        format!("@misc{{ url2ref,\n{}}}", self.formatted_string)
+        // The title is quoted, with the following punctuation inside the quotes.
+        let parts = [
+            (self.authors, false),
+            (self.title, true),
+            (self.site, false),
+            (self.date, false),
+            (self.url, false),
+        ];
+        let parts: Vec<(String, bool)> = parts
+            .into_iter()
+            .filter_map(|(part, quoted)| part.filter(|p| !p.is_empty()).map(|p| (p, quoted)))
+            .collect();
+
+        let mut output = String::new();
+        for (i, (part, quoted)) in parts.iter().enumerate() {
+            let punctuation = if i + 1 == parts.len() { "." } else { "," };
+            if *quoted {
+                output.push_str(&format!("\"{part}{punctuation}\""));
+            } else {
+                output.push_str(&format!("{part}{punctuation}"));
+            }
+            if i + 1 < parts.len() {
+                output.push(' ');
+            }
+        }
+        output
+    }
+}
+
+/// Escapes characters which would otherwise be read as Markdown syntax,
+/// e.g. emphasis (`*`, `_`) or the brackets of a link.
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Builds a Markdown link to the work followed by its author and date, in
+/// the form `[Title](URL) — Author, Date`. Works without a URL are cited by
+/// their title as plain text.
+#[derive(Default)]
+pub struct MarkdownCitation {
+    authors: Option<String>,
+    title: Option<String>,
+    date: Option<String>,
+    url: Option<String>,
+}
+
+impl CitationBuilder for MarkdownCitation {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn kind(self, _kind: ReferenceKind) -> Self {
+        self
+    }
+
+    fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
+        match attribute_option {
+            Some(attribute) => self.add(attribute),
+            None => self,
+        }
+    }
+
+    fn add(mut self, attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Title(val)    => self.title = Some(escape_markdown(val)),
+            Attribute::Authors(vals) => self.authors = Some(escape_markdown(&natural_author_list(vals))),
+            Attribute::Date(val)     => self.date = Some(long_date(val)),
+            // Spaces and parentheses would end the link destination
+            Attribute::Url(val)      => self.url = Some(val.replace(' ', "%20").replace('(', "%28").replace(')', "%29")),
+            _ => ()
+        };
+        self
+    }
+
+    fn build(self) -> String {
+        let link = match (self.title, self.url) {
+            (Some(title), Some(url)) => Some(format!("[{title}]({url})")),
+            (None, Some(url)) => Some(format!("<{url}>")),
+            (title, None) => title,
+        };
+        let details: Vec<String> = [self.authors, self.date]
+            .into_iter()
+            .flatten()
+            .filter(|detail| !detail.is_empty())
+            .collect();
+
+        match (link, details.is_empty()) {
+            (Some(link), true) => link,
+            (Some(link), false) => format!("{link} — {}", details.join(", ")),
+            (None, _) => details.join(", "),
+        }
+    }
+}
+
+/// Builds a citation in the [Vancouver style] used in medicine, in the form
+/// `Author AB, Author CD. Title. Journal. Year;Volume(Issue):Pages.`
+///
+/// [Vancouver style]: https://www.ncbi.nlm.nih.gov/books/NBK7256/
+#[derive(Default)]
+pub struct VancouverCitation {
+    authors: Option<String>,
+    title: Option<String>,
+    container: Option<String>,
+    year: Option<i32>,
+    volume: Option<String>,
+    issue: Option<String>,
+    pages: Option<String>,
+    url: Option<String>,
+}
+impl VancouverCitation {
+    /// At most this many authors are listed, followed by "et al."
+    const MAX_AUTHORS: usize = 6;
+
+    fn handle_authors(&self, authors: &[Author]) -> String {
+
+        // Persons are listed by surname followed by their initials without periods.
+        fn stringify_author(author: &Author) -> String {
+            match author {
+                Author::Person(str) => match PersonName::parse(str) {
+                    Some(name) => match &name.suffix {
+                        Some(suffix) => format!("{} {} {suffix}", name.family, name.initials()),
+                        None => format!("{} {}", name.family, name.initials()),
+                    },
+                    None => str.clone(),
+                },
+                Author::Organization(str) | Author::Generic(str) => str.clone(),
+            }
+        }
+
+        let mut author_list: Vec<String> = authors
+            .iter()
+            .take(Self::MAX_AUTHORS)
+            .map(stringify_author)
+            .collect();
+        if authors.len() > Self::MAX_AUTHORS {
+            author_list.push("et al".to_string());
+        }
+        author_list.join(", ")
+    }
+
+    fn handle_year(&self, date: &Date) -> i32 {
+        match date {
+            Date::DateTime(dt) => dt.year(),
+            Date::YearMonthDay(nd) => nd.year(),
+            Date::YearMonth { year, .. } | Date::Year(year) => *year,
+        }
+    }
+}
+
+impl CitationBuilder for VancouverCitation {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn kind(self, _kind: ReferenceKind) -> Self {
+        self
+    }
+
+    fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
+        match attribute_option {
+            Some(attribute) => self.add(attribute),
+            None => self,
+        }
+    }
+
+    fn add(mut self, attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Title(val)    => self.title = Some(val.to_string()),
+            Attribute::Authors(vals) => self.authors = Some(self.handle_authors(vals)),
+            Attribute::Date(val)     => self.year = Some(self.handle_year(val)),
+            Attribute::Journal(val)  => self.container = Some(val.to_string()),
+            Attribute::Site(val)     => self.container = self.container.take().or(Some(val.to_string())),
+            Attribute::Volume(val)   => self.volume = Some(val.to_string()),
+            Attribute::Issue(val)    => self.issue = Some(val.to_string()),
+            Attribute::Pages(val)    => self.pages = Some(val.to_string()),
+            Attribute::Url(val)      => self.url = Some(val.to_string()),
+            _ => ()
+        };
+        self
+    }
+
+    fn build(self) -> String {
+        // Each element ends with a period, unless it already ends with punctuation.
+        fn element(text: &str) -> String {
+            match text.ends_with(['.', '?', '!']) {
+                true => text.to_string(),
+                false => format!("{text}."),
+            }
+        }
+
+        let mut elements = Vec::new();
+        elements.extend(self.authors.as_deref().map(element));
+        elements.extend(self.title.as_deref().map(element));
+        elements.extend(self.container.as_deref().map(element));
+
+        // Year;Volume(Issue):Pages
+        let mut publication = self.year.map(|year| year.to_string()).unwrap_or_default();
+        if let Some(volume) = &self.volume {
+            publication.push_str(&format!(";{volume}"));
+        }
+        if let Some(issue) = &self.issue {
+            publication.push_str(&format!("({issue})"));
+        }
+        if let Some(pages) = &self.pages {
+            publication.push_str(&format!(":{pages}"));
+        }
+        let publication = publication.trim_start_matches(';');
+        if !publication.is_empty() {
+            elements.push(element(publication));
+        }
+
+        if let Some(url) = &self.url {
+            elements.push(format!("Available from: {url}"));
+        }
+        elements.join(" ")
+    }
+}
+
+/// Builds a citation in the [Harvard style] as described by Cite Them Right,
+/// in the form `Author, A. (Year) 'Title', Site, Day Month. Available at:
+/// URL (Accessed: Day Month Year).` Works without authors are led by their title.
+///
+/// [Harvard style]: https://www.citethemrightonline.com/
+#[derive(Default)]
+pub struct HarvardCitation {
+    kind: Option<ReferenceKind>,
+    authors: Option<String>,
+    date: Option<Date>,
+    title: Option<String>,
+    container: Option<String>,
+    section: Option<String>,
+    volume: Option<String>,
+    issue: Option<String>,
+    pages: Option<String>,
+    url: Option<String>,
+    access_date: Option<String>,
+}
+impl HarvardCitation {
+    /// At most this many authors are listed, followed by "et al."
+    const MAX_AUTHORS: usize = 3;
+
+    fn handle_authors(&self, authors: &[Author]) -> String {
+
+        // Persons are listed by surname followed by their initials, e.g. `Dijkstra, A.-M.`
+        fn stringify_author(author: &Author) -> String {
+            match author {
+                Author::Person(str) => match PersonName::parse(str) {
+                    Some(name) => {
+                        let initials: String = name.given
+                            .split(|c: char| c.is_whitespace() || c == '.')
+                            .filter(|word| !word.is_empty())
+                            .map(|word| word
+                                .split('-')
+                                .filter_map(|part| part.chars().find(|c| c.is_alphabetic()))
+                                .map(|initial| format!("{}.", initial.to_uppercase()))
+                                .collect::<Vec<String>>()
+                                .join("-"))
+                            .collect();
+                        match &name.suffix {
+                            Some(suffix) => format!("{}, {initials} {suffix}", name.family),
+                            None => format!("{}, {initials}", name.family),
+                        }
+                    }
+                    None => str.clone(),
+                },
+                Author::Organization(str) | Author::Generic(str) => str.clone(),
+            }
+        }
+
+        let names: Vec<String> = authors.iter().map(stringify_author).collect();
+        match names.as_slice() {
+            [] => String::new(),
+            [name] => name.to_string(),
+            names if names.len() > Self::MAX_AUTHORS => format!("{} et al.", names[0]),
+            [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+        }
+    }
+
+    /// Day and month of publication, given for news articles along with the year.
+    fn handle_day_month(&self, date: &Date) -> Option<String> {
+        match date {
+            Date::DateTime(dt) => Some(dt.format("%-d %B").to_string()),
+            Date::YearMonthDay(nd) => Some(nd.format("%-d %B").to_string()),
+            Date::YearMonth { year, month } => NaiveDate::from_ymd_opt(*year, *month as u32, 1)
+                .map(|nd| nd.format("%B").to_string()),
+            Date::Year(_) => None,
+        }
+    }
+
+    fn handle_date(&self, date: &Date) -> String {
+        let dmy_pattern = "%-d %B %Y";
+
+        match date {
+            Date::DateTime(dt) => dt.format(dmy_pattern).to_string(),
+            Date::YearMonthDay(nd) => nd.format(dmy_pattern).to_string(),
+            Date::YearMonth { year, month } => NaiveDate::from_ymd_opt(*year, *month as u32, 1)
+                .map(|nd| nd.format("%B %Y").to_string())
+                .unwrap_or_else(|| format!("{}-{}", year, month)),
+            Date::Year(year) => format!("{}", year),
+        }
+    }
+}
+
+impl CitationBuilder for HarvardCitation {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn kind(mut self, kind: ReferenceKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
+        match attribute_option {
+            Some(attribute) => self.add(attribute),
+            None => self,
+        }
+    }
+
+    fn add(mut self, attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Title(val)      => self.title = Some(val.to_string()),
+            Attribute::Authors(vals)   => self.authors = Some(self.handle_authors(vals)),
+            Attribute::Date(val)       => self.date = Some(val.clone()),
+            Attribute::Journal(val)    => self.container = Some(val.to_string()),
+            Attribute::Site(val)       => self.container = self.container.take().or(Some(val.to_string())),
+            Attribute::Section(val)    => self.section = Some(val.to_string()),
+            Attribute::Volume(val)     => self.volume = Some(val.to_string()),
+            Attribute::Issue(val)      => self.issue = Some(val.to_string()),
+            Attribute::Pages(val)      => self.pages = Some(val.to_string()),
+            Attribute::Url(val)        => self.url = Some(val.to_string()),
+            Attribute::AccessDate(val) => self.access_date = Some(self.handle_date(val)),
+            _ => ()
+        };
+        self
+    }
+
+    fn build(self) -> String {
+        let year = self.date.as_ref().map_or("no date".to_string(), |date| date.year().to_string());
+        let day_month = match self.kind {
+            Some(ReferenceKind::NewsArticle) => self.date.as_ref().and_then(|date| self.handle_day_month(date)),
+            _ => None,
+        };
+
+        // The title is quoted when followed by the site or journal it appeared in.
+        let (lead, title) = match self.authors.filter(|authors| !authors.is_empty()) {
+            Some(authors) => (Some(authors), self.title),
+            None => (self.title, None),
+        };
+        let title = title.map(|title| match self.container {
+            Some(_) => format!("'{title}'"),
+            None => title,
+        });
+
+        let publication = match (self.volume, self.issue) {
+            (Some(volume), Some(issue)) => Some(format!("{volume}({issue})")),
+            (Some(volume), None) => Some(volume),
+            (None, Some(issue)) => Some(format!("no. {issue}")),
+            (None, None) => None,
+        };
+        let pages = self.pages.map(|pages| match pages.contains(['-', '–']) {
+            true => format!("pp. {pages}"),
+            false => format!("p. {pages}"),
+        });
+        let details: Vec<String> = [title, self.container, self.section, day_month, publication, pages]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let mut output = match lead {
+            Some(lead) => format!("{lead} ({year})"),
+            None => format!("({year})"),
+        };
+        if !details.is_empty() {
+            output.push_str(&format!(" {}", details.join(", ")));
+        }
+        output.push('.');
+
+        if let Some(url) = &self.url {
+            output.push_str(&format!(" Available at: {url}"));
+            if let Some(access_date) = &self.access_date {
+                output.push_str(&format!(" (Accessed: {access_date})"));
+            }
+            output.push('.');
+        }
+        output
+    }
+}
+
+/// Builds an item in [CSL-JSON], the format used by Zotero, Pandoc and other
+/// citation processors. The item is wrapped in an array, so that the output
+/// can be used as a bibliography file directly.
+///
+/// [CSL-JSON]: https://citeproc-js.readthedocs.io/en/latest/csl-json/markup.html
+pub struct CslCitation {
+    item: Map<String, Value>,
+    /// Names of the authors, in the order of the `author` list
+    author_names: Vec<String>,
+    affiliations: Vec<Affiliation>,
+}
+impl CslCitation {
+    /// Persons are split into their family and given names;
+    /// organizations are given as literal names.
+    fn handle_authors(&self, authors: &[Author]) -> Value {
+        fn name(author: &Author) -> Value {
+            match author {
+                Author::Person(str) => match PersonName::parse(str) {
+                    Some(PersonName { given, family, suffix: Some(suffix) }) => {
+                        json!({ "family": family, "given": given, "suffix": suffix })
+                    }
+                    Some(PersonName { given, family, suffix: None }) => json!({ "family": family, "given": given }),
+                    None => json!({ "literal": str }),
+                },
+                Author::Organization(str) | Author::Generic(str) => json!({ "literal": str }),
+            }
+        }
+
+        Value::Array(authors.iter().map(name).collect())
+    }
+
+    /// Dates are given as date parts, which are as precise as the date itself.
+    fn handle_date(&self, date: &Date) -> Value {
+        let date_parts = match date {
+            Date::DateTime(dt) => json!([dt.year(), dt.month(), dt.day()]),
+            Date::YearMonthDay(nd) => json!([nd.year(), nd.month(), nd.day()]),
+            Date::YearMonth { year, month } => json!([year, month]),
+            Date::Year(year) => json!([year]),
+        };
+        json!({ "date-parts": [date_parts] })
+    }
+
+    fn set(&mut self, key: &str, value: impl Into<Value>) {
+        self.item.insert(key.to_string(), value.into());
+    }
+}
+
+impl CitationBuilder for CslCitation {
+    fn new() -> Self {
+        let mut item = Map::new();
+        item.insert("id".to_string(), json!("url2ref"));
+        item.insert("type".to_string(), json!("webpage"));
+        Self { item, author_names: Vec::new(), affiliations: Vec::new() }
+    }
+
+    fn kind(mut self, kind: ReferenceKind) -> Self {
+        let csl_type = match kind {
+            ReferenceKind::NewsArticle => "article-newspaper",
+            ReferenceKind::ScholarlyArticle => "article-journal",
+            ReferenceKind::VideoObject => "motion_picture",
+            ReferenceKind::PodcastEpisode => "broadcast",
+            ReferenceKind::GenericReference => "webpage",
+        };
+        self.set("type", csl_type);
+        self
+    }
+
+    fn try_add(self, attribute_option: &Option<Attribute>) -> Self {
+        match attribute_option {
+            Some(attribute) => self.add(attribute),
+            None => self,
+        }
+    }
+
+    fn add(mut self, attribute: &Attribute) -> Self {
+        match attribute {
+            Attribute::Title(val)        => self.set("title", val.as_str()),
+            Attribute::Authors(vals) => {
+                self.set("author", self.handle_authors(vals));
+                self.author_names = vals
+                    .iter()
+                    .map(|author| match author {
+                        Author::Person(str) | Author::Organization(str) | Author::Generic(str) => str.clone(),
+                    })
+                    .collect();
+            }
+            Attribute::Affiliations(vals) => self.affiliations = vals.clone(),
+            Attribute::Date(val)         => self.set("issued", self.handle_date(val)),
+            Attribute::OriginalDate(val) => self.set("original-date", self.handle_date(val)),
+            Attribute::AccessDate(val)   => self.set("accessed", self.handle_date(val)),
+            Attribute::Language(val)     => self.set("language", val.as_str()),
+            Attribute::Journal(val)      => self.set("container-title", val.as_str()),
+            // The journal, if any, is the container rather than the site.
+            Attribute::Site(val) => {
+                self.item.entry("container-title").or_insert_with(|| json!(val));
+            }
+            Attribute::Section(val)      => self.set("section", val.as_str()),
+            Attribute::Series(val)       => self.set("collection-title", val.as_str()),
+            Attribute::Url(val)          => self.set("URL", val.as_str()),
+            Attribute::Publisher(val)    => self.set("publisher", val.as_str()),
+            Attribute::Volume(val)       => self.set("volume", val.as_str()),
+            // Episodes of broadcasts are numbered rather than issued.
+            Attribute::Issue(val) if self.item["type"] == "broadcast" => self.set("number", val.as_str()),
+            Attribute::Issue(val)        => self.set("issue", val.as_str()),
+            Attribute::Pages(val)        => self.set("page", val.as_str()),
+            Attribute::PageCount(val)    => self.set("number-of-pages", *val),
+            Attribute::Duration(val)     => self.set("dimensions", val.as_str()),
+            Attribute::ContentHash(val)  => self.set("note", format!("SHA-256 of the cited page: {val}")),
+            Attribute::Status(val)       => self.set("status", val.as_str()),
+            _ => ()
+        };
+        self
+    }
+
+    fn build(mut self) -> String {
+        // Affiliations are added to the names of the authors they belong to.
+        if let Some(Value::Array(names)) = self.item.get_mut("author") {
+            for (name, author_name) in names.iter_mut().zip(&self.author_names) {
+                if let Some(affiliation) = self.affiliations.iter().find(|affiliation| &affiliation.author == author_name) {
+                    name["affiliation"] = json!(affiliation.institutions.join("; "));
+                }
+            }
+        }
+
+        let items = Value::Array(vec![Value::Object(self.item)]);
+        serde_json::to_string_pretty(&items).expect("JSON values are always serializable")
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
+    use crate::attribute::{Translation, UrlStatus};
+    use crate::reference::Reference;
 
     #[test]
     fn wiki_citation_try_add() {
@@ -208,4 +1172,518 @@ mod tests {
 
         assert_eq!(wiki_citation, expected_result)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn original_date_rendered_separately() {
+        let date = Attribute::Date(Date::Year(2024));
+        let original_date = Attribute::OriginalDate(Date::YearMonth { year: 1998, month: 5 });
+
+        let wiki_citation = WikiCitation::new()
+            .add(&date)
+            .add(&original_date)
+            .build();
+        assert_eq!(wiki_citation, "{{cite web |date=2024 |orig-date=1998-05 }}");
+
+        let bibtex_citation = BibTeXCitation::new()
+            .add(&date)
+            .add(&original_date)
+            .build();
+        assert_eq!(bibtex_citation, "@misc{ url2ref,\nyear = \"2024\",\norigdate = \"1998-05\",\n}");
+        validate_bibtex(&bibtex_citation).unwrap();
+    }
+
+    #[test]
+    fn translated_site_rendered_as_note() {
+        let site = Attribute::Site("Коммерсантъ".to_string());
+        let translated_site = Attribute::TranslatedSite(Translation { text: "Kommersant".to_string(), language: "en".to_string() });
+
+        let wiki_citation = WikiCitation::new()
+            .add(&site)
+            .add(&translated_site)
+            .build();
+        assert_eq!(wiki_citation, "{{cite web |site=Коммерсантъ <!-- translated site: Kommersant --> }}");
+
+        let plain_citation = PlainCitation::new()
+            .add(&site)
+            .add(&translated_site)
+            .build();
+        assert_eq!(plain_citation, "Коммерсантъ.");
+    }
+
+    #[test]
+    fn wiki_citation_escaping() {
+        let title = Attribute::Title("Review | The best laptops of {{2024}}".to_string());
+        let url = Attribute::Url("https://example.com/search?q=a|b".to_string());
+
+        let wiki_citation = WikiCitation::new()
+            .add(&title)
+            .add(&url)
+            .build();
+        assert_eq!(
+            wiki_citation,
+            "{{cite web |title=Review {{!}} The best laptops of &#123;&#123;2024&#125;&#125; |url=https://example.com/search?q=a%7Cb }}"
+        );
+    }
+
+    #[test]
+    fn bibtex_citation_escaping() {
+        let title = Attribute::Title("The \"best\" laptops {of} 2024".to_string());
+        let authors = Attribute::Authors(vec![Author::Organization("Laptops {R} Us".to_string())]);
+
+        let bibtex_citation = BibTeXCitation::new()
+            .add(&title)
+            .add(&authors)
+            .build();
+        assert_eq!(
+            bibtex_citation,
+            "@misc{ laptopsrus,\ntitle = \"The {\\textquotedbl}best{\\textquotedbl} laptops \\{of\\} 2024\",\nauthor = \"{Laptops \\{R\\} Us}\",\n}"
+        );
+        validate_bibtex(&bibtex_citation).unwrap();
+    }
+
+    #[test]
+    fn person_names_split() {
+        let authors = Attribute::Authors(vec![
+            Author::Person("Ludwig van Beethoven".to_string()),
+            Author::Person("King, Jr., Martin Luther".to_string()),
+            Author::Person("Cher".to_string()),
+        ]);
+
+        let wiki_citation = WikiCitation::new().add(&authors).build();
+        assert_eq!(
+            wiki_citation,
+            "{{cite web |last1=van Beethoven |first1=Ludwig |last2=King |first2=Martin Luther Jr. |author3=Cher }}"
+        );
+
+        let bibtex_citation = BibTeXCitation::new().add(&authors).build();
+        assert_eq!(
+            bibtex_citation,
+            "@misc{ vanbeethoven,\nauthor = \"van Beethoven, Ludwig and King, Jr., Martin Luther and {Cher}\",\n}"
+        );
+        validate_bibtex(&bibtex_citation).unwrap();
+
+        let plain_citation = PlainCitation::new().add(&authors).build();
+        assert_eq!(plain_citation, "Ludwig van Beethoven, Martin Luther King Jr., and Cher.");
+    }
+
+    #[test]
+    fn video_citation() {
+        let title = Attribute::Title("Building a Tide Gauge from Scrap".to_string());
+        let duration = Attribute::Duration("PT4M13S".to_string());
+
+        let wiki_citation = WikiCitation::new()
+            .kind(ReferenceKind::VideoObject)
+            .add(&title)
+            .add(&duration)
+            .build();
+        assert_eq!(wiki_citation, "{{cite AV media |title=Building a Tide Gauge from Scrap |time=4:13 }}");
+    }
+
+    #[test]
+    fn podcast_citation() {
+        let title = Attribute::Title("The last lighthouse keepers".to_string());
+        let series = Attribute::Series("Tidewaters".to_string());
+        let episode = Attribute::Issue("42".to_string());
+
+        let wiki_citation = WikiCitation::new()
+            .kind(ReferenceKind::PodcastEpisode)
+            .add(&title)
+            .add(&series)
+            .add(&episode)
+            .build();
+        assert_eq!(wiki_citation, "{{cite podcast |title=The last lighthouse keepers |series=Tidewaters |number=42 }}");
+    }
+
+    #[test]
+    fn plain_citation_full() {
+        let title = Attribute::Title("Harbour expansion approved".to_string());
+        let authors = Attribute::Authors(vec![
+            Author::Person("Jane Doe".to_string()),
+            Author::Person("John Smith".to_string()),
+        ]);
+        let site = Attribute::Site("Coastal Times".to_string());
+        let date = Attribute::Date(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+        let url = Attribute::Url("https://example.com/news/harbour".to_string());
+
+        let plain_citation = PlainCitation::new()
+            .add(&title)
+            .add(&authors)
+            .add(&date)
+            .add(&site)
+            .add(&url)
+            .build();
+        assert_eq!(
+            plain_citation,
+            "Jane Doe and John Smith, \"Harbour expansion approved,\" Coastal Times, March 1, 2024, https://example.com/news/harbour."
+        );
+    }
+
+    #[test]
+    fn plain_citation_sparse() {
+        let title = Attribute::Title("Harbour expansion approved".to_string());
+        let date = Attribute::Date(Date::Year(2024));
+
+        let plain_citation = PlainCitation::new()
+            .add(&title)
+            .build();
+        assert_eq!(plain_citation, "\"Harbour expansion approved.\"");
+
+        let plain_citation = PlainCitation::new()
+            .add(&date)
+            .build();
+        assert_eq!(plain_citation, "2024.");
+    }
+
+    #[test]
+    fn plain_citation_relative_dates() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let recent = Attribute::Date(Date::DateTime(now - Duration::days(2)));
+        let old = Attribute::Date(Date::DateTime(now - Duration::days(30)));
+
+        let plain_citation = PlainCitation::new().with_relative_dates(now).add(&recent).build();
+        assert_eq!(plain_citation, "2 days ago.");
+
+        let plain_citation = PlainCitation::new().with_relative_dates(now).add(&old).build();
+        assert_eq!(plain_citation, "February 9, 2024.");
+
+        let plain_citation = PlainCitation::new().add(&recent).build();
+        assert_eq!(plain_citation, "March 8, 2024.");
+
+        assert_eq!(relative_date(&(now - Duration::minutes(5)), &now), Some("5 minutes ago".to_string()));
+        assert_eq!(relative_date(&(now - Duration::hours(1)), &now), Some("1 hour ago".to_string()));
+        assert_eq!(relative_date(&(now + Duration::days(1)), &now), None);
+    }
+
+    #[test]
+    fn titles_truncated_at_word_boundaries() {
+        assert_eq!(truncate_title("Harbour expansion approved", 26), "Harbour expansion approved");
+        assert_eq!(truncate_title("Harbour expansion approved", 25), "Harbour expansion…");
+        assert_eq!(truncate_title("Harbour expansion approved", 18), "Harbour expansion…");
+        assert_eq!(truncate_title("Storm: harbour closed", 10), "Storm…");
+        assert_eq!(truncate_title("Havneudvidelsesprojektet", 10), "Havneudvi…");
+    }
+
+    #[test]
+    fn vancouver_scholarly_article() {
+        let reference = Reference::ScholarlyArticle {
+            title: Some(Attribute::Title("The Structure of Ordinary Water".to_string())),
+            translated_title: None,
+            author: Some(Attribute::Authors(vec![
+                Author::Person("Henry S. Frank".to_string()),
+                Author::Person("Anne-Marie Dijkstra".to_string()),
+            ])),
+            affiliations: None,
+            date: Some(Attribute::Date(Date::YearMonth { year: 1970, month: 8 })),
+            original_date: None,
+            language: None,
+            url: None,
+            journal: Some(Attribute::Journal("Science".to_string())),
+            volume: Some(Attribute::Volume("169".to_string())),
+            issue: Some(Attribute::Issue("3946".to_string())),
+            pages: Some(Attribute::Pages("635-641".to_string())),
+            publisher: None,
+            status: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            content_hash: None,
+        };
+
+        assert_eq!(
+            reference.vancouver(),
+            "Frank HS, Dijkstra AM. The Structure of Ordinary Water. Science. 1970;169(3946):635-641."
+        );
+    }
+
+    #[test]
+    fn harvard_scholarly_article() {
+        let reference = Reference::ScholarlyArticle {
+            title: Some(Attribute::Title("The Structure of Ordinary Water".to_string())),
+            translated_title: None,
+            author: Some(Attribute::Authors(vec![
+                Author::Person("Henry S. Frank".to_string()),
+                Author::Person("Anne-Marie Dijkstra".to_string()),
+            ])),
+            affiliations: None,
+            date: Some(Attribute::Date(Date::YearMonth { year: 1970, month: 8 })),
+            original_date: None,
+            language: None,
+            url: None,
+            journal: Some(Attribute::Journal("Science".to_string())),
+            volume: Some(Attribute::Volume("169".to_string())),
+            issue: Some(Attribute::Issue("3946".to_string())),
+            pages: Some(Attribute::Pages("635-641".to_string())),
+            publisher: None,
+            status: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            content_hash: None,
+        };
+
+        assert_eq!(
+            reference.harvard(),
+            "Frank, H.S. and Dijkstra, A.-M. (1970) 'The Structure of Ordinary Water', Science, 169(3946), pp. 635-641."
+        );
+    }
+
+    #[test]
+    fn harvard_without_author() {
+        let harvard_citation = HarvardCitation::new()
+            .kind(ReferenceKind::NewsArticle)
+            .add(&Attribute::Title("Harbour expansion approved".to_string()))
+            .add(&Attribute::Site("Coastal Times".to_string()))
+            .add(&Attribute::Section("Business".to_string()))
+            .add(&Attribute::Url("https://example.com/news/harbour".to_string()))
+            .add(&Attribute::AccessDate(Date::YearMonth { year: 2024, month: 3 }))
+            .build();
+        assert_eq!(
+            harvard_citation,
+            "Harbour expansion approved (no date) Coastal Times, Business. \
+             Available at: https://example.com/news/harbour (Accessed: March 2024)."
+        );
+    }
+
+    #[test]
+    fn markdown_link() {
+        let markdown_citation = MarkdownCitation::new()
+            .add(&Attribute::Title("The [updated] *best* laptops of 2024".to_string()))
+            .add(&Attribute::Authors(vec![Author::Person("Doe, Jane".to_string()), Author::Organization("Laptops_R_Us".to_string())]))
+            .add(&Attribute::Date(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 8).unwrap())))
+            .add(&Attribute::Url("https://example.com/laptops_(2024)".to_string()))
+            .build();
+        assert_eq!(
+            markdown_citation,
+            "[The \\[updated\\] \\*best\\* laptops of 2024](https://example.com/laptops_%282024%29) — Jane Doe and Laptops\\_R\\_Us, March 8, 2024"
+        );
+    }
+
+    #[test]
+    fn markdown_without_url() {
+        let markdown_citation = MarkdownCitation::new()
+            .add(&Attribute::Title("Harbour expansion approved".to_string()))
+            .add(&Attribute::Date(Date::Year(2024)))
+            .build();
+        assert_eq!(markdown_citation, "Harbour expansion approved — 2024");
+
+        let markdown_citation = MarkdownCitation::new().add(&Attribute::Title("Harbour expansion approved".to_string())).build();
+        assert_eq!(markdown_citation, "Harbour expansion approved");
+    }
+
+    #[test]
+    fn affiliations_rendered() {
+        let authors = Attribute::Authors(vec![
+            Author::Person("Henry S. Frank".to_string()),
+            Author::Person("Anne-Marie Dijkstra".to_string()),
+        ]);
+        let affiliations = Attribute::Affiliations(vec![Affiliation {
+            author: "Anne-Marie Dijkstra".to_string(),
+            institutions: vec!["Delft University of Technology".to_string(), "Deltares".to_string()],
+        }]);
+
+        let csl: serde_json::Value = serde_json::from_str(&CslCitation::new().add(&authors).add(&affiliations).build()).unwrap();
+        assert_eq!(csl[0]["author"], serde_json::json!([
+            { "family": "Frank", "given": "Henry S." },
+            { "family": "Dijkstra", "given": "Anne-Marie", "affiliation": "Delft University of Technology; Deltares" }
+        ]));
+
+        let bibtex_citation = BibTeXCitation::new().add(&affiliations).build();
+        assert_eq!(
+            bibtex_citation,
+            "@misc{ url2ref,\nnote = \"Affiliations: Anne-Marie Dijkstra (Delft University of Technology and Deltares)\",\n}"
+        );
+    }
+
+    #[test]
+    fn content_hash_rendered_as_note() {
+        let content_hash = Attribute::ContentHash("8c38a69a7cdc891d".to_string());
+        let affiliations = Attribute::Affiliations(vec![Affiliation {
+            author: "Henry S. Frank".to_string(),
+            institutions: vec!["University of Pittsburgh".to_string()],
+        }]);
+
+        // Both notes share the single note field.
+        let bibtex_citation = BibTeXCitation::new().add(&affiliations).add(&content_hash).build();
+        assert_eq!(
+            bibtex_citation,
+            "@misc{ url2ref,\nnote = \"Affiliations: Henry S. Frank (University of Pittsburgh). SHA-256 of the cited page: 8c38a69a7cdc891d\",\n}"
+        );
+
+        let csl: serde_json::Value = serde_json::from_str(&CslCitation::new().add(&content_hash).build()).unwrap();
+        assert_eq!(csl[0]["note"], "SHA-256 of the cited page: 8c38a69a7cdc891d");
+        assert_eq!(WikiCitation::new().add(&content_hash).build(), "{{cite web }}");
+    }
+
+    #[test]
+    fn bibtex_entry_type() {
+        let journal = Attribute::Journal("Science".to_string());
+
+        let bibtex_citation = BibTeXCitation::new()
+            .kind(ReferenceKind::ScholarlyArticle)
+            .add(&journal)
+            .build();
+        assert_eq!(bibtex_citation, "@article{ url2ref,\njournal = \"Science\",\n}");
+        validate_bibtex(&bibtex_citation).unwrap();
+
+        let bibtex_citation = BibTeXCitation::new()
+            .kind(ReferenceKind::NewsArticle)
+            .build();
+        assert_eq!(bibtex_citation, "@misc{ url2ref,\n}");
+    }
+
+    #[test]
+    fn vancouver_et_al() {
+        let authors: Vec<Author> = ["Ann Aa", "Ben Bb", "Cid Cc", "Dan Dd", "Eva Ee", "Fay Ff", "Gus Gg"]
+            .iter()
+            .map(|name| Author::Person(name.to_string()))
+            .collect();
+
+        let vancouver_citation = VancouverCitation::new()
+            .add(&Attribute::Authors(authors))
+            .add(&Attribute::Title("Tides".to_string()))
+            .add(&Attribute::Date(Date::Year(2021)))
+            .build();
+        assert_eq!(vancouver_citation, "Aa A, Bb B, Cc C, Dd D, Ee E, Ff F, et al. Tides. 2021.");
+    }
+
+    #[test]
+    fn csl_json_news_article() {
+        let reference = Reference::NewsArticle {
+            title: Some(Attribute::Title("Harbour expansion approved".to_string())),
+            translated_title: None,
+            author: Some(Attribute::Authors(vec![
+                Author::Person("Jane van Doe".to_string()),
+                Author::Organization("Coastal Times Staff".to_string()),
+            ])),
+            date: Some(Attribute::Date(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()))),
+            original_date: None,
+            language: Some(Attribute::Language("en".to_string())),
+            site: Some(Attribute::Site("Coastal Times".to_string())),
+            translated_site: None,
+            section: None,
+            place: None,
+            url: Some(Attribute::Url("https://example.com/news/harbour".to_string())),
+            publisher: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: Some(Attribute::AccessDate(Date::YearMonth { year: 2024, month: 3 })),
+            page_count: None,
+            image_credit: None,
+            content_hash: None,
+        };
+
+        let csl: serde_json::Value = serde_json::from_str(&reference.csl_json()).unwrap();
+        assert_eq!(csl, serde_json::json!([{
+            "id": "url2ref",
+            "type": "article-newspaper",
+            "title": "Harbour expansion approved",
+            "author": [
+                { "family": "van Doe", "given": "Jane" },
+                { "literal": "Coastal Times Staff" }
+            ],
+            "issued": { "date-parts": [[2024, 3, 1]] },
+            "language": "en",
+            "container-title": "Coastal Times",
+            "URL": "https://example.com/news/harbour",
+            "accessed": { "date-parts": [[2024, 3]] }
+        }]));
+    }
+
+    #[test]
+    fn csl_json_scholarly_article() {
+        // As generated from the BibTeX entry of a DOI.
+        let reference = Reference::ScholarlyArticle {
+            title: Some(Attribute::Title("The Structure of Ordinary Water".to_string())),
+            translated_title: None,
+            author: Some(Attribute::Authors(vec![Author::Person("Henry S. Frank".to_string())])),
+            affiliations: None,
+            date: Some(Attribute::Date(Date::Year(1970))),
+            original_date: None,
+            language: None,
+            url: Some(Attribute::Url("https://doi.org/10.1126/science.169.3946.635".to_string())),
+            journal: Some(Attribute::Journal("Science".to_string())),
+            volume: Some(Attribute::Volume("169".to_string())),
+            issue: Some(Attribute::Issue("3946".to_string())),
+            pages: Some(Attribute::Pages("635-641".to_string())),
+            publisher: Some(Attribute::Publisher("American Association for the Advancement of Science".to_string())),
+            status: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            content_hash: None,
+        };
+
+        let csl: serde_json::Value = serde_json::from_str(&reference.csl_json()).unwrap();
+        assert_eq!(csl, serde_json::json!([{
+            "id": "url2ref",
+            "type": "article-journal",
+            "title": "The Structure of Ordinary Water",
+            "author": [{ "family": "Frank", "given": "Henry S." }],
+            "issued": { "date-parts": [[1970]] },
+            "URL": "https://doi.org/10.1126/science.169.3946.635",
+            "container-title": "Science",
+            "volume": "169",
+            "issue": "3946",
+            "page": "635-641",
+            "publisher": "American Association for the Advancement of Science"
+        }]));
+    }
+
+    fn archived_reference(archived: bool) -> Reference {
+        Reference::GenericReference {
+            title: Some(Attribute::Title("Harbour expansion approved".to_string())),
+            translated_title: None,
+            author: None,
+            date: None,
+            original_date: None,
+            language: None,
+            site: None,
+            translated_site: None,
+            section: None,
+            url: Some(Attribute::Url("https://example.com/news/harbour".to_string())),
+            archive_url: archived.then(|| {
+                Attribute::ArchiveUrl("http://web.archive.org/web/20240305101500/https://example.com/news/harbour".to_string())
+            }),
+            archive_date: archived.then(|| Attribute::ArchiveDate(Date::DateTime(Utc.with_ymd_and_hms(2024, 3, 5, 10, 15, 0).unwrap()))),
+            url_status: archived.then_some(Attribute::UrlStatus(UrlStatus::Dead)),
+            access_date: Some(Attribute::AccessDate(Date::YearMonth { year: 2024, month: 3 })),
+            page_count: None,
+            image_credit: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn archive_attributes_rendered() {
+        let reference = archived_reference(true);
+        assert_eq!(
+            reference.wiki(),
+            "{{cite web |title=Harbour expansion approved |url=https://example.com/news/harbour \
+             |archive-url=http://web.archive.org/web/20240305101500/https://example.com/news/harbour \
+             |archive-date=2024-03-05 |url-status=dead |access-date=2024-03 }}"
+        );
+        assert_eq!(
+            reference.bibtex(),
+            "@misc{ examplecom,\ntitle = \"Harbour expansion approved\",\nurl = \"https://example.com/news/harbour\",\n\
+             archiveurl = \"http://web.archive.org/web/20240305101500/https://example.com/news/harbour\",\n\
+             archivedate = \"2024-03-05\",\nurldate = \"2024-03\",\n}"
+        );
+    }
+
+    #[test]
+    fn archive_attributes_missing() {
+        let reference = archived_reference(false);
+        assert_eq!(
+            reference.wiki(),
+            "{{cite web |title=Harbour expansion approved |url=https://example.com/news/harbour |access-date=2024-03 }}"
+        );
+        assert_eq!(
+            reference.bibtex(),
+            "@misc{ examplecom,\ntitle = \"Harbour expansion approved\",\nurl = \"https://example.com/news/harbour\",\nurldate = \"2024-03\",\n}"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Extraction of the publisher from the copyright notice in the footer
+//! of a page, e.g. `© 2024 Example Media Ltd. All rights reserved.`, for
+//! pages whose metadata does not name it. As the notice is not marked
+//! up, the extraction is heuristic and opt-in.
+
+use regex::Regex;
+use scraper::{Html, Selector};
+
+/// Footers of the page, in order of preference.
+const FOOTER_SELECTORS: &[&str] = &["footer", "[role=\"contentinfo\"]", "#footer", ".footer"];
+
+/// Abbreviations ending the names of companies, whose period is kept.
+const COMPANY_ABBREVIATIONS: &[&str] = &["Ltd.", "Inc.", "Co.", "Corp.", "Bros.", "Pty.", "Plc."];
+
+/// Longest name considered a publisher rather than a sentence.
+const MAX_PUBLISHER_CHARS: usize = 80;
+
+/// Returns the lines of text of the footers of the page.
+fn footer_lines(document: &Html) -> Vec<String> {
+    FOOTER_SELECTORS
+        .iter()
+        .flat_map(|selector| {
+            let selector = Selector::parse(selector).unwrap();
+            document.select(&selector).flat_map(|footer| footer.text()).collect::<Vec<&str>>()
+        })
+        .flat_map(str::lines)
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Finds the holder named by the copyright notice in the line. The
+/// notice begins with `©`, `(c)` or `Copyright`, optionally followed
+/// by the year or years, and the name ends the line or is followed by
+/// `All rights reserved` or a separator.
+fn find_copyright_holder(line: &str) -> Option<String> {
+    let re = Regex::new(
+        r"(?i)(?:©|\(c\)|copyright)(?:\s*(?:©|\(c\)))?\s*(?:\d{4}(?:\s*[-–]\s*\d{4})?)?[\s,]*(?P<name>[^|•]+)",
+    )
+    .unwrap();
+    let rights_reserved = Regex::new(r"(?i)\s*all rights reserved.*$").unwrap();
+
+    let name = re.captures(line)?["name"].to_string();
+    let name = rights_reserved.replace(&name, "");
+    let mut name = name.trim().trim_end_matches([',', ';', ':', '-', '–', '—']).trim_end();
+    let is_abbreviation = COMPANY_ABBREVIATIONS.iter().any(|abbreviation| name.ends_with(abbreviation));
+    if !is_abbreviation {
+        name = name.trim_end_matches('.');
+    }
+
+    let starts_with_letter = name.chars().next().is_some_and(char::is_alphabetic);
+    (starts_with_letter && name.chars().count() <= MAX_PUBLISHER_CHARS).then(|| name.to_string())
+}
+
+/// Returns the publisher named by the copyright notice in the footer of
+/// the page, if it has one.
+pub fn find_publisher(document: &Html) -> Option<String> {
+    footer_lines(document).iter().find_map(|line| find_copyright_holder(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_copyright_holder;
+
+    #[test]
+    fn copyright_notices() {
+        #[rustfmt::skip]
+        let cases = [
+            ("© 2024 Example Media Ltd. All rights reserved.", "Example Media Ltd."),
+            ("Copyright © 2019–2024 Harbour Media Group A/S", "Harbour Media Group A/S"),
+            ("(c) 2023, Fjord Post.", "Fjord Post"),
+            ("Copyright 2024 Coastal Times | Privacy policy", "Coastal Times"),
+            ("©Nordic Press Inc.", "Nordic Press Inc."),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(find_copyright_holder(line), Some(expected.to_string()), "{line}");
+        }
+    }
+
+    #[test]
+    fn no_copyright_holder() {
+        assert_eq!(find_copyright_holder("Privacy policy"), None);
+        assert_eq!(find_copyright_holder("© 2024"), None);
+        assert_eq!(find_copyright_holder("© 2024 — All rights reserved"), None);
+    }
+}
@@ -1,36 +1,143 @@
 use curl::easy::{Easy, List};
+use std::fmt;
 use std::result;
+use std::sync::Mutex;
+use std::thread;
 use thiserror::Error;
 
+use crate::charset;
+use crate::generator::RequestOptions;
+
 type Result<T> = result::Result<T, CurlError>;
 
+/// Redirects followed before giving up, e.g. in case of a redirect loop.
+const MAX_REDIRECTIONS: u32 = 10;
+
+/// Header of requests sending JSON, see [`HttpClient::post_json`].
+const JSON_CONTENT_TYPE: &str = "Content-Type: application/json";
+
 #[derive(Error, Debug)]
 pub enum CurlError {
     #[error("Curl could not GET url")]
     GetError(#[from] curl::Error),
 
-    #[error("Curl response is not valid UTF8")]
-    Utf8Error(#[from] std::string::FromUtf8Error),
+    #[error("Server responded with HTTP status {0}")]
+    HttpStatus(u32),
+
+    /// Failure of an [`HttpClient`] other than [`CurlClient`].
+    #[error("Request failed: {0}")]
+    Transport(String),
 }
 
-pub fn get_html(url: &str) -> Result<String> {
-    get(url, None, false)
+impl CurlError {
+    /// Whether the request may succeed if sent again, i.e. it timed out,
+    /// the connection failed or the server responded with a 5xx status.
+    fn is_transient(&self) -> bool {
+        match self {
+            CurlError::GetError(error) => {
+                error.is_operation_timedout()
+                    || error.is_couldnt_connect()
+                    || error.is_send_error()
+                    || error.is_recv_error()
+                    || error.is_got_nothing()
+            }
+            CurlError::HttpStatus(status) => *status >= 500,
+            CurlError::Transport(_) => false,
+        }
+    }
 }
 
-pub fn get(url: &str, header_opt: Option<&str>, follow_location: bool) -> Result<String> {
-    let mut easy = Easy::new();
-    let mut buf = Vec::new();
+/// Fails on responses other than 2xx, so that e.g. error pages are not
+/// mistaken for the requested resource. Redirects count as failures
+/// unless they are followed. Non-HTTP transfers have no response code.
+fn check_status(easy: &mut Easy) -> Result<()> {
+    match easy.response_code()? {
+        0 | 200..=299 => Ok(()),
+        status => Err(CurlError::HttpStatus(status)),
+    }
+}
 
-    // Header determines output format
-    if let Some(header) = header_opt {
-        let mut header_list = List::new();
-        header_list.append(header)?;
-        easy.http_headers(header_list)?;
+/// Applies the timeouts and User-Agent of the options to the handle.
+fn configure(easy: &mut Easy, options: &RequestOptions) -> Result<()> {
+    easy.connect_timeout(options.connect_timeout)?;
+    easy.timeout(options.timeout)?;
+    if let Some(user_agent) = &options.user_agent {
+        easy.useragent(user_agent)?;
     }
+    Ok(())
+}
 
-    easy.follow_location(follow_location)?;
-    easy.url(url)?;
+/// Performs the request, retrying transient failures as many times as
+/// configured. The delay between attempts doubles after each attempt.
+fn with_retries<T>(options: &RequestOptions, mut request: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delay = options.retry_backoff;
+    let mut attempt = 0;
+    loop {
+        match request() {
+            Err(error) if attempt < options.retries && error.is_transient() => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Performs the HTTP requests made during reference generation. A client
+/// can be shared between generations using [`RequestOptions::http_client`],
+/// e.g. so that server applications reuse connections, or replaced by one
+/// using another HTTP library or a proxy. Each call is a single attempt;
+/// failures are retried as configured by the caller.
+pub trait HttpClient: fmt::Debug + Send + Sync {
+    /// Sends a GET request with the optional header, following redirects
+    /// if `follow_location` is set, and returns the response body.
+    fn get(&self, url: &str, header: Option<&str>, follow_location: bool, options: &RequestOptions) -> Result<String>;
+
+    /// Sends a GET request with all of the headers. Clients only sending
+    /// a single header fall back to [`HttpClient::get`] with the first one.
+    fn get_with_headers(&self, url: &str, headers: &[&str], follow_location: bool, options: &RequestOptions) -> Result<String> {
+        self.get(url, headers.first().copied(), follow_location, options)
+    }
 
+    /// Sends a POST request with the body and returns the response body.
+    fn post(&self, url: &str, body: &str, header: Option<&str>, options: &RequestOptions) -> Result<String>;
+
+    /// Sends a POST request with the JSON body and returns the response body.
+    fn post_json(&self, url: &str, body: &str, options: &RequestOptions) -> Result<String> {
+        self.post(url, body, Some(JSON_CONTENT_TYPE), options)
+    }
+
+    /// Follows the redirects of a URL and returns the response code and the
+    /// URL finally arrived at. Only the headers are requested if `head_only` is set.
+    fn resolve_redirects(&self, url: &str, head_only: bool, options: &RequestOptions) -> Result<(u32, String)>;
+}
+
+/// [`HttpClient`] using libcurl. Handles are kept after use, so that later
+/// requests made using the same client reuse their connections.
+#[derive(Debug, Default)]
+pub struct CurlClient {
+    handles: Mutex<Vec<Easy>>,
+}
+impl CurlClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Performs a transfer using an idle handle, or a new one if all are in use.
+    fn with_handle<T>(&self, transfer: impl FnOnce(&mut Easy) -> Result<T>) -> Result<T> {
+        let mut easy = self.handles.lock().unwrap().pop().unwrap_or_else(Easy::new);
+        easy.reset();
+        let result = transfer(&mut easy);
+        self.handles.lock().unwrap().push(easy);
+        result
+    }
+}
+
+/// Reads the response body of the transfer configured on the handle,
+/// decoded into UTF-8 according to its charset.
+fn perform(easy: &mut Easy) -> Result<String> {
+    let mut buf = Vec::new();
     {
         let mut transfer = easy.transfer();
         transfer.write_function(|data| {
@@ -39,7 +146,118 @@ pub fn get(url: &str, header_opt: Option<&str>, follow_location: bool) -> Result
         })?;
         transfer.perform()?;
     }
+    check_status(easy)?;
+
+    Ok(charset::decode(&buf, easy.content_type()?))
+}
+
+impl HttpClient for CurlClient {
+    fn get(&self, url: &str, header: Option<&str>, follow_location: bool, options: &RequestOptions) -> Result<String> {
+        let headers: Vec<&str> = header.into_iter().collect();
+        self.get_with_headers(url, &headers, follow_location, options)
+    }
+
+    fn get_with_headers(&self, url: &str, headers: &[&str], follow_location: bool, options: &RequestOptions) -> Result<String> {
+        self.with_handle(|easy| {
+            // Headers determine output format
+            if !headers.is_empty() {
+                let mut header_list = List::new();
+                for header in headers {
+                    header_list.append(header)?;
+                }
+                easy.http_headers(header_list)?;
+            }
+
+            configure(easy, options)?;
+            easy.follow_location(follow_location)?;
+            easy.url(url)?;
+            perform(easy)
+        })
+    }
+
+    fn post(&self, url: &str, body: &str, header: Option<&str>, options: &RequestOptions) -> Result<String> {
+        self.with_handle(|easy| {
+            if let Some(header) = header {
+                let mut header_list = List::new();
+                header_list.append(header)?;
+                easy.http_headers(header_list)?;
+            }
+
+            configure(easy, options)?;
+            easy.url(url)?;
+            easy.post(true)?;
+            easy.post_fields_copy(body.as_bytes())?;
+            perform(easy)
+        })
+    }
+
+    fn resolve_redirects(&self, url: &str, head_only: bool, options: &RequestOptions) -> Result<(u32, String)> {
+        self.with_handle(|easy| {
+            configure(easy, options)?;
+            easy.url(url)?;
+            easy.follow_location(true)?;
+            easy.max_redirections(MAX_REDIRECTIONS)?;
+            easy.nobody(head_only)?;
+
+            {
+                // The body of GET responses is discarded.
+                let mut transfer = easy.transfer();
+                transfer.write_function(|data| Ok(data.len()))?;
+                transfer.perform()?;
+            }
+
+            let response_code = easy.response_code()?;
+            let effective_url = easy.effective_url()?.unwrap_or(url).to_string();
+            Ok((response_code, effective_url))
+        })
+    }
+}
+
+/// Performs the request using the client of the options, or a new
+/// [`CurlClient`] if none is set, retrying as configured.
+fn with_client<T>(options: &RequestOptions, request: impl Fn(&dyn HttpClient) -> Result<T>) -> Result<T> {
+    let default_client = CurlClient::new();
+    let client: &dyn HttpClient = options.http_client.as_deref().unwrap_or(&default_client);
+    with_retries(options, || request(client))
+}
+
+/// Retrieves a web page, optionally requesting a specific
+/// language edition using the `Accept-Language` header.
+/// Redirects are followed.
+pub fn get_html(url: &str, language: Option<&str>, options: &RequestOptions) -> Result<String> {
+    let header = language.map(|language| format!("Accept-Language: {language}"));
+    get(url, header.as_deref(), true, options)
+}
+
+/// Media types accepted by [`get_html_or_json_ld`], preferring JSON-LD.
+const JSON_LD_ACCEPT: &str = "Accept: application/ld+json, text/html;q=0.9, */*;q=0.8";
+
+/// Retrieves a web page like [`get_html`], but asks for its JSON-LD
+/// representation using the `Accept` header. Servers not supporting
+/// content negotiation respond with the HTML page as usual.
+pub fn get_html_or_json_ld(url: &str, language: Option<&str>, options: &RequestOptions) -> Result<String> {
+    let language = language.map(|language| format!("Accept-Language: {language}"));
+    let headers: Vec<&str> = [Some(JSON_LD_ACCEPT), language.as_deref()].into_iter().flatten().collect();
+    with_client(options, |client| client.get_with_headers(url, &headers, true, options))
+}
+
+pub fn get(url: &str, header_opt: Option<&str>, follow_location: bool, options: &RequestOptions) -> Result<String> {
+    with_client(options, |client| client.get(url, header_opt, follow_location, options))
+}
+
+/// Sends a form-encoded POST request and returns the response body.
+pub fn post(url: &str, body: &str, header_opt: Option<&str>, options: &RequestOptions) -> Result<String> {
+    with_client(options, |client| client.post(url, body, header_opt, options))
+}
+
+/// Sends a POST request with the JSON body and returns the response body.
+pub fn post_json(url: &str, body: &str, options: &RequestOptions) -> Result<String> {
+    with_client(options, |client| client.post_json(url, body, options))
+}
 
-    let reponse_string = String::from_utf8(buf)?;
-    Ok(reponse_string)
+/// Follows the redirects of a URL and returns the response code
+/// and the URL finally arrived at. Only the headers are requested
+/// if `head_only` is set.
+pub fn resolve_redirects(url: &str, head_only: bool, options: &RequestOptions) -> Result<(u32, String)> {
+    with_client(options, |client| client.resolve_redirects(url, head_only, options))
 }
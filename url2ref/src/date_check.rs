@@ -0,0 +1,83 @@
+//! Detection of publication dates which are likely to be wrong, such as
+//! copyright years, `1970` epoch defaults and scheduled future dates.
+
+use chrono::{Datelike, NaiveDate};
+use thiserror::Error;
+
+use crate::attribute::Date;
+
+/// Publication dates before this year are implausible for web content.
+const EARLIEST_PLAUSIBLE_YEAR: i32 = 1990;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DateWarning {
+    #[error("Publication date {0} lies after the access date")]
+    InFuture(NaiveDate),
+
+    #[error("Publication date {0} predates the web")]
+    TooEarly(NaiveDate),
+}
+
+/// The first day covered by the date, e.g. January 1 for a year.
+fn first_day(date: &Date) -> Option<NaiveDate> {
+    match date {
+        Date::DateTime(datetime) => Some(datetime.date_naive()),
+        Date::YearMonthDay(naive_date) => Some(*naive_date),
+        Date::YearMonth { year, month } => NaiveDate::from_ymd_opt(*year, u32::try_from(*month).ok()?, 1),
+        Date::Year(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+    }
+}
+
+/// Checks whether the publication date is plausible given the date on
+/// which the page was accessed. A day of leeway is allowed for dates in
+/// the future, as the two may be given in different time zones.
+pub fn check_date(date: &Date, access_date: &Date) -> Option<DateWarning> {
+    let day = first_day(date)?;
+
+    if day.year() < EARLIEST_PLAUSIBLE_YEAR {
+        return Some(DateWarning::TooEarly(day));
+    }
+
+    let access_day = first_day(access_date)?;
+    (day > access_day.succ_opt()?).then_some(DateWarning::InFuture(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::{check_date, DateWarning};
+    use crate::attribute::Date;
+
+    fn access_date() -> Date {
+        Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap())
+    }
+
+    #[test]
+    fn future_date() {
+        let date = Date::YearMonthDay(NaiveDate::from_ymd_opt(2099, 1, 1).unwrap());
+        assert_eq!(
+            check_date(&date, &access_date()),
+            Some(DateWarning::InFuture(NaiveDate::from_ymd_opt(2099, 1, 1).unwrap()))
+        );
+
+        // The next day may already have begun in the time zone of the publisher.
+        let date = Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 6, 4).unwrap());
+        assert_eq!(check_date(&date, &access_date()), None);
+    }
+
+    #[test]
+    fn epoch_date() {
+        let date = Date::DateTime(Utc.timestamp_opt(0, 0).unwrap());
+        assert_eq!(
+            check_date(&date, &access_date()),
+            Some(DateWarning::TooEarly(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()))
+        );
+    }
+
+    #[test]
+    fn plausible_dates() {
+        assert_eq!(check_date(&Date::Year(2024), &access_date()), None);
+        assert_eq!(check_date(&Date::YearMonth { year: 1998, month: 5 }, &access_date()), None);
+    }
+}
@@ -0,0 +1,63 @@
+//! Extraction of the dateline with which wire-service articles begin,
+//! e.g. `WASHINGTON —` or `LONDON (Reuters) -`, giving the place the
+//! article was reported from. As articles are not marked up for this,
+//! the extraction is heuristic and opt-in.
+
+use regex::Regex;
+use scraper::{Html, Selector};
+
+use crate::title_case::normalize_title_case;
+
+/// Paragraphs of the article body, in order of preference.
+const BODY_SELECTORS: &[&str] = &["[itemprop=\"articleBody\"] p", "article p", "body p"];
+
+/// Returns the text of the first non-empty paragraph of the article body.
+fn first_paragraph(document: &Html) -> Option<String> {
+    BODY_SELECTORS.iter().find_map(|selector| {
+        let selector = Selector::parse(selector).unwrap();
+        document
+            .select(&selector)
+            .map(|paragraph| paragraph.text().collect::<String>())
+            .map(|text| text.trim().to_string())
+            .find(|text| !text.is_empty())
+    })
+}
+
+/// Finds the place given by the dateline at the start of the text. The
+/// place is written in uppercase, optionally followed by a region (e.g.
+/// `SPRINGFIELD, Ill.`) and the news agency, and ends with a dash.
+fn find_dateline(text: &str) -> Option<String> {
+    let re = Regex::new(
+        r"^(?P<place>\p{Lu}[\p{Lu}'.\-]+(?:\s+\p{Lu}[\p{Lu}'.\-]*)*)(?P<region>,\s*\p{Lu}[\p{L}.]*(?:\s+\p{Lu}[\p{L}.]*)*)?\s*(?:\([^)]*\))?\s*(?:—|–|--|-)\s",
+    )
+    .unwrap();
+    let captures = re.captures(text)?;
+
+    let place = normalize_title_case(&captures["place"]);
+    let region = captures.name("region").map_or("", |region| region.as_str());
+    Some(format!("{place}{region}"))
+}
+
+/// Returns the place of the dateline of the article, if it has one.
+pub fn find_place(document: &Html) -> Option<String> {
+    find_dateline(&first_paragraph(document)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_dateline;
+
+    #[test]
+    fn dateline_variants() {
+        assert_eq!(find_dateline("LONDON — The Bank of England"), Some("London".to_string()));
+        assert_eq!(find_dateline("NEW YORK (Reuters) - Stocks fell"), Some("New York".to_string()));
+        assert_eq!(find_dateline("SPRINGFIELD, Ill. – Lawmakers"), Some("Springfield, Ill.".to_string()));
+    }
+
+    #[test]
+    fn no_dateline() {
+        assert_eq!(find_dateline("The council approved the expansion — after months of debate."), None);
+        assert_eq!(find_dateline("A - B testing is common"), None);
+        assert_eq!(find_dateline("NATO allies met on Tuesday"), None);
+    }
+}
@@ -3,13 +3,16 @@
 
 use crate::attribute::{Attribute, AttributeType, Author, Date};
 use crate::curl::{get, CurlError};
-use crate::generator::ReferenceGenerationError;
+use crate::generator::{ReferenceGenerationError, RequestOptions, ResponseCache};
 use crate::parser::{AttributeParser, ParseInfo};
 
-use biblatex::{Bibliography, Chunk, Entry, PermissiveType};
+use biblatex::{Bibliography, Chunk, ChunksExt, Entry, PermissiveType};
 use chrono::NaiveDate;
+use percent_encoding::percent_decode_str;
 use regex::Regex;
+use serde::Deserialize;
 use thiserror::Error;
+use url::Url;
 
 #[derive(Error, Debug)]
 pub enum DoiError {
@@ -21,6 +24,9 @@ pub enum DoiError {
 
     #[error("BibTeX string could not be parsed")]
     BibtexParseError,
+
+    #[error("CrossRef response could not be parsed")]
+    CrossrefParseError,
 }
 
 fn doi_regex_match(string: &str) -> Result<&str, DoiError> {
@@ -39,46 +45,142 @@ fn doi_regex_match(string: &str) -> Result<&str, DoiError> {
     result.cloned().ok_or(DoiError::DoiNotInHtmlError)
 }
 
+/// Prefixes which may precede the DOI itself.
+const DOI_PREFIXES: &[&str] = &[
+    "https://doi.org/",
+    "http://doi.org/",
+    "https://dx.doi.org/",
+    "http://dx.doi.org/",
+    "doi.org/",
+    "doi:",
+];
+
+/// Normalizes a DOI by stripping resolver URL and `doi:` prefixes as well as
+/// trailing punctuation picked up from the surrounding text. DOIs are
+/// case-insensitive, so the result is lowercased. Returns `None` if the
+/// result is not a valid DOI.
+pub fn normalize_doi(doi: &str) -> Option<String> {
+    let mut doi = doi.trim();
+    for prefix in DOI_PREFIXES {
+        if doi.len() >= prefix.len() && doi[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            doi = doi[prefix.len()..].trim_start();
+        }
+    }
+
+    // Closing parentheses are only stripped if unbalanced,
+    // as DOIs may legitimately end with one.
+    loop {
+        doi = doi.trim_end_matches(|c: char| matches!(c, '.' | ',' | ';') || c.is_whitespace());
+        let unbalanced = doi.matches(')').count() > doi.matches('(').count();
+        match doi.strip_suffix(')') {
+            Some(stripped) if unbalanced => doi = stripped,
+            _ => break,
+        }
+    }
+
+    let doi = doi.to_lowercase();
+    let re = Regex::new(r#"^10\.\d{4,9}/[-.;()/:\w]+$"#).unwrap();
+    re.is_match(&doi).then_some(doi)
+}
+
 /// Tries to find a DOI link in the HTML.
 fn try_find_doi_in_string(html: &str) -> Result<String, DoiError> {
     let doi_in_text = doi_regex_match(html);
     return doi_in_text.map(str::to_string);
 }
 
+/// Finds a DOI given in the URL itself, either as the value of a query
+/// parameter (e.g. `?doi=10.1234/abcd`) or in the path (e.g.
+/// `/doi/10.1234/abcd`). Both are percent-decoded, as the slash of
+/// the DOI is often encoded.
+fn find_doi_in_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let in_query = url.query_pairs().find_map(|(_, value)| normalize_doi(&value));
+
+    in_query.or_else(|| {
+        let path = percent_decode_str(url.path()).decode_utf8().ok()?;
+        normalize_doi(doi_regex_match(&path).ok()?)
+    })
+}
+
 /// Returns a BibTeX entry in string format by calling the DOI API.
 /// See https://citation.crosscite.org/docs.html for more information.
-fn send_doi_request(doi: &str) -> std::result::Result<String, DoiError> {
+fn send_doi_request(doi: &str, options: &RequestOptions) -> std::result::Result<String, DoiError> {
     let full_doi = format!("https://doi.org/{}", doi);
     let header_opt = Some("Accept: application/x-bibtex");
     let follow_location = true;
 
-    Ok(get(full_doi.as_str(), header_opt, follow_location)?)
+    Ok(get(full_doi.as_str(), header_opt, follow_location, options)?)
 }
 
-/// The function first tries to find a DOI address in the HTML
-/// or in the URL itself.
+/// Like [`send_doi_request`], but consults the cache first, if provided,
+/// and stores fresh responses in it.
+fn cached_doi_request(
+    doi: &str,
+    cache: Option<&ResponseCache>,
+    options: &RequestOptions,
+) -> std::result::Result<String, DoiError> {
+    let Some(cache) = cache else {
+        return send_doi_request(doi, options);
+    };
+
+    let key = format!("doi:{doi}");
+    if let Some(response) = cache.load(&key) {
+        return Ok(response);
+    }
+
+    let response = send_doi_request(doi, options)?;
+    cache.store(&key, &response);
+    Ok(response)
+}
+
+/// The function first tries to find a DOI address in the URL itself,
+/// as e.g. repositories give it in the query string, and then in the HTML.
 /// If found, the DOI is resolved and returned as Bibtex markup
-/// and finally parsed.
+/// and finally parsed. Entries ignored are added to the warnings.
 pub fn try_doi_to_bib(
     url: &str,
     html: &str,
     contained: &bool,
+    cache: Option<&ResponseCache>,
+    options: &RequestOptions,
+    warnings: &mut Vec<String>,
 ) -> Result<Bibliography, ReferenceGenerationError> {
     if !contained {
         return Err(ReferenceGenerationError::ParseSkip);
     }
-    let doi_html = try_find_doi_in_string(html);
-    let doi_url = try_find_doi_in_string(url);
-
-    let doi_address = if doi_html.is_ok() {
-        doi_html.unwrap()
-    } else {
-        doi_url?
+    let doi_address = match find_doi_in_url(url) {
+        Some(doi_address) => doi_address,
+        None => {
+            let doi_html = try_find_doi_in_string(html)?;
+            normalize_doi(&doi_html).ok_or(DoiError::DoiNotInHtmlError)?
+        }
     };
 
-    let doi_response = send_doi_request(doi_address.as_str())?;
+    let doi_response = cached_doi_request(doi_address.as_str(), cache, options)?;
     let bib = Bibliography::parse(doi_response.as_str()).map_err(|_| DoiError::BibtexParseError)?;
-    Ok(bib)
+    Ok(select_entry(bib, &doi_address, warnings))
+}
+
+/// Some DOIs (e.g. those of containers) resolve to several BibTeX entries.
+/// Keeps only the entry for the requested DOI, or the first entry if none
+/// of them match. The number of entries ignored is added to the warnings.
+fn select_entry(bib: Bibliography, doi: &str, warnings: &mut Vec<String>) -> Bibliography {
+    if bib.len() <= 1 {
+        return bib;
+    }
+
+    let mut entries = bib.into_vec();
+    let index = entries
+        .iter()
+        .position(|entry| entry.doi().ok().and_then(|d| normalize_doi(&d)).as_deref() == Some(doi))
+        .unwrap_or(0);
+    let selected = entries.swap_remove(index);
+    warnings.push(format!("Ignoring {} additional BibTeX entries returned for DOI {}", entries.len(), doi));
+
+    let mut selected_bib = Bibliography::new();
+    selected_bib.insert(selected);
+    selected_bib
 }
 
 fn author_to_attribute(entry: &Entry) -> Option<Attribute> {
@@ -149,6 +251,17 @@ fn permissive_to_string<T: biblatex::Type + std::fmt::Debug>(
     }
 }
 
+fn format_page_ranges(ranges: &[std::ops::Range<u32>]) -> String {
+    ranges
+        .iter()
+        .map(|range| match range.end > range.start {
+            true => format!("{}-{}", range.start, range.end),
+            false => range.start.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
 #[rustfmt::skip]
 fn attribute_type_to_attribute(entry: &Entry, attribute_type: AttributeType) -> Option<Attribute> {
     match attribute_type {
@@ -184,6 +297,18 @@ fn attribute_type_to_attribute(entry: &Entry, attribute_type: AttributeType) ->
             let value = permissive_to_string(&pt)?;
             Some(Attribute::Volume(value.to_string()))
         },
+        AttributeType::Issue  => {
+            let chunks = entry.number().ok()?;
+            Some(Attribute::Issue(chunks.format_verbatim()))
+        },
+        AttributeType::Pages  => {
+            let pt = entry.pages().ok()?;
+            let value = match pt {
+                PermissiveType::Typed(ranges) => format_page_ranges(&ranges),
+                PermissiveType::Chunks(chunks) => chunks.format_verbatim(),
+            };
+            Some(Attribute::Pages(value))
+        },
         AttributeType::Language => {
             let lang = entry.language().ok()?;
             Some(Attribute::Language(lang))
@@ -209,17 +334,110 @@ fn attribute_type_to_attribute(entry: &Entry, attribute_type: AttributeType) ->
     }
 }
 
+/// Endpoint of the CrossRef API listing works, e.g. the notices updating a DOI.
+const CROSSREF_WORKS_ENDPOINT: &str = "https://api.crossref.org/works";
+
+#[derive(Deserialize)]
+struct CrossrefResponse {
+    message: CrossrefMessage,
+}
+
+#[derive(Deserialize)]
+struct CrossrefMessage {
+    #[serde(default)]
+    items: Vec<CrossrefWork>,
+}
+
+#[derive(Deserialize)]
+struct CrossrefWork {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(rename = "update-to", default)]
+    update_to: Vec<CrossrefUpdate>,
+}
+
+#[derive(Deserialize)]
+struct CrossrefUpdate {
+    #[serde(rename = "DOI")]
+    doi: String,
+    #[serde(rename = "type")]
+    update_type: String,
+}
+
+/// Notice updating a DOI, e.g. the retraction of an article.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DoiUpdate {
+    /// Status of the work given by the notice, e.g. `Retracted`
+    pub status: &'static str,
+    /// DOI of the notice itself
+    pub notice: String,
+}
+
+impl DoiUpdate {
+    pub fn is_retraction(&self) -> bool {
+        self.status.ends_with("etracted")
+    }
+}
+
+/// Status given by a CrossRef update type, ranked by severity.
+#[rustfmt::skip]
+fn update_status(update_type: &str) -> Option<(u8, &'static str)> {
+    match update_type {
+        "retraction" | "withdrawal" | "removal"            => Some((3, "Retracted")),
+        "partial_retraction"                               => Some((2, "Partially retracted")),
+        "expression_of_concern"                            => Some((2, "Expression of concern")),
+        "correction" | "erratum" | "corrigendum" | "addendum" => Some((1, "Corrected")),
+        _ => None,
+    }
+}
+
+/// DOI of the first entry of the bibliography, which those resolved using
+/// [`send_doi_request`] give.
+pub fn bibliography_doi(bib: &Bibliography) -> Option<String> {
+    normalize_doi(&bib.iter().next()?.doi().ok()?)
+}
+
+/// Asks the CrossRef API for notices updating the DOI, i.e. works whose
+/// `update-to` names it, and returns the most severe of them, e.g. a
+/// retraction rather than an earlier correction.
+/// See https://www.crossref.org/documentation/crossmark/ for more information.
+pub fn fetch_update(
+    doi: &str,
+    cache: Option<&ResponseCache>,
+    options: &RequestOptions,
+) -> std::result::Result<Option<DoiUpdate>, DoiError> {
+    let key = format!("crossref-updates:{doi}");
+    let response = match cache.and_then(|cache| cache.load(&key)) {
+        Some(response) => response,
+        None => {
+            let url = format!("{CROSSREF_WORKS_ENDPOINT}?filter=updates:{doi}");
+            let response = get(&url, Some("Accept: application/json"), true, options)?;
+            if let Some(cache) = cache {
+                cache.store(&key, &response);
+            }
+            response
+        }
+    };
+
+    let response: CrossrefResponse = serde_json::from_str(&response).map_err(|_| DoiError::CrossrefParseError)?;
+    let update = response
+        .message
+        .items
+        .iter()
+        .flat_map(|notice| notice.update_to.iter().map(move |update| (notice, update)))
+        .filter(|(_, update)| normalize_doi(&update.doi).as_deref() == Some(doi))
+        .filter_map(|(notice, update)| Some((update_status(&update.update_type)?, notice)))
+        .max_by_key(|((severity, _), _)| *severity)
+        .map(|((_, status), notice)| DoiUpdate { status, notice: notice.doi.to_lowercase() });
+    Ok(update)
+}
+
 pub struct Doi;
 
 impl AttributeParser for Doi {
     fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
         let bib = parse_info.bibliography.as_ref()?;
 
-        assert!(
-            bib.len() == 1,
-            "Parsed BibTeX contained more than one entry, was input ok?"
-        );
-
         // Ignore any extra entries.
         let root_entry = bib.iter().next()?;
         attribute_type_to_attribute(root_entry, attribute_type)
@@ -228,7 +446,17 @@ impl AttributeParser for Doi {
 
 #[cfg(test)]
 mod tests {
-    use super::{doi_regex_match, send_doi_request};
+    use super::{doi_regex_match, find_doi_in_url, normalize_doi, select_entry, send_doi_request, try_doi_to_bib, Doi};
+    use crate::attribute::{Attribute, AttributeType, Author};
+    use crate::generator::attribute_config::{AttributeConfig, AttributePriority};
+    use crate::generator::{from_url, from_url_all, ArchiveOptions, MetadataType, RequestOptions, ResponseCache};
+    use crate::mock_client::MockClient;
+    use crate::parser::{AttributeParser, ParseInfo};
+    use crate::{GenerationOptions, Reference};
+    use biblatex::Bibliography;
+    use scraper::Html;
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn match_regex_doi() {
@@ -242,13 +470,204 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn normalize_doi_variants() {
+        let expected = Some("10.1126/science.169.3946.635".to_string());
+
+        assert_eq!(normalize_doi("10.1126/science.169.3946.635"), expected);
+        assert_eq!(normalize_doi("https://doi.org/10.1126/science.169.3946.635"), expected);
+        assert_eq!(normalize_doi("http://dx.doi.org/10.1126/science.169.3946.635"), expected);
+        assert_eq!(normalize_doi("doi:10.1126/science.169.3946.635"), expected);
+        assert_eq!(normalize_doi("DOI: 10.1126/SCIENCE.169.3946.635"), expected);
+        assert_eq!(normalize_doi("  10.1126/science.169.3946.635.\n"), expected);
+        assert_eq!(normalize_doi("10.1126/science.169.3946.635)."), expected);
+    }
+
+    #[test]
+    fn normalize_doi_keeps_balanced_parentheses() {
+        let doi = "10.1016/0006-2952(72)90018-6";
+        assert_eq!(normalize_doi(doi), Some(doi.to_string()));
+    }
+
+    #[test]
+    fn doi_in_url() {
+        assert_eq!(find_doi_in_url("https://repo.org/view?doi=10.1234/abcd"), Some("10.1234/abcd".to_string()));
+        assert_eq!(find_doi_in_url("https://repo.org/view?id=7&doi=10.1234%2FABCD"), Some("10.1234/abcd".to_string()));
+        assert_eq!(
+            find_doi_in_url("https://www.science.org/doi/10.1126/science.169.3946.635"),
+            Some("10.1126/science.169.3946.635".to_string())
+        );
+        assert_eq!(find_doi_in_url("https://repo.org/pdf/10.1234%2Fabcd.pdf"), Some("10.1234/abcd.pdf".to_string()));
+        assert_eq!(find_doi_in_url("https://repo.org/view?id=7"), None);
+        assert_eq!(find_doi_in_url(""), None);
+    }
+
+    #[test]
+    fn normalize_doi_invalid() {
+        assert_eq!(normalize_doi("https://doi.org/"), None);
+        assert_eq!(normalize_doi("11.1126/science"), None);
+        assert_eq!(normalize_doi("not a doi"), None);
+    }
+
     #[test]
     fn get_doi_request() {
         let doi = "10.1126/science.169.3946.635";
         let expected = " @article{Frank_1970, title={The Structure of Ordinary Water: New data and interpretations are yielding new insights into this fascinating substance.}, volume={169}, ISSN={1095-9203}, url={http://dx.doi.org/10.1126/science.169.3946.635}, DOI={10.1126/science.169.3946.635}, number={3946}, journal={Science}, publisher={American Association for the Advancement of Science (AAAS)}, author={Frank, Henry S.}, year={1970}, month=aug, pages={635–641} }\n";
 
-        let result = send_doi_request(doi);
+        let result = send_doi_request(doi, &RequestOptions::default());
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), expected);
     }
+
+    #[test]
+    fn doi_resolved_offline() {
+        let page_url = "https://journal.example.org/articles/sediment-transport";
+        let client = Arc::new(MockClient::new(&[
+            (page_url, r#"<html><head><meta name="citation_doi" content="10.5555/wadden.2021.3"></head></html>"#),
+            ("https://doi.org/10.5555/wadden.2021.3", "@article{Jensen_2021, title={Sediment transport in the Wadden Sea},
+                author={Jensen, Karen and Møller, Anders}, journal={Journal of Coastal Studies}, volume={12}, number={3},
+                pages={101--118}, year={2021}, month=sep}"),
+        ]));
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        let Reference::ScholarlyArticle { title, author, journal, volume, issue, pages, .. } = from_url(page_url, &options).unwrap() else {
+            panic!("Expected a scholarly article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Sediment transport in the Wadden Sea".to_string())));
+        assert_eq!(
+            author,
+            Some(Attribute::Authors(vec![Author::Person("Karen Jensen".to_string()), Author::Person("Anders Møller".to_string())]))
+        );
+        assert_eq!(journal, Some(Attribute::Journal("Journal of Coastal Studies".to_string())));
+        assert_eq!(volume, Some(Attribute::Volume("12".to_string())));
+        assert_eq!(issue, Some(Attribute::Issue("3".to_string())));
+        assert_eq!(pages, Some(Attribute::Pages("101-118".to_string())));
+        assert_eq!(client.requested(), [page_url, "https://doi.org/10.5555/wadden.2021.3"]);
+    }
+
+    #[test]
+    fn retraction_reported() {
+        let page_url = "https://journal.example.org/articles/tidal-memory";
+        // The retraction notice, following a correction of the same article.
+        let crossref = r#"{"status": "ok", "message": {"items": [
+            {"DOI": "10.5555/ERRATUM.2022.1", "update-to": [{"DOI": "10.5555/wadden.2021.3", "type": "correction"}]},
+            {"DOI": "10.5555/RETRACTION.2023.7", "update-to": [{"DOI": "10.5555/wadden.2021.3", "type": "retraction"}]}
+        ]}}"#;
+        let client = Arc::new(MockClient::new(&[
+            (page_url, r#"<html><head><meta name="citation_doi" content="10.5555/wadden.2021.3"></head></html>"#),
+            ("https://doi.org/10.5555/wadden.2021.3", "@article{Jensen_2021, title={Tidal memory in estuaries}, DOI={10.5555/wadden.2021.3}, journal={Journal of Coastal Studies}, year={2021}}"),
+            ("https://api.crossref.org/works?filter=updates:10.5555/wadden.2021.3", crossref),
+        ]));
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            check_doi_updates: true,
+            ..Default::default()
+        };
+
+        let result = from_url_all(page_url, &options).unwrap();
+        let Reference::ScholarlyArticle { status, .. } = &result.reference else {
+            panic!("Expected a scholarly article")
+        };
+        assert_eq!(status, &Some(Attribute::Status("Retracted".to_string())));
+        assert!(
+            result.warnings.iter().any(|warning| warning.contains("RETRACTED (https://doi.org/10.5555/retraction.2023.7)")),
+            "{:?}",
+            result.warnings
+        );
+        assert!(result.reference.bibtex().contains("note = \"Retracted\""));
+    }
+
+    #[test]
+    fn ignored_entries_reported() {
+        let page_url = "https://journal.example.org/proceedings/tides";
+        let client = Arc::new(MockClient::new(&[
+            (page_url, r#"<html><head><meta name="citation_doi" content="10.5555/12345.678"></head></html>"#),
+            ("https://doi.org/10.5555/12345.678", TWO_ENTRIES),
+        ]));
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        // The warning is returned rather than printed.
+        let result = from_url_all(page_url, &options).unwrap();
+        assert_eq!(result.reference.title(), Some("Measuring Tides"));
+        assert_eq!(result.warnings, ["Ignoring 1 additional BibTeX entries returned for DOI 10.5555/12345.678"]);
+    }
+
+    #[test]
+    fn doi_lookup_read_from_cache() {
+        let directory = std::env::temp_dir().join(format!("url2ref-doi-cache-{}", std::process::id()));
+        let cache = ResponseCache::new(directory, Duration::from_secs(3600));
+        let bibtex = "@article{Frank_1970, title={The Structure of Ordinary Water}, journal={Science}, year={1970}}";
+        cache.store("doi:10.1126/science.169.3946.635", bibtex);
+
+        // The DOI is never resolved over the network, as the cached response is used.
+        let url = "https://www.science.org/doi/10.1126/science.169.3946.635";
+        let bib = try_doi_to_bib(url, "", &true, Some(&cache), &RequestOptions::default(), &mut Vec::new()).unwrap();
+
+        let entry = bib.iter().next().unwrap();
+        assert_eq!(entry.key, "Frank_1970");
+    }
+
+    const TWO_ENTRIES: &str = "@book{Proceedings_2020, title={Proceedings of the Tide Workshop}, doi={10.5555/12345}}\n\
+                               @inproceedings{Doe_2020, title={Measuring Tides}, doi={10.5555/12345.678}}";
+
+    #[test]
+    fn multiple_entries_use_first() {
+        let bibliography = Bibliography::parse(TWO_ENTRIES).unwrap();
+        let parse_info = ParseInfo {
+            url: None,
+            raw_html: String::new(),
+            document: Html::new_document(),
+            html: None,
+            json_ld: Vec::new(),
+            bibliography: Some(bibliography),
+            zotero: None,
+            parsers: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let title = Doi::parse_attribute(&parse_info, AttributeType::Title);
+        assert_eq!(title, Some(Attribute::Title("Proceedings of the Tide Workshop".to_string())));
+    }
+
+    #[test]
+    fn multiple_entries_select_requested_doi() {
+        let bibliography = Bibliography::parse(TWO_ENTRIES).unwrap();
+
+        let mut warnings = Vec::new();
+        let selected = select_entry(bibliography, "10.5555/12345.678", &mut warnings);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected.iter().next().unwrap().key, "Doe_2020");
+        assert_eq!(warnings, ["Ignoring 1 additional BibTeX entries returned for DOI 10.5555/12345.678"]);
+    }
+
+    #[test]
+    fn issue_and_pages() {
+        let bibliography = Bibliography::parse("@article{Frank_1970, number={3946}, pages={635--641}}").unwrap();
+        let parse_info = ParseInfo {
+            url: None,
+            raw_html: String::new(),
+            document: Html::new_document(),
+            html: None,
+            json_ld: Vec::new(),
+            bibliography: Some(bibliography),
+            zotero: None,
+            parsers: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        assert_eq!(Doi::parse_attribute(&parse_info, AttributeType::Issue), Some(Attribute::Issue("3946".to_string())));
+        assert_eq!(Doi::parse_attribute(&parse_info, AttributeType::Pages), Some(Attribute::Pages("635-641".to_string())));
+    }
 }
@@ -0,0 +1,185 @@
+//! Parser for the Dublin Core (e.g. `DC.title`, `DCTERMS.issued`) and
+//! Highwire Press (e.g. `citation_title`, `citation_author`) meta tags with
+//! which academic repositories such as DSpace and EPrints annotate pages.
+
+use scraper::{Html, Selector};
+
+use crate::attribute::{Attribute, AttributeType, Author, Date};
+use crate::parser::{parse_date, AttributeParser, ParseInfo};
+
+/// Names of the meta tags giving each attribute, in order of preference.
+/// Highwire Press tags are preferred, as repositories give them in a more
+/// consistent form. Names are compared case-insensitively.
+fn meta_names(attribute_type: AttributeType) -> &'static [&'static str] {
+    match attribute_type {
+        AttributeType::Title     => &["citation_title", "dc.title", "dcterms.title"],
+        AttributeType::Author    => &["citation_author", "dc.creator", "dcterms.creator"],
+        AttributeType::Date      => &["citation_publication_date", "citation_date", "dcterms.issued", "dc.date", "dcterms.date", "dcterms.created"],
+        AttributeType::Publisher => &["citation_publisher", "dc.publisher", "dcterms.publisher"],
+        AttributeType::Language  => &["citation_language", "dc.language", "dcterms.language"],
+        AttributeType::Journal   => &["citation_journal_title"],
+        AttributeType::Volume    => &["citation_volume"],
+        AttributeType::Issue     => &["citation_issue"],
+        AttributeType::Url       => &["citation_public_url", "citation_abstract_html_url", "dc.identifier", "dcterms.identifier"],
+        _ => &[],
+    }
+}
+
+pub struct DublinCore;
+
+/// Non-empty values of the meta tags of the given name, in document order.
+fn meta_values<'a>(document: &'a Html, name: &str) -> Vec<&'a str> {
+    let selector = Selector::parse("meta[name][content]").unwrap();
+    document
+        .select(&selector)
+        .filter(|element| element.value().attr("name").is_some_and(|n| n.eq_ignore_ascii_case(name)))
+        .filter_map(|element| element.value().attr("content"))
+        .map(str::trim)
+        .filter(|content| !content.is_empty())
+        .collect()
+}
+
+/// Values of the first of the meta tags which the page has.
+fn first_values<'a>(document: &'a Html, names: &[&str]) -> Vec<&'a str> {
+    names
+        .iter()
+        .map(|name| meta_values(document, name))
+        .find(|values| !values.is_empty())
+        .unwrap_or_default()
+}
+
+/// Dates are given with slashes by Highwire Press tags, e.g. `2024/03/01`.
+fn find_date(document: &Html, names: &[&str]) -> Option<Date> {
+    names
+        .iter()
+        .flat_map(|name| meta_values(document, name))
+        .find_map(|date| parse_date(&date.replace('/', "-")))
+}
+
+/// Identifiers may also be e.g. handles or ISSNs, so only URLs are cited.
+/// Pages giving nothing but a DOI are cited using its resolver link.
+fn find_url(document: &Html, names: &[&str]) -> Option<String> {
+    let url = names
+        .iter()
+        .flat_map(|name| meta_values(document, name))
+        .find(|value| value.starts_with("http://") || value.starts_with("https://"));
+
+    url.map(str::to_string).or_else(|| {
+        let doi = meta_values(document, "citation_doi").into_iter().next()?;
+        Some(format!("https://doi.org/{}", doi.trim_start_matches("doi:")))
+    })
+}
+
+/// Page range given by the first and last page, e.g. `101-118`.
+fn find_pages(document: &Html) -> Option<String> {
+    let first_page = meta_values(document, "citation_firstpage").into_iter().next()?;
+    match meta_values(document, "citation_lastpage").into_iter().next() {
+        Some(last_page) if last_page != first_page => Some(format!("{first_page}-{last_page}")),
+        _ => Some(first_page.to_string()),
+    }
+}
+
+impl AttributeParser for DublinCore {
+    fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
+        let document = &parse_info.document;
+        let names = meta_names(attribute_type);
+
+        match attribute_type {
+            // Each author is given by a tag of their own
+            AttributeType::Author => {
+                let authors: Vec<Author> = first_values(document, names)
+                    .into_iter()
+                    .map(|name| Author::Person(name.to_string()))
+                    .collect();
+                (!authors.is_empty()).then_some(Attribute::Authors(authors))
+            }
+            AttributeType::Date => find_date(document, names).map(Attribute::Date),
+            AttributeType::Url => find_url(document, names).map(Attribute::Url),
+            AttributeType::Pages => find_pages(document).map(Attribute::Pages),
+            _ => {
+                let value = first_values(document, names).into_iter().next()?.to_string();
+                match attribute_type {
+                    AttributeType::Title => Some(Attribute::Title(value)),
+                    AttributeType::Publisher => Some(Attribute::Publisher(value)),
+                    AttributeType::Language => Some(Attribute::Language(value)),
+                    AttributeType::Journal => Some(Attribute::Journal(value)),
+                    AttributeType::Volume => Some(Attribute::Volume(value)),
+                    AttributeType::Issue => Some(Attribute::Issue(value)),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::DublinCore;
+    use crate::attribute::{Attribute, AttributeType, Author, Date};
+    use crate::parser::{AttributeParser, ParseInfo};
+
+    fn parse_info(head: &str) -> ParseInfo<'static> {
+        let raw_html = format!("<html><head>{head}</head><body></body></html>");
+        ParseInfo {
+            url: None,
+            document: Html::parse_document(&raw_html),
+            raw_html,
+            html: None,
+            json_ld: Vec::new(),
+            bibliography: None,
+            zotero: None,
+            parsers: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dublin_core_tags() {
+        let parse_info = parse_info(
+            r#"<meta name="DC.title" content="Sediment transport in the Wadden Sea">
+               <meta name="DC.creator" content="Jensen, Karen">
+               <meta name="DC.creator" content="Møller, Anders">
+               <meta name="DCTERMS.issued" content="2021-09">
+               <meta name="DC.identifier" content="urn:nbn:dk:sdu-2021-117">
+               <meta name="DC.identifier" content="https://portal.example.dk/en/publications/sediment-transport">
+               <meta name="dc.language" content="en">"#,
+        );
+        let parse = |attribute_type| DublinCore::parse_attribute(&parse_info, attribute_type);
+
+        assert_eq!(parse(AttributeType::Title), Some(Attribute::Title("Sediment transport in the Wadden Sea".to_string())));
+        assert_eq!(
+            parse(AttributeType::Author),
+            Some(Attribute::Authors(vec![
+                Author::Person("Jensen, Karen".to_string()),
+                Author::Person("Møller, Anders".to_string()),
+            ]))
+        );
+        assert_eq!(parse(AttributeType::Date), Some(Attribute::Date(Date::YearMonth { year: 2021, month: 9 })));
+        assert_eq!(
+            parse(AttributeType::Url),
+            Some(Attribute::Url("https://portal.example.dk/en/publications/sediment-transport".to_string()))
+        );
+        assert_eq!(parse(AttributeType::Language), Some(Attribute::Language("en".to_string())));
+        assert_eq!(parse(AttributeType::Journal), None);
+    }
+
+    #[test]
+    fn highwire_tags_preferred() {
+        let parse_info = parse_info(
+            r#"<meta name="DC.creator" content="Jensen, K.">
+               <meta name="citation_author" content="Jensen, Karen">
+               <meta name="citation_date" content="2021/09/14">
+               <meta name="citation_doi" content="10.5555/wadden.2021.3">"#,
+        );
+        let parse = |attribute_type| DublinCore::parse_attribute(&parse_info, attribute_type);
+
+        assert_eq!(parse(AttributeType::Author), Some(Attribute::Authors(vec![Author::Person("Jensen, Karen".to_string())])));
+        assert_eq!(
+            parse(AttributeType::Date),
+            Some(Attribute::Date(Date::YearMonthDay(chrono::NaiveDate::from_ymd_opt(2021, 9, 14).unwrap())))
+        );
+        assert_eq!(parse(AttributeType::Url), Some(Attribute::Url("https://doi.org/10.5555/wadden.2021.3".to_string())));
+    }
+}
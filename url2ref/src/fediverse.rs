@@ -0,0 +1,131 @@
+//! Support for citing Mastodon and other Fediverse posts.
+//!
+//! Posts are described by their [ActivityPub] object, which is requested
+//! from the post URL itself using content negotiation.
+//!
+//! [ActivityPub]: https://www.w3.org/TR/activitypub/
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use scraper::Html;
+use serde::Deserialize;
+use url::Url;
+
+use crate::attribute::{Attribute, AttributeType, Author};
+use crate::curl;
+use crate::generator::RequestOptions;
+use crate::parser::{parse_date, AttributeCollection};
+
+/// Maximum number of characters of the post content used as the title.
+const MAX_SNIPPET_LENGTH: usize = 100;
+
+/// The parts of an ActivityPub `Note` object relevant for citing a post.
+#[derive(Debug, Deserialize)]
+struct ActivityPubNote {
+    content: Option<String>,
+    published: Option<String>,
+}
+
+/// Returns the handle (`@user@instance`) of the author of a
+/// Mastodon-style post URL (`https://instance/@user/postid`).
+fn post_author_handle(url: &Url) -> Option<String> {
+    let re = Regex::new(r"^/@([A-Za-z0-9_.]+)(?:@[^/]+)?/\d+/?$").unwrap();
+    let user = re.captures(url.path())?.get(1)?.as_str();
+    let host = url.host_str()?;
+
+    Some(format!("@{user}@{host}"))
+}
+
+/// Converts the HTML content of a post into a plain text snippet
+/// shortened at a word boundary.
+fn content_snippet(content: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(content);
+    let words: Vec<&str> = fragment
+        .root_element()
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect();
+
+    let mut snippet = String::new();
+    for word in words {
+        if snippet.chars().count() + word.chars().count() + 1 > MAX_SNIPPET_LENGTH {
+            snippet.push('…');
+            break;
+        }
+        if !snippet.is_empty() {
+            snippet.push(' ');
+        }
+        snippet.push_str(word);
+    }
+
+    (!snippet.is_empty()).then_some(snippet)
+}
+
+/// If the URL points to a Fediverse post, fetches its ActivityPub object
+/// and creates the attributes describing the post.
+pub fn fetch_attributes(url: &str, options: &RequestOptions) -> Option<AttributeCollection> {
+    let parsed_url = Url::parse(url).ok()?;
+    let handle = post_author_handle(&parsed_url)?;
+
+    let response = curl::get(url, Some("Accept: application/activity+json"), true, options).ok()?;
+    let note: ActivityPubNote = serde_json::from_str(&response).ok()?;
+
+    let mut attributes = HashMap::new();
+    attributes.insert(AttributeType::Author, Attribute::Authors(vec![Author::Generic(handle)]));
+    if let Some(title) = note.content.as_deref().and_then(content_snippet) {
+        attributes.insert(AttributeType::Title, Attribute::Title(title));
+    }
+    if let Some(date) = note.published.as_deref().and_then(parse_date) {
+        attributes.insert(AttributeType::Date, Attribute::Date(date));
+    }
+    if let Some(host) = parsed_url.host_str() {
+        attributes.insert(AttributeType::Site, Attribute::Site(host.to_string()));
+    }
+
+    Some(AttributeCollection { attributes, provenance: HashMap::new() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{from_url, ArchiveOptions};
+    use crate::mock_server::{MockServer, Response};
+    use crate::GenerationOptions;
+
+    #[test]
+    fn post_url_detection() {
+        let post = Url::parse("https://mastodon.social/@Gargron/109932525584930142").unwrap();
+        let profile = Url::parse("https://mastodon.social/@Gargron").unwrap();
+
+        assert_eq!(post_author_handle(&post), Some("@Gargron@mastodon.social".to_string()));
+        assert_eq!(post_author_handle(&profile), None);
+    }
+
+    #[test]
+    fn cite_mastodon_post() {
+        let note = r#"{
+            "type": "Note",
+            "published": "2024-03-01T12:00:00Z",
+            "content": "<p>Our new tide gauge is live! Readings every ten minutes.</p>"
+        }"#;
+        let page = r#"<html><head><meta property="og:title" content="Alice (@alice)"></head></html>"#;
+        let server = MockServer::start(move |request| {
+            match request.header("Accept") {
+                Some("application/activity+json") => Response::ok(note),
+                _ => Response::ok(page),
+            }
+        });
+        let url = server.url("/@alice/111222333");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            ..Default::default()
+        };
+        let citation = from_url(&url, &options).unwrap().wiki();
+
+        assert!(citation.contains("|title=Our new tide gauge is live! Readings every ten minutes."));
+        assert!(citation.contains("|author=@alice@127.0.0.1"));
+        assert!(citation.contains("|date=2024-03-01"));
+    }
+}
@@ -1,24 +1,51 @@
 //! Generator responsible for producing a [`Reference`]
 
-use deepl_api::{DeepL, Error as DeepLError, TranslatableTextList};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::result;
+use std::thread;
+use std::time::{Duration, Instant};
 
+use biblatex::EntryType;
 use chrono::{NaiveDateTime, DateTime, Utc, ParseError};
+use regex::Regex;
 use serde::Deserialize;
 use serde_json::Value;
-use strum::{EnumIter, EnumCount};
+use sha2::{Digest, Sha256};
+use strum::{EnumIter, EnumCount, IntoEnumIterator};
 use thiserror::Error;
+use url::form_urlencoded::byte_serialize;
 
-use crate::attribute::{Attribute, AttributeType, Date, Translation};
+use crate::attribute::{Attribute, AttributeType, Date, Translation, UrlStatus};
 
 use serde::Serialize;
 
-use crate::curl::CurlError;
-use crate::doi::DoiError;
-use crate::parser::{AttributeCollection, ParseInfo};
-use crate::reference::Reference;
+use crate::curl::{CurlError, HttpClient};
+use crate::doi::{self, DoiError};
+use crate::parser::{parse_all_sources, AttributeCollection, ParseInfo};
+use crate::reference::{Reference, ReferenceKind};
 use crate::GenerationOptions;
 use crate::curl;
+use crate::pagination;
+use crate::amp;
+use crate::interstitial;
+use crate::fediverse;
+use crate::qa_page;
+use crate::copyright;
+use crate::dateline;
+use crate::hreflang;
+use crate::schema_org;
+use crate::spa;
+use crate::syndication;
+use crate::redirect::expand_url;
+use crate::title_case::normalize_title_case;
+use crate::translation;
+use crate::url_normalization::normalize_url;
+use crate::url_site::site_from_url;
+use crate::date_check::check_date;
 
 type GenerationResult<T> = result::Result<T, ReferenceGenerationError>;
 
@@ -29,8 +56,17 @@ pub enum ReferenceGenerationError {
     #[error("curl GET failed")]
     CurlError(#[from] CurlError),
 
-    #[error("All provided parsers failed")]
-    ParseFailure,
+    #[error("{url} responded with HTTP status {status}")]
+    HttpStatus { url: String, status: u32 },
+
+    /// The page is e.g. a consent wall or a captcha, and no copy
+    /// of the page behind it could be retrieved.
+    #[error("{url} blocks its content, e.g. behind a consent wall or captcha")]
+    BlockedContent { url: String },
+
+    /// Carries the reason for which each of the parsers used failed.
+    #[error("All provided parsers failed for {}: {}", .url.as_deref().unwrap_or("the page"), .failures.iter().map(|(metadata_type, reason)| format!("{metadata_type}: {reason}")).collect::<Vec<_>>().join("; "))]
+    ParseFailure { url: Option<String>, failures: Vec<(MetadataType, String)> },
 
     #[error("Parser was skipped")]
     ParseSkip,
@@ -38,17 +74,40 @@ pub enum ReferenceGenerationError {
     #[error("HTML failed to parse")]
     HTMLParseError(#[from] std::io::Error),
 
-    #[error("DeepL translation failed")]
-    DeepLError(#[from] DeepLError),
-
-    #[error("Title translation procedure failed")]
-    TranslationError,
+    #[error("Title translation failed: {0}")]
+    TranslationError(String),
 
     #[error("Retrieving DOI failed")]
     DoiError(#[from] DoiError),
 
-    #[error("Retrieving DOI failed")]
+    #[error("Retrieving archived snapshot failed")]
     ArchiveError(#[from] ArchiveError),
+
+    #[error("Archiving the page failed")]
+    ArchivalError(ArchiveError),
+
+    #[error("Required attributes are missing: {}", .0.iter().map(|attribute_type| format!("{attribute_type:?}")).collect::<Vec<_>>().join(", "))]
+    InsufficientMetadata(Vec<AttributeType>),
+}
+
+impl ReferenceGenerationError {
+    /// URL of the page being processed, if the error is specific to it.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            ReferenceGenerationError::HttpStatus { url, .. } => Some(url),
+            ReferenceGenerationError::BlockedContent { url } => Some(url),
+            ReferenceGenerationError::ParseFailure { url, .. } => url.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Reason for which each parser failed, if all of them did.
+    pub fn parse_failures(&self) -> &[(MetadataType, String)] {
+        match self {
+            ReferenceGenerationError::ParseFailure { failures, .. } => failures,
+            _ => &[],
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -60,7 +119,25 @@ pub enum ArchiveError {
     DeserializeError(#[from] serde_json::Error),
 
     #[error("JSON byte-to-String conversion failed")]
-    ByteConversionError(#[from] std::string::FromUtf8Error)
+    ByteConversionError(#[from] std::string::FromUtf8Error),
+
+    #[error("No snapshot of the URL was found")]
+    NoSnapshot,
+
+    #[error("Save Page Now job failed: {0}")]
+    SaveFailed(String),
+
+    #[error("Save Page Now job did not finish in time")]
+    SaveTimeout,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LanguageError {
+    #[error("DeepL cannot translate from \"{0}\", supported source languages are {}", translation::DEEPL_SOURCE_LANGUAGES.join(", "))]
+    UnsupportedSource(String),
+
+    #[error("DeepL cannot translate into \"{0}\", supported target languages are {}", translation::DEEPL_TARGET_LANGUAGES.join(", "))]
+    UnsupportedTarget(String),
 }
 
 #[derive(
@@ -70,35 +147,287 @@ pub enum MetadataType {
     #[default]
     OpenGraph,
     SchemaOrg,
-    Doi
+    Doi,
+    /// The `<title>` element, `<meta name>` tags and canonical link.
+    HtmlMeta,
+    /// Dublin Core and Highwire Press `<meta name>` tags, as given
+    /// by academic repositories, e.g. `DC.title` and `citation_author`.
+    DublinCore,
+    /// RDFa `property` and `typeof` attributes on the elements of the
+    /// page, e.g. `<h1 property="dc:title">`.
+    Rdfa,
+    /// The Zotero item returned for the URL by a Citoid service.
+    /// Sends the URL being cited to the service.
+    Zotero,
+}
+
+impl MetadataType {
+    /// Lowercase name of the metadata type, as accepted by
+    /// [`MetadataType::from_str`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetadataType::OpenGraph => "opengraph",
+            MetadataType::SchemaOrg => "schema_org",
+            MetadataType::Doi => "doi",
+            MetadataType::HtmlMeta => "html_meta",
+            MetadataType::DublinCore => "dublin_core",
+            MetadataType::Rdfa => "rdfa",
+            MetadataType::Zotero => "zotero",
+        }
+    }
 }
 
-/// User options for title translation.
-#[derive(Clone, Default)]
+impl fmt::Display for MetadataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Unknown metadata type: {0}")]
+pub struct UnknownMetadataType(pub String);
+
+/// Names are compared case-insensitively and regardless of separators,
+/// so e.g. `schema_org`, `schema-org` and `SchemaOrg` are all accepted.
+impl FromStr for MetadataType {
+    type Err = UnknownMetadataType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalize = |name: &str| name.replace(['_', '-'], "").to_ascii_lowercase();
+        MetadataType::iter()
+            .find(|metadata_type| normalize(metadata_type.name()) == normalize(s))
+            .ok_or_else(|| UnknownMetadataType(s.to_string()))
+    }
+}
+
+/// Services with which titles are translated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranslationProvider {
+    #[default]
+    DeepL,
+    /// Version 2 of the Google Cloud Translation API
+    Google,
+}
+
+/// User options for title translation. The API keys are
+/// not serialized, so that they stay out of config files.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TranslationOptions {
     /// Contains an ISO 639 language code. If None, source language is guessed
     pub source: Option<String>,
     /// Contains an ISO 639 language code. If None, no translation.
     pub target: Option<String>,
+    /// Service used for translation, whose API key must be given
+    pub provider: TranslationProvider,
     /// DeepL API key
+    #[serde(skip)]
     pub deepl_key: Option<String>,
+    /// Google Cloud API key
+    #[serde(skip)]
+    pub google_key: Option<String>,
+    /// Attributes to translate, which may be [`AttributeType::Title`]
+    /// and [`AttributeType::Site`], e.g. for sites in non-Latin scripts.
+    /// Only the title is translated by default.
+    pub attributes: Vec<AttributeType>,
+}
+
+impl Default for TranslationOptions {
+    fn default() -> Self {
+        Self {
+            source: None,
+            target: None,
+            provider: TranslationProvider::default(),
+            deepl_key: None,
+            google_key: None,
+            attributes: vec![AttributeType::Title],
+        }
+    }
+}
+
+impl TranslationOptions {
+    /// Checks that DeepL supports the source and target languages and
+    /// returns the options with the codes normalized to DeepL's, e.g.
+    /// `en-gb` to `EN-GB`. DeepL only distinguishes regional variants of
+    /// target languages, so the region of source languages is dropped,
+    /// while the bare targets `en` and `pt` are taken to mean British
+    /// English and European Portuguese, as they formerly were by DeepL.
+    /// Options for other providers are returned as they are.
+    pub fn validate(&self) -> Result<TranslationOptions, LanguageError> {
+        if self.provider != TranslationProvider::DeepL {
+            return Ok(self.clone());
+        }
+
+        let source = self.source.as_deref().map(translation::normalize_deepl_source).transpose()?;
+        let target = self.target.as_deref().map(translation::normalize_deepl_target).transpose()?;
+        Ok(TranslationOptions { source, target, ..self.clone() })
+    }
+}
+
+/// Services from which archived copies of web pages are retrieved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveProvider {
+    /// The Internet Archive's [Wayback Machine](https://web.archive.org/).
+    #[default]
+    WaybackMachine,
+    /// [archive.today](https://archive.today/), which frequently has
+    /// snapshots of paywalled news sites.
+    ArchiveToday,
 }
 
 /// User options for fetching of archived URL and date.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ArchiveOptions {
     /// Whether to attempt to fetch an archived URL and date
     pub include_archived: bool,
     /// Whether to attempt perform the archive operation if the site
-    /// hasn't been archived yet.
-    /// TODO: implement this
+    /// hasn't been archived yet. Only supported by the Wayback Machine.
     pub perform_archival: bool,
+    /// Service from which the archived URL and date are fetched
+    pub provider: ArchiveProvider,
+    /// How long to wait for the archive operation to finish
+    #[serde(with = "duration_seconds")]
+    pub archival_timeout: Duration,
+    /// Status of the cited URL given along with the archived URL,
+    /// e.g. [`UrlStatus::Dead`] for pages which have gone offline
+    pub url_status: UrlStatus,
 }
 impl Default for ArchiveOptions {
     fn default() -> Self {
         Self {
             include_archived: true,
             perform_archival: false,
+            provider: ArchiveProvider::default(),
+            archival_timeout: Duration::from_secs(60),
+            url_status: UrlStatus::default(),
+        }
+    }
+}
+
+/// User options for the HTTP requests made during reference generation,
+/// i.e. retrieval of the page, DOI lookups and archive services.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestOptions {
+    /// How long to wait for a connection to be established
+    #[serde(with = "duration_seconds")]
+    pub connect_timeout: Duration,
+    /// How long a request may take in total, including the transfer
+    #[serde(with = "duration_seconds")]
+    pub timeout: Duration,
+    /// How many times requests failing with timeouts, connection
+    /// errors or 5xx responses are retried
+    pub retries: u32,
+    /// Delay before the first retry, doubled after each further attempt
+    #[serde(with = "duration_seconds")]
+    pub retry_backoff: Duration,
+    /// User-Agent sent with the requests. If None, no User-Agent header is sent.
+    pub user_agent: Option<String>,
+    /// Client performing the requests, which may be shared between
+    /// generations to reuse connections. If None, every request is
+    /// made using a new [`crate::CurlClient`]. Not serialized.
+    #[serde(skip)]
+    pub http_client: Option<Arc<dyn HttpClient>>,
+    /// How many pages [`crate::generate_batch`] fetches at once
+    pub batch_concurrency: usize,
+}
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            retries: 1,
+            retry_backoff: Duration::from_millis(500),
+            user_agent: None,
+            http_client: None,
+            batch_concurrency: 8,
+        }
+    }
+}
+
+/// Third-party providers of cached copies of web pages, consulted when the
+/// page itself only yields an interstitial (e.g. an email-gated "view article"
+/// page) instead of the article. No public cache is built in, as Google shut
+/// down its web cache in 2024, so providers are given by their URL template.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheProvider {
+    /// URL template in which `{url}` is replaced by the percent-encoded
+    /// URL of the page, e.g. `https://cache.example.com/?url={url}`.
+    Custom(String),
+}
+
+/// Transformation applied to an extracted [`Attribute`], e.g. to rewrite
+/// the name of a site. See [`crate::GenerationOptions::attribute_transforms`].
+pub type AttributeTransform = Arc<dyn Fn(Attribute) -> Attribute + Send + Sync>;
+
+/// Metadata type which supplied each attribute of a [`Reference`], e.g. to
+/// find out whether a wrong date came from Open Graph or Schema.org.
+/// Attributes not parsed from metadata (e.g. the access date, archive
+/// attributes or the URL of the first page of a multi-part article)
+/// have no entry.
+pub type Provenance = HashMap<AttributeType, MetadataType>;
+
+/// Attributes found in each metadata type regardless of the priorities,
+/// e.g. to compare the titles given by Open Graph and Schema.org.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultiSourceAttributeCollection {
+    pub sources: HashMap<MetadataType, HashMap<AttributeType, Attribute>>,
+}
+impl MultiSourceAttributeCollection {
+    /// Retrieves the [`Attribute`] found in the metadata type, if any.
+    pub fn get(&self, metadata_type: MetadataType, attribute_type: AttributeType) -> Option<&Attribute> {
+        self.sources.get(&metadata_type)?.get(&attribute_type)
+    }
+}
+
+/// Everything generated for a page from a single fetch of it,
+/// see [`from_url_all`].
+#[derive(Debug)]
+pub struct FullResult {
+    pub reference: Reference,
+    pub sources: MultiSourceAttributeCollection,
+    pub provenance: Provenance,
+    /// Problems which did not prevent generation, e.g. an
+    /// implausible date or a failure to archive the page.
+    pub warnings: Vec<String>,
+}
+
+/// On-disk cache of responses to network lookups, e.g. the BibTeX entries
+/// retrieved for DOIs. Each response is stored as a JSON file in `directory`
+/// and reused until it is older than `ttl`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseCache {
+    pub directory: PathBuf,
+    #[serde(with = "duration_seconds")]
+    pub ttl: Duration,
+}
+
+/// (De)serialization of durations as seconds, e.g. `timeout = 30` or
+/// `retry_backoff = 0.5` in config files.
+mod duration_seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Whole(u64),
+        Fractional(f64),
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        match duration.subsec_nanos() {
+            0 => serializer.serialize_u64(duration.as_secs()),
+            _ => serializer.serialize_f64(duration.as_secs_f64()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        match Seconds::deserialize(deserializer)? {
+            Seconds::Whole(seconds) => Ok(Duration::from_secs(seconds)),
+            Seconds::Fractional(seconds) => Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom),
         }
     }
 }
@@ -120,7 +449,7 @@ pub mod attribute_config {
     impl Default for AttributePriority {
         fn default() -> Self {
             Self {
-                priority: vec![MetadataType::OpenGraph, MetadataType::SchemaOrg],
+                priority: vec![MetadataType::OpenGraph, MetadataType::SchemaOrg, MetadataType::DublinCore, MetadataType::Rdfa, MetadataType::HtmlMeta],
             }
         }
     }
@@ -138,16 +467,27 @@ pub mod attribute_config {
         pub title: Option<AttributePriority>,
         pub authors: Option<AttributePriority>,
         pub date: Option<AttributePriority>,
+        pub original_date: Option<AttributePriority>,
         pub archive_date: Option<AttributePriority>,
         pub language: Option<AttributePriority>,
         pub locale: Option<AttributePriority>,
         pub site: Option<AttributePriority>,
+        pub section: Option<AttributePriority>,
         pub url: Option<AttributePriority>,
         pub archive_url: Option<AttributePriority>,
         pub journal: Option<AttributePriority>,
         pub publisher: Option<AttributePriority>,
         pub institution: Option<AttributePriority>,
         pub volume: Option<AttributePriority>,
+        pub issue: Option<AttributePriority>,
+        pub pages: Option<AttributePriority>,
+        pub duration: Option<AttributePriority>,
+        pub series: Option<AttributePriority>,
+        pub image_credit: Option<AttributePriority>,
+        /// Metadata types tried, in order, for any attribute still missing
+        /// after its own priority, e.g. to take everything from Open Graph
+        /// but fill in the author from Schema.org.
+        pub fallback: Option<AttributePriority>,
     }
 
     impl AttributeConfig {
@@ -156,16 +496,23 @@ pub mod attribute_config {
                 .title(priority.clone())
                 .authors(priority.clone())
                 .date(priority.clone())
+                .original_date(priority.clone())
                 .archive_date(priority.clone())
                 .language(priority.clone())
                 .locale(priority.clone())
                 .site(priority.clone())
+                .section(priority.clone())
                 .url(priority.clone())
                 .archive_url(priority.clone())
                 .journal(priority.clone())
                 .publisher(priority.clone())
                 .institution(priority.clone())
                 .volume(priority.clone())
+                .issue(priority.clone())
+                .pages(priority.clone())
+                .duration(priority.clone())
+                .series(priority.clone())
+                .image_credit(priority.clone())
                 .build()
                 .unwrap()
         }
@@ -174,41 +521,91 @@ pub mod attribute_config {
             match attribute_type {
                 AttributeType::Title       => &self.title,
                 AttributeType::Author      => &self.authors,
+                AttributeType::Affiliations => &self.authors, // Taken from where the authors are
                 AttributeType::Date        => &self.date,
+                AttributeType::OriginalDate => &self.original_date,
                 AttributeType::ArchiveDate => &self.archive_date,
+                AttributeType::AccessDate  => &None, // Set at generation time, not parsed
                 AttributeType::Language    => &self.language,
                 AttributeType::Locale      => &self.locale,
                 AttributeType::Site        => &self.site,
+                AttributeType::Section     => &self.section,
                 AttributeType::Url         => &self.url,
                 AttributeType::ArchiveUrl  => &self.archive_url,
                 AttributeType::Type        => &None, // TODO: Decide future of AttributeType::Type
                 AttributeType::Journal     => &self.journal,
                 AttributeType::Publisher   => &self.publisher,
                 AttributeType::Volume      => &self.volume,
+                AttributeType::Issue       => &self.issue,
+                AttributeType::Pages       => &self.pages,
                 AttributeType::Institution => &self.institution,
+                AttributeType::Duration    => &self.duration,
+                AttributeType::Series      => &self.series,
+                AttributeType::Place       => &None, // Extracted from the dateline, not parsed
+                AttributeType::ImageCredit => &self.image_credit,
             }
         }
 
+        /// Returns the config with the priority of a single attribute replaced,
+        /// e.g. to take the date from DOI but everything else from Schema.org.
+        /// [`AttributeType::Type`], [`AttributeType::AccessDate`] and
+        /// [`AttributeType::Place`] have no configurable priority and are ignored,
+        /// as is [`AttributeType::Affiliations`], which follows the authors.
+        pub fn with_override(mut self, attribute_type: AttributeType, priority: AttributePriority) -> Self {
+            let field = match attribute_type {
+                AttributeType::Title       => &mut self.title,
+                AttributeType::Author      => &mut self.authors,
+                AttributeType::Affiliations => return self,
+                AttributeType::Date        => &mut self.date,
+                AttributeType::OriginalDate => &mut self.original_date,
+                AttributeType::ArchiveDate => &mut self.archive_date,
+                AttributeType::AccessDate  => return self,
+                AttributeType::Language    => &mut self.language,
+                AttributeType::Locale      => &mut self.locale,
+                AttributeType::Site        => &mut self.site,
+                AttributeType::Section     => &mut self.section,
+                AttributeType::Url         => &mut self.url,
+                AttributeType::ArchiveUrl  => &mut self.archive_url,
+                AttributeType::Type        => return self,
+                AttributeType::Journal     => &mut self.journal,
+                AttributeType::Publisher   => &mut self.publisher,
+                AttributeType::Volume      => &mut self.volume,
+                AttributeType::Issue       => &mut self.issue,
+                AttributeType::Pages       => &mut self.pages,
+                AttributeType::Institution => &mut self.institution,
+                AttributeType::Duration    => &mut self.duration,
+                AttributeType::Series      => &mut self.series,
+                AttributeType::Place       => return self,
+                AttributeType::ImageCredit => &mut self.image_credit,
+            };
+            *field = Some(priority);
+            self
+        }
+
+        /// Returns the config with the fallback replaced, see [`AttributeConfig::fallback`].
+        pub fn with_fallback(mut self, fallback: AttributePriority) -> Self {
+            self.fallback = Some(fallback);
+            self
+        }
+
         /// Finds the parsers used.
         /// Serialize to JSON, deserialize back to a HashMap. This allows us to iterate over all fields.
         /// This is important because if additional fields of AttributeConfig are added, this function will
         /// still work.
         pub fn parsers_used(&self) -> Vec<MetadataType> {
             let json_string = serde_json::to_string(self).unwrap();
-            let map: HashMap<String, Option<AttributePriority>> =
+            let mut map: HashMap<String, Option<AttributePriority>> =
                 serde_json::from_str(&json_string).unwrap();
             
+            // Unlike for the attributes, a missing fallback means no fallback rather than the default.
+            let fallback = map.remove("fallback").flatten().map(|fallback| fallback.priority).unwrap_or_default();
 
-            let flattened_map: Vec<MetadataType> = map
+            map
                 .values()
-                .into_iter()
                 .map(|a| a.clone().unwrap_or_default().priority)
+                .chain(std::iter::once(fallback))
                 .collect::<Vec<Vec<MetadataType>>>()
-                .concat();
-
-            println!("{:?}", flattened_map);
-
-            flattened_map
+                .concat()
                 .into_iter()
                 .collect::<HashSet<_>>()
                 .into_iter()
@@ -219,89 +616,530 @@ pub mod attribute_config {
 
 /// Generates a [`Reference`] from a URL.
 pub fn from_url(url: &str, options: &GenerationOptions) -> GenerationResult<Reference> {
-    let parse_info = ParseInfo::from_url(url, &options.attribute_config.parsers_used())?;
-    create_reference(&parse_info, &options)
+    from_url_with_provenance(url, options).map(|(reference, _)| reference)
+}
+
+/// Generates a [`Reference`] from a URL along with the
+/// [`Provenance`] of its attributes.
+pub fn from_url_with_provenance(url: &str, options: &GenerationOptions) -> GenerationResult<(Reference, Provenance)> {
+    let result = url_result(url, options, false)?;
+    Ok((result.reference, result.provenance))
+}
+
+/// Generates a [`Reference`] from a URL along with the attributes of
+/// every metadata type, their [`Provenance`] and any warnings.
+pub fn from_url_all(url: &str, options: &GenerationOptions) -> GenerationResult<FullResult> {
+    url_result(url, options, true)
+}
+
+/// Generates a [`Reference`] from a URL, parsing the attributes of every
+/// metadata type only if `include_sources` is set, as this is costly.
+fn url_result(url: &str, options: &GenerationOptions, include_sources: bool) -> GenerationResult<FullResult> {
+    let mut warnings = Vec::new();
+    let expanded_url = options.expand_short_urls.then(|| expand_url(url, &options.request_options)).transpose()?;
+    let url = expanded_url.as_deref().unwrap_or(url);
+    let parse_info = ParseInfo::from_url(url, options)?;
+
+    // If a specific language edition was requested and the page
+    // advertises it as an alternate, cite that edition instead.
+    let alternate_url = options.preferred_content_language.as_deref()
+        .and_then(|language| hreflang::find_alternate_url(&parse_info.document, url, language));
+    let (url, parse_info) = match &alternate_url {
+        Some(alternate_url) => (alternate_url.as_str(), ParseInfo::from_url(alternate_url, options)?),
+        None => (url, parse_info),
+    };
+
+    // Paywalled pages may only hold a teaser with incomplete metadata.
+    let paywalled = schema_org::is_paywalled(&parse_info);
+    if paywalled {
+        warnings.push(format!("{} is paywalled, so its metadata may be incomplete", url));
+    }
+
+    // Interstitials carry no metadata about the article itself, and paywalled
+    // pages may lack some, so use a cached copy of the page instead, if configured.
+    let blocked = interstitial::is_interstitial(&parse_info, &options.blocked_content_patterns);
+    if paywalled || blocked {
+        if let Some(cached_info) = interstitial::fetch_cached(url, options) {
+            return create_reference(&cached_info, options, warnings, include_sources);
+        }
+    }
+
+    // Otherwise cite the Zotero item of the page, which Citoid may have
+    // retrieved past the interstitial, or the latest archived snapshot.
+    if blocked {
+        if parse_info.zotero.is_some() {
+            warnings.push(format!("{} blocks its content, so its Zotero item is cited", url));
+            let zotero_options = GenerationOptions {
+                attribute_config: attribute_config::AttributeConfig::new(attribute_config::AttributePriority::new(&[MetadataType::Zotero])),
+                ..options.clone()
+            };
+            return create_reference(&parse_info, &zotero_options, warnings, include_sources);
+        }
+        if let Some(archived_info) = fetch_archived_content(url, options) {
+            warnings.push(format!("{} blocks its content, so its archived snapshot is cited", url));
+            return create_reference(&archived_info, options, warnings, include_sources);
+        }
+        return Err(ReferenceGenerationError::BlockedContent { url: url.to_string() });
+    }
+
+    create_reference(&parse_info, options, warnings, include_sources)
 }
 
 /// Generates a [`Reference`] from raw HTML as read from a file.
 pub fn from_file(html_path: &str, options: &GenerationOptions) -> GenerationResult<Reference> {
-    let parse_info = ParseInfo::from_file(html_path)?;
-    create_reference(&parse_info, &options)
+    from_file_with_provenance(html_path, options).map(|(reference, _)| reference)
+}
+
+/// Generates a [`Reference`] from raw HTML as read from a file
+/// along with the [`Provenance`] of its attributes.
+pub fn from_file_with_provenance(
+    html_path: &str,
+    options: &GenerationOptions,
+) -> GenerationResult<(Reference, Provenance)> {
+    let parse_info = ParseInfo::from_file(html_path, options)?;
+    let result = create_reference(&parse_info, options, Vec::new(), false)?;
+    Ok((result.reference, result.provenance))
+}
+
+/// Generates a [`Reference`] from raw HTML as read from a file along with
+/// the attributes of every metadata type, their [`Provenance`] and any warnings.
+pub fn from_file_all(html_path: &str, options: &GenerationOptions) -> GenerationResult<FullResult> {
+    let parse_info = ParseInfo::from_file(html_path, options)?;
+    create_reference(&parse_info, options, Vec::new(), true)
+}
+
+/// Generates a [`Reference`] from raw HTML as read from a file, which was
+/// saved from the given URL. Unlike [`from_file`], the URL is cited if the
+/// metadata lacks one, and is used to look up the DOI and Zotero item.
+pub fn from_file_with_url(html_path: &str, url: &str, options: &GenerationOptions) -> GenerationResult<Reference> {
+    let parse_info = ParseInfo::from_file_with_url(html_path, url, options)?;
+    let result = create_reference(&parse_info, options, Vec::new(), false)?;
+    Ok(result.reference)
+}
+
+/// Like [`from_file_with_url`], but also returns the attributes of every
+/// metadata type, their [`Provenance`] and any warnings.
+pub fn from_file_with_url_all(html_path: &str, url: &str, options: &GenerationOptions) -> GenerationResult<FullResult> {
+    let parse_info = ParseInfo::from_file_with_url(html_path, url, options)?;
+    create_reference(&parse_info, options, Vec::new(), true)
 }
 
 /// Create [`Reference`] by combining the extracted Open Graph and
-/// Schema.org metadata.
-fn create_reference(parse_info: &ParseInfo, options: &GenerationOptions) -> GenerationResult<Reference> {
+/// Schema.org metadata. The attributes of every metadata type are
+/// only parsed for [`FullResult::sources`] if `include_sources` is set.
+fn create_reference(
+    parse_info: &ParseInfo,
+    options: &GenerationOptions,
+    mut warnings: Vec<String>,
+    include_sources: bool,
+) -> GenerationResult<FullResult> {
+    warnings.extend(parse_info.warnings.iter().cloned());
+
     // Build attribute collection based on configuration
-    let attributes = AttributeCollection::initialize(&options.attribute_config, parse_info);
+    let mut attributes = AttributeCollection::initialize(&options.attribute_config, parse_info);
+
+    // Shells of single-page applications lack metadata until JavaScript runs.
+    if spa::is_spa_shell(parse_info) {
+        warnings.push(spa::JavaScriptRequired.to_string());
+    }
+
+    // Fall back to the AMP version of metadata-poor pages if requested.
+    if options.prefer_amp && amp::is_metadata_poor(&attributes) {
+        if let Some(amp_attributes) = amp::fetch_attributes(parse_info, options) {
+            attributes = amp_attributes.merge(attributes);
+        }
+    }
+
+    // Fediverse posts are described by their ActivityPub object,
+    // which takes precedence over the metadata of the web page.
+    if let Some(post_attributes) = parse_info.url.and_then(|url| fediverse::fetch_attributes(url, &options.request_options)) {
+        attributes = post_attributes.merge(attributes);
+    }
+
+    // Questions on Q&A sites are described by their Schema.org `Question`,
+    // which is more specific than the generic metadata of the page.
+    if parse_info.parsers.contains(&MetadataType::SchemaOrg) {
+        if let Some(question_attributes) = qa_page::find_attributes(parse_info) {
+            attributes = question_attributes.merge(attributes);
+        }
+    }
+
+    // The extraction of datelines is heuristic, so it is only done if requested.
+    if options.extract_dateline {
+        if let Some(place) = dateline::find_place(&parse_info.document) {
+            attributes.attributes.insert(AttributeType::Place, Attribute::Place(place));
+        }
+    }
+
+    // The copyright notice is only a last resort for the publisher.
+    if options.extract_copyright_publisher && attributes.get(AttributeType::Publisher).is_none() {
+        if let Some(publisher) = copyright::find_publisher(&parse_info.document) {
+            attributes.attributes.insert(AttributeType::Publisher, Attribute::Publisher(publisher));
+        }
+    }
+
+    // Syndicated articles are dated by the original rather than the republication.
+    if let Some((date, metadata_type)) = syndication::find_canonical_date(parse_info, &options.attribute_config) {
+        attributes.attributes.insert(AttributeType::Date, date);
+        attributes.provenance.insert(AttributeType::Date, metadata_type);
+    }
+
+    let mut attributes = attributes.transform(&options.attribute_transforms);
+    let mut provenance = attributes.provenance.clone();
+
+    if options.normalize_title_case {
+        if let Some(Attribute::Title(title_str)) = attributes.get(AttributeType::Title) {
+            let normalized = normalize_title_case(title_str);
+            attributes.attributes.insert(AttributeType::Title, Attribute::Title(normalized));
+        }
+    }
 
     let title = attributes.get(AttributeType::Title).cloned();
     let author = attributes.get(AttributeType::Author).cloned();
-    let date = attributes.get(AttributeType::Date).cloned();
-    let language = attributes.get(AttributeType::Locale).cloned();
-    let site = attributes.get(AttributeType::Site).cloned();
+    let mut date = attributes.get(AttributeType::Date).cloned();
+    let original_date = attributes.get(AttributeType::OriginalDate).cloned();
+    let language = attributes.get(AttributeType::Language)
+        .or(attributes.get(AttributeType::Locale))
+        .cloned();
+    if !attributes.attributes.contains_key(&AttributeType::Language) {
+        if let Some(metadata_type) = provenance.get(&AttributeType::Locale).copied() {
+            provenance.insert(AttributeType::Language, metadata_type);
+        }
+    }
+    let mut site = attributes.get(AttributeType::Site).cloned();
+    let section = attributes.get(AttributeType::Section).cloned();
     let url = attributes.get(AttributeType::Url).cloned()
         .or(parse_info.url.map(|x| Attribute::Url(x.to_string()))); // If no URL collected, attempt to use user-supplied URL
     let publisher = attributes.get(AttributeType::Publisher).cloned();
+    let duration = attributes.get(AttributeType::Duration).cloned();
 
-    // Act according to translation options;
-    // if translation fails, None will be the result.
-    let translated_title = translate_title(&title, &options.translation_options).ok();
-
-    // Include archived URL and date according to archive options.
-    let (archive_url, archive_date) = fetch_archive_info(&url, &options.archive_options);
-
-    let reference = Reference::NewsArticle {
-        title,
-        translated_title,
-        author,
-        date,
-        language,
-        url,
-        site,
-        publisher,
-        archive_url,
-        archive_date
+    // Multi-part articles are cited using the URL of their canonical or first page.
+    let url_str = match &url {
+        Some(Attribute::Url(url_str)) => Some(url_str.as_str()),
+        _ => parse_info.url,
+    };
+    let pagination = pagination::detect(&parse_info.document, &parse_info.raw_html, url_str);
+    let page_count = pagination.as_ref().and_then(|p| p.total_pages).map(Attribute::PageCount);
+    if pagination.is_some() {
+        provenance.remove(&AttributeType::Url);
+    }
+    let url = pagination.map(|p| Attribute::Url(p.first_page_url)).or(url);
+    let url = match url {
+        Some(Attribute::Url(url)) if options.normalize_urls => {
+            Some(Attribute::Url(normalize_url(&url, parse_info.url, &options.tracking_parameters)))
+        }
+        url => url,
     };
 
-    Ok(reference)
-}
-
-/// Attempts to translate the provided [`Attribute::Title`].
-/// Returns Option<[`Attribute::TranslatedTitle`]> on if successful and None otherwise.
-fn translate_title(title: &Option<Attribute>, options: &TranslationOptions) -> GenerationResult<Attribute> {
-    // If title parameter is actually an Attribute::Title,
-    // proceed with translation. Otherwise, throw an error.
-    if let Some(Attribute::Title(content)) = title {
-        let text = translate(content, &options)?;
-        let translation_attribute = Attribute::TranslatedTitle(Translation {
-            text,
-            // We can safely unwrap here as the call to translate()
-            // would've already failed if no target language was provided.
-            language: options.target.clone().unwrap(),
-        });
-        Ok(translation_attribute)
-    } else {
-        Err(ReferenceGenerationError::TranslationError)
+    // The host of the URL is only a last resort for the site.
+    if site.is_none() && options.derive_site_from_host {
+        if let Some(Attribute::Url(url)) = &url {
+            site = site_from_url(url).map(Attribute::Site);
+        }
+    }
+
+    // In strict mode, references which cannot be told apart are not generated.
+    if options.strict {
+        let missing: Vec<AttributeType> = [(AttributeType::Title, &title), (AttributeType::Url, &url)]
+            .into_iter()
+            .filter(|(_, attribute)| attribute.is_none())
+            .map(|(attribute_type, _)| attribute_type)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ReferenceGenerationError::InsufficientMetadata(missing));
+        }
     }
+
+    let now = options.clock.unwrap_or_else(Utc::now);
+    let access_date = options.access_date.clone()
+        .unwrap_or_else(|| Date::YearMonthDay(now.date_naive()));
+
+    if let Some(Attribute::Date(date_value)) = &date {
+        if let Some(warning) = check_date(date_value, &access_date) {
+            warnings.push(warning.to_string());
+            if options.drop_implausible_dates {
+                date = None;
+                provenance.remove(&AttributeType::Date);
+            }
+        }
+    }
+
+    // The translation and the archive lookup are independent, so they
+    // are made concurrently, together taking as long as the slower one.
+    let (translations, (archive_url, archive_date, archive_warnings)) = thread::scope(|scope| {
+        // Include archived URL and date according to archive options.
+        let archive = scope.spawn(|| {
+            let mut archive_warnings = Vec::new();
+            let (archive_url, archive_date) = fetch_archive_info(
+                &url,
+                &options.archive_options,
+                &options.request_options,
+                options.response_cache.as_ref(),
+                options.clock,
+                &mut archive_warnings,
+            );
+            (archive_url, archive_date, archive_warnings)
+        });
+        let translations = translate_attributes(&attributes, &options.translation_options, &options.request_options);
+        (translations, archive.join().unwrap())
+    });
+
+    // Act according to translation options; if translation
+    // fails, the attributes are left untranslated.
+    let mut translations = translations.unwrap_or_else(|error| {
+        warnings.push(error.to_string());
+        HashMap::new()
+    });
+    let translated_title = translations.remove(&AttributeType::Title);
+    let translated_site = translations.remove(&AttributeType::Site);
+    warnings.extend(archive_warnings);
+
+    let archive_url = match archive_url {
+        Some(Attribute::ArchiveUrl(archive_url)) if options.normalize_urls => {
+            Some(Attribute::ArchiveUrl(normalize_url(&archive_url, None, &options.tracking_parameters)))
+        }
+        archive_url => archive_url,
+    };
+
+    let access_date = Some(Attribute::AccessDate(access_date));
+    let url_status = archive_url.as_ref().map(|_| Attribute::UrlStatus(options.archive_options.url_status));
+    let content_hash = options
+        .include_content_hash
+        .then(|| Attribute::ContentHash(content_hash(&parse_info.raw_html)));
+
+    let kind = select_reference_kind(&attributes, parse_info);
+    let status = (kind == ReferenceKind::ScholarlyArticle && options.check_doi_updates)
+        .then(|| check_doi_updates(parse_info, options, &mut warnings))
+        .flatten();
+
+    let reference = match kind {
+        ReferenceKind::VideoObject => Reference::VideoObject {
+            title,
+            translated_title,
+            author,
+            date,
+            original_date,
+            language,
+            site,
+            translated_site,
+            url,
+            duration,
+            archive_url,
+            archive_date,
+            url_status,
+            access_date,
+            content_hash,
+        },
+        ReferenceKind::PodcastEpisode => Reference::PodcastEpisode {
+            title,
+            translated_title,
+            author,
+            date,
+            original_date,
+            language,
+            site,
+            translated_site,
+            series: attributes.get(AttributeType::Series).cloned(),
+            episode: attributes.get(AttributeType::Issue).cloned(),
+            url,
+            duration,
+            archive_url,
+            archive_date,
+            url_status,
+            access_date,
+            content_hash,
+        },
+        ReferenceKind::ScholarlyArticle => Reference::ScholarlyArticle {
+            title,
+            translated_title,
+            author,
+            affiliations: attributes.get(AttributeType::Affiliations).cloned(),
+            date,
+            original_date,
+            language,
+            url,
+            journal: attributes.get(AttributeType::Journal).cloned(),
+            volume: attributes.get(AttributeType::Volume).cloned(),
+            issue: attributes.get(AttributeType::Issue).cloned(),
+            pages: attributes.get(AttributeType::Pages).cloned(),
+            publisher,
+            status,
+            archive_url,
+            archive_date,
+            url_status,
+            access_date,
+            content_hash,
+        },
+        ReferenceKind::GenericReference => Reference::GenericReference {
+            title,
+            translated_title,
+            author,
+            date,
+            original_date,
+            language,
+            site,
+            translated_site,
+            section,
+            url,
+            archive_url,
+            archive_date,
+            url_status,
+            access_date,
+            page_count,
+            image_credit: attributes.get(AttributeType::ImageCredit).cloned(),
+            content_hash,
+        },
+        ReferenceKind::NewsArticle => Reference::NewsArticle {
+            title,
+            translated_title,
+            author,
+            date,
+            original_date,
+            language,
+            url,
+            site,
+            translated_site,
+            section,
+            place: attributes.get(AttributeType::Place).cloned(),
+            publisher,
+            archive_url,
+            archive_date,
+            url_status,
+            access_date,
+            page_count,
+            image_credit: attributes.get(AttributeType::ImageCredit).cloned(),
+            content_hash,
+        },
+    };
+
+    Ok(FullResult {
+        reference,
+        sources: match include_sources {
+            true => parse_all_sources(parse_info),
+            false => MultiSourceAttributeCollection::default(),
+        },
+        provenance,
+        warnings,
+    })
 }
 
-/// Translates content according to the provided TranslationOptions.
-fn translate<'a>(content: &'a str, options: &TranslationOptions) -> GenerationResult<String> {
-    let api_key = options.deepl_key.clone().ok_or(ReferenceGenerationError::TranslationError)?;
-    let deepl = DeepL::new(api_key);
+/// Hexadecimal SHA-256 hash of the HTML of the page.
+fn content_hash(raw_html: &str) -> String {
+    Sha256::digest(raw_html.as_bytes()).iter().map(|byte| format!("{byte:02x}")).collect()
+}
 
-    let texts = TranslatableTextList {
-        source_language: options.source.clone(),
-        target_language: options
-            .target
-            .clone()
-            .ok_or(ReferenceGenerationError::TranslationError)?,
-        texts: vec![content.to_string()],
+/// Asks CrossRef about notices updating the DOI of the article, returning
+/// its status, e.g. `Retracted`. Citing a retracted article without saying
+/// so is misleading, so a warning is given for any such notice.
+fn check_doi_updates(parse_info: &ParseInfo, options: &GenerationOptions, warnings: &mut Vec<String>) -> Option<Attribute> {
+    let doi = doi::bibliography_doi(parse_info.bibliography.as_ref()?)?;
+    let update = match doi::fetch_update(&doi, options.response_cache.as_ref(), &options.request_options) {
+        Ok(update) => update?,
+        Err(error) => {
+            warnings.push(format!("Could not check DOI {doi} for retractions: {error}"));
+            return None;
+        }
+    };
+
+    let notice = format!("https://doi.org/{}", update.notice);
+    warnings.push(match update.is_retraction() {
+        true => format!("The article with DOI {doi} has been RETRACTED ({notice}); cite it only as a retracted article"),
+        false => format!("The article with DOI {doi} has a notice updating it: {} ({notice})", update.status),
+    });
+    Some(Attribute::Status(update.status.to_string()))
+}
+
+/// Schema.org types and BibTeX entry types of scholarly articles.
+const SCHOLARLY_TYPES: &[&str] = &["ScholarlyArticle", "MedicalScholarlyArticle", "article"];
+
+/// Schema.org and Open Graph types of pages which are not articles.
+const GENERIC_TYPES: &[&str] = &["WebPage", "WebSite", "ProfilePage", "website", "profile"];
+
+/// Chooses the kind of reference from the collected attributes and the
+/// DOI bibliography entry, if any. Podcast episodes are recognized by their
+/// Schema.org type, as they have a duration like videos. Videos are e.g.
+/// Schema.org `VideoObject`s, Open Graph `video.*` objects or anything
+/// else with a duration. Anything
+/// published in a journal is a scholarly article. Pages without any
+/// indication otherwise are cited as news articles.
+fn select_reference_kind(attributes: &AttributeCollection, parse_info: &ParseInfo) -> ReferenceKind {
+    let object_type = match attributes.get(AttributeType::Type) {
+        Some(Attribute::Type(object_type)) => Some(object_type.as_str()),
+        _ => None,
+    };
+    let bib_article = parse_info.bibliography.as_ref()
+        .and_then(|bib| bib.iter().next())
+        .is_some_and(|entry| entry.entry_type == EntryType::Article);
+
+    let is_podcast = object_type == Some("PodcastEpisode");
+    let is_video = object_type.is_some_and(|t| t == "VideoObject" || t.starts_with("video"))
+        || attributes.get(AttributeType::Duration).is_some();
+    let is_scholarly = object_type.is_some_and(|t| SCHOLARLY_TYPES.contains(&t))
+        || attributes.get(AttributeType::Journal).is_some()
+        || bib_article;
+    let is_generic = object_type.is_some_and(|t| GENERIC_TYPES.contains(&t));
+
+    match (is_video, is_scholarly, is_generic) {
+        _ if is_podcast => ReferenceKind::PodcastEpisode,
+        (true, _, _) => ReferenceKind::VideoObject,
+        (_, true, _) => ReferenceKind::ScholarlyArticle,
+        (_, _, true) => ReferenceKind::GenericReference,
+        _ => ReferenceKind::NewsArticle,
+    }
+}
+
+/// Translates the attributes of the types given by the options, which are
+/// sent to the translation service in a single request. Returns the
+/// translated companions (e.g. [`Attribute::TranslatedTitle`]) by the type
+/// of the attribute translated, which are none if no target language is given.
+fn translate_attributes(
+    attributes: &AttributeCollection,
+    options: &TranslationOptions,
+    request_options: &RequestOptions,
+) -> GenerationResult<HashMap<AttributeType, Attribute>> {
+    let Some(target_language) = options.target.clone() else {
+        return Ok(HashMap::new());
     };
 
-    let translated = deepl.translate(None, texts)?;
-    Ok(translated[0].text.to_owned())
+    let (attribute_types, texts): (Vec<AttributeType>, Vec<String>) = options
+        .attributes
+        .iter()
+        .filter_map(|attribute_type| match attributes.get(*attribute_type) {
+            Some(Attribute::Title(text)) | Some(Attribute::Site(text)) => Some((*attribute_type, text.clone())),
+            _ => None,
+        })
+        .unzip();
+    if texts.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let translated = translate(&texts, options, request_options)?;
+    let translations = attribute_types
+        .into_iter()
+        .zip(translated)
+        .filter_map(|(attribute_type, text)| {
+            let translation = Translation { text, language: target_language.clone() };
+            match attribute_type {
+                AttributeType::Title => Some((attribute_type, Attribute::TranslatedTitle(translation))),
+                AttributeType::Site => Some((attribute_type, Attribute::TranslatedSite(translation))),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok(translations)
+}
+
+/// Translates texts according to the provided TranslationOptions.
+fn translate(texts: &[String], options: &TranslationOptions, request_options: &RequestOptions) -> GenerationResult<Vec<String>> {
+    let options = &options
+        .validate()
+        .map_err(|error| ReferenceGenerationError::TranslationError(error.to_string()))?;
+    let target = options
+        .target
+        .as_deref()
+        .ok_or_else(|| ReferenceGenerationError::TranslationError("No target language given".to_string()))?;
+
+    translation::translator(options, request_options)?.translate(texts, options.source.as_deref(), target)
 }
 
 /// Struct denoting a snapshot returned by the Wayback Machine API.
@@ -318,25 +1156,70 @@ struct WaybackSnapshot {
     timestamp: String,
 }
 
-/// Attempt to fetch archive information from the Wayback Machine and
-/// construct an archive URL and date.
-fn fetch_archive_info(url: &Option<Attribute>, options: &ArchiveOptions) -> (Option<Attribute>, Option<Attribute>) {
+/// Endpoint of the archive.today [Memento] TimeMap, listing all snapshots of a URL.
+///
+/// [Memento]: https://mementoweb.org/guide/rfc/
+const ARCHIVE_TODAY_TIMEMAP: &str = "https://archive.ph/timemap/";
+
+/// Endpoint of the Wayback Machine's availability API.
+const WAYBACK_AVAILABILITY_ENDPOINT: &str = "http://archive.org/wayback/available";
+
+/// Endpoint of the Wayback Machine's [Save Page Now] API.
+///
+/// [Save Page Now]: https://docs.google.com/document/d/1Nsv52MvSjbLb2PCpHlat0gkzw0EvtSgpKHu4mk0MnrA
+const WAYBACK_SAVE_ENDPOINT: &str = "https://web.archive.org/save";
+
+/// Interval at which the status of a Save Page Now job is polled.
+const SAVE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Attempt to fetch archive information from the configured archive
+/// provider and construct an archive URL and date. Snapshots found are
+/// kept in the cache, if provided. Failures to archive the page are
+/// added to the warnings.
+fn fetch_archive_info(
+    url: &Option<Attribute>,
+    options: &ArchiveOptions,
+    request_options: &RequestOptions,
+    cache: Option<&ResponseCache>,
+    clock: Option<DateTime<Utc>>,
+    warnings: &mut Vec<String>,
+) -> (Option<Attribute>, Option<Attribute>) {
     if !options.include_archived {
         return (None, None)
     }
 
     // If URL specified, attempt to fetch archived URL.
     if let Some(Attribute::Url(url_str)) = url {
-        let wayback_snapshot = call_wayback_api(url_str, &None).ok();
-
-        let url_attribute  = wayback_snapshot.as_ref().map(|snapshot| Attribute::ArchiveUrl(snapshot.url.clone()));
-        let date_attribute = wayback_snapshot.as_ref().map(|snapshot| {
-            Attribute::ArchiveDate(
-                Date::DateTime(
-                    parse_wayback_timestamp(&snapshot.timestamp).unwrap() // TODO: Get rid of this unwrap()
-                )
-            )
-        });
+        // With the clock set, the snapshot closest to it is used rather than the latest.
+        let timestamp = clock.map(|clock| clock.format("%Y%m%d%H%M%S").to_string());
+        let mut snapshot = match options.provider {
+            ArchiveProvider::WaybackMachine => call_wayback_api(url_str, &timestamp.as_deref(), WAYBACK_AVAILABILITY_ENDPOINT, cache, request_options)
+                .ok()
+                .and_then(|snapshot| {
+                    let datetime = parse_wayback_timestamp(&snapshot.timestamp).ok()?;
+                    Some((snapshot.url, datetime))
+                }),
+            ArchiveProvider::ArchiveToday => call_archive_today_api(url_str, ARCHIVE_TODAY_TIMEMAP, cache, request_options).ok(),
+        };
+
+        // Archive the page if no snapshot exists yet. Failing to do so
+        // leaves out the archive attributes rather than failing generation.
+        if snapshot.is_none() && options.perform_archival && options.provider == ArchiveProvider::WaybackMachine {
+            match save_page_now(
+                url_str,
+                WAYBACK_SAVE_ENDPOINT,
+                options.archival_timeout,
+                SAVE_POLL_INTERVAL,
+                request_options,
+            ) {
+                Ok(saved) => snapshot = Some(saved),
+                Err(error) => warnings.push(ReferenceGenerationError::ArchivalError(error).to_string()),
+            }
+        }
+
+        let snapshot = snapshot.filter(|(archive_url, _)| Attribute::ArchiveUrl(archive_url.clone()).is_allowed());
+        let url_attribute  = snapshot.as_ref().map(|(archive_url, _)| Attribute::ArchiveUrl(archive_url.clone()));
+        let date_attribute = snapshot.map(|(_, datetime)| Attribute::ArchiveDate(Date::DateTime(datetime)));
 
         return (url_attribute, date_attribute)
     } 
@@ -344,19 +1227,147 @@ fn fetch_archive_info(url: &Option<Attribute>, options: &ArchiveOptions) -> (Opt
     (None, None)
 }
 
+/// Response to the submission of a Save Page Now job.
+#[derive(Debug, Deserialize)]
+struct SaveJob {
+    job_id: String,
+}
+
+/// Status of a Save Page Now job. The timestamp and original URL
+/// are only present once the job has succeeded.
+#[derive(Debug, Deserialize)]
+struct SaveJobStatus {
+    status: String,
+    timestamp: Option<String>,
+    original_url: Option<String>,
+    message: Option<String>,
+}
+
+/// Submit a URL to the Wayback Machine's Save Page Now API and poll the
+/// job until the capture is finished, returning the URL and date of the
+/// new snapshot.
+fn save_page_now(
+    url: &str,
+    save_endpoint: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+    request_options: &RequestOptions,
+) -> Result<(String, DateTime<Utc>), ArchiveError> {
+    let header = Some("Accept: application/json");
+    let encoded_url: String = byte_serialize(url.as_bytes()).collect();
+    let response = curl::post(save_endpoint, &format!("url={encoded_url}"), header, request_options)?;
+    let job: SaveJob = serde_json::from_str(&response)?;
+
+    let started = Instant::now();
+    loop {
+        let response = curl::get(&format!("{save_endpoint}/status/{}", job.job_id), header, false, request_options)?;
+        let status: SaveJobStatus = serde_json::from_str(&response)?;
+
+        match status.status.as_str() {
+            "success" => {
+                let timestamp = status.timestamp.ok_or(ArchiveError::NoSnapshot)?;
+                let original_url = status.original_url.unwrap_or(url.to_string());
+                let datetime = parse_wayback_timestamp(&timestamp).map_err(|_| ArchiveError::NoSnapshot)?;
+                return Ok((format!("https://web.archive.org/web/{timestamp}/{original_url}"), datetime));
+            }
+            "pending" => (),
+            _ => return Err(ArchiveError::SaveFailed(status.message.unwrap_or(status.status))),
+        }
+
+        if started.elapsed() + poll_interval > timeout {
+            return Err(ArchiveError::SaveTimeout);
+        }
+        thread::sleep(poll_interval);
+    }
+}
+
+/// Retrieves the latest Wayback Machine snapshot of a page blocking its
+/// content, if snapshots are included and it is not blocked itself. The
+/// snapshot is requested as archived, i.e. without the Wayback Machine's
+/// toolbar, and associated with the original URL so that it is cited.
+fn fetch_archived_content<'a>(url: &'a str, options: &GenerationOptions) -> Option<ParseInfo<'a>> {
+    let archive_options = &options.archive_options;
+    if !archive_options.include_archived || archive_options.provider != ArchiveProvider::WaybackMachine {
+        return None;
+    }
+
+    let cache = options.response_cache.as_ref();
+    let snapshot = call_wayback_api(url, &None, WAYBACK_AVAILABILITY_ENDPOINT, cache, &options.request_options).ok()?;
+    let timestamp = &snapshot.timestamp;
+    let snapshot_url = snapshot.url.replacen(&format!("/{timestamp}/"), &format!("/{timestamp}id_/"), 1);
+    let language = options.preferred_content_language.as_deref();
+    let raw_html = curl::get_html(&snapshot_url, language, &options.request_options).ok()?;
+    let parse_info = ParseInfo::from_html(url, raw_html, options).ok()?;
+    (!interstitial::is_interstitial(&parse_info, &options.blocked_content_patterns)).then_some(parse_info)
+}
+
 /// Send a query for a URL to the Wayback Machine API and return the closest snapshot.
-fn call_wayback_api(url: &str, timestamp_option: &Option<&str>) -> Result<WaybackSnapshot, ArchiveError> {
+fn call_wayback_api(
+    url: &str,
+    timestamp_option: &Option<&str>,
+    availability_endpoint: &str,
+    cache: Option<&ResponseCache>,
+    request_options: &RequestOptions,
+) -> Result<WaybackSnapshot, ArchiveError> {
     // If timestamp provided, fetch the archived URL closest to the timestamp.
     let timestamp = timestamp_option.unwrap_or_default();
-    let request_url = format!("http://archive.org/wayback/available?url={url}&timestamp={timestamp}");
-    let response = curl::get(&request_url, None, false)?;
-    
+    let request_url = format!("{availability_endpoint}?url={url}&timestamp={timestamp}");
+    let key = format!("wayback:{url}@{timestamp}");
+    let cached = cache.and_then(|cache| cache.load(&key));
+    let response = match &cached {
+        Some(response) => response.clone(),
+        None => curl::get(&request_url, None, false, request_options)?,
+    };
+
     // Extract snapshot information for the closest retrieved snapshot.
     let snapshot_info = &serde_json::from_str::<Value>(&response)?["archived_snapshots"]["closest"];
 
     // Attempt to deserialize the snapshot information to a [`WaybackSnapshot`] struct.
-    serde_json::from_value(snapshot_info.clone())
-        .map_err(|err| ArchiveError::DeserializeError(err))
+    let snapshot = serde_json::from_value(snapshot_info.clone())
+        .map_err(ArchiveError::DeserializeError)?;
+
+    // Only snapshots are cached, so that pages archived later are found.
+    if let (Some(cache), None) = (cache, &cached) {
+        cache.store(&key, &response);
+    }
+    Ok(snapshot)
+}
+
+/// Query the archive.today TimeMap for a URL and return the URL and date
+/// of the most recent snapshot. The TimeMap is in the link format, with one
+/// entry per line, e.g.
+/// `<https://archive.ph/20240301120000/https://example.com/>; rel="last memento"; datetime="Fri, 01 Mar 2024 12:00:00 GMT",`
+fn call_archive_today_api(
+    url: &str,
+    timemap_endpoint: &str,
+    cache: Option<&ResponseCache>,
+    request_options: &RequestOptions,
+) -> Result<(String, DateTime<Utc>), ArchiveError> {
+    let key = format!("archive-today:{url}");
+    let cached = cache.and_then(|cache| cache.load(&key));
+    let response = match &cached {
+        Some(response) => response.clone(),
+        // archive.today responds with 404 to URLs without snapshots.
+        None => match curl::get(&format!("{timemap_endpoint}{url}"), None, true, request_options) {
+            Err(CurlError::HttpStatus(404)) => return Err(ArchiveError::NoSnapshot),
+            response => response?,
+        },
+    };
+    let re = Regex::new(r#"<([^>]+)>;\s*rel="[^"]*\bmemento\b[^"]*";\s*datetime="([^"]+)""#).unwrap();
+
+    let snapshot = re
+        .captures_iter(&response)
+        .filter_map(|captures| {
+            let datetime = DateTime::parse_from_rfc2822(&captures[2]).ok()?;
+            Some((captures[1].to_string(), datetime.with_timezone(&Utc)))
+        })
+        .max_by_key(|(_, datetime)| *datetime)
+        .ok_or(ArchiveError::NoSnapshot)?;
+
+    if let (Some(cache), None) = (cache, &cached) {
+        cache.store(&key, &response);
+    }
+    Ok(snapshot)
 }
 
 /// Utility function to parse a timestamp from snapshots 
@@ -371,11 +1382,38 @@ fn parse_wayback_timestamp(timestamp: &str) -> Result<DateTime<Utc>, ParseError>
 }
 #[cfg(test)]
 mod test {
-    use crate::attribute::Attribute;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use crate::attribute::{Attribute, AttributeType, Author, Date, UrlStatus};
+    use crate::mock_client::MockClient;
+    use crate::mock_server::{MockServer, Response};
+    use crate::{CurlClient, GenerationOptions, Reference};
+
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
 
     use super::{
-        attribute_config::{AttributeConfig, AttributePriority}, fetch_archive_info, ArchiveOptions, MetadataType
+        attribute_config::{AttributeConfig, AttributePriority}, call_archive_today_api, fetch_archive_info, from_url,
+        from_url_all, from_url_with_provenance, save_page_now, translate_attributes, ArchiveError, ArchiveOptions, AttributeTransform,
+        MetadataType, ReferenceGenerationError, RequestOptions, TranslationOptions, TranslationProvider, UnknownMetadataType
     };
+    use strum::IntoEnumIterator;
+    use crate::parser::AttributeCollection;
+
+    #[test]
+    fn metadata_type_names_round_trip() {
+        for metadata_type in MetadataType::iter() {
+            let name = metadata_type.to_string();
+            assert_eq!(name.parse::<MetadataType>(), Ok(metadata_type));
+            assert_eq!(name.to_uppercase().parse::<MetadataType>(), Ok(metadata_type));
+        }
+        assert_eq!("schema-org".parse::<MetadataType>(), Ok(MetadataType::SchemaOrg));
+        assert_eq!("htmlmeta".parse::<MetadataType>(), Ok(MetadataType::HtmlMeta));
+        assert_eq!("microdata".parse::<MetadataType>(), Err(UnknownMetadataType("microdata".to_string())));
+    }
 
     #[test]
     fn test_get_unique_parsers() {
@@ -393,7 +1431,7 @@ mod test {
     // this test must be changed to match.
     #[test]
     fn test_attribute_config_default() {
-        let expected = vec![MetadataType::OpenGraph, MetadataType::SchemaOrg];
+        let expected = [MetadataType::OpenGraph, MetadataType::SchemaOrg, MetadataType::DublinCore, MetadataType::Rdfa, MetadataType::HtmlMeta];
         let config = AttributeConfig::default();
         let result = config.parsers_used();
 
@@ -401,6 +1439,17 @@ mod test {
         assert!(expected.iter().all(|item| result.contains(item)));
     }
 
+    #[test]
+    fn test_attribute_config_override() {
+        let base = AttributeConfig::new(AttributePriority::new(&[MetadataType::SchemaOrg]));
+        let config = base.with_override(AttributeType::Date, AttributePriority::new(&[MetadataType::Doi]));
+
+        let date_priority = config.get(AttributeType::Date).clone().unwrap().priority;
+        let title_priority = config.get(AttributeType::Title).clone().unwrap().priority;
+        assert_eq!(date_priority, vec![MetadataType::Doi]);
+        assert_eq!(title_priority, vec![MetadataType::SchemaOrg]);
+    }
+
     #[test]
     fn test_archive_url() {
         let url = "https://www.information.dk/kultur/2018/01/casper-mandrilaftalen-burde-lade-goere-gjorde";
@@ -408,7 +1457,7 @@ mod test {
         let archive_options = ArchiveOptions::default();
         
         // Timestamp is difficult to test for, so it is not needed for now.
-        let (url_result, _) = fetch_archive_info(&url_attribute, &archive_options);
+        let (url_result, _) = fetch_archive_info(&url_attribute, &archive_options, &RequestOptions::default(), None, None, &mut Vec::new());
         
         let expected_archive_url = "http://web.archive.org/web/20211026003805/https://www.information.dk/kultur/2018/01/casper-mandrilaftalen-burde-lade-goere-gjorde";
         let expected_archive_url_attribute = Some(Attribute::ArchiveUrl(expected_archive_url.to_string()));
@@ -422,11 +1471,532 @@ mod test {
         let url_attribute = Some(Attribute::Url(url.to_string()));
         let archive_options = ArchiveOptions {
             include_archived: false,
-            perform_archival: false
+            ..Default::default()
         };
         
         // Timestamp is difficult to test for, so it is not needed for now.
-        let (url_result, _) = fetch_archive_info(&url_attribute, &archive_options);
+        let (url_result, _) = fetch_archive_info(&url_attribute, &archive_options, &RequestOptions::default(), None, None, &mut Vec::new());
         assert_eq!(url_result, None);
     }
+
+    #[test]
+    fn test_archive_today() {
+        let timemap = concat!(
+            "<https://example.com/story>; rel=\"original\",\n",
+            "<https://archive.ph/timegate/https://example.com/story>; rel=\"timegate\",\n",
+            "<https://archive.ph/20230105080000/https://example.com/story>; rel=\"first memento\"; datetime=\"Thu, 05 Jan 2023 08:00:00 GMT\",\n",
+            "<https://archive.ph/20240301120000/https://example.com/story>; rel=\"last memento\"; datetime=\"Fri, 01 Mar 2024 12:00:00 GMT\",\n",
+        );
+        let server = MockServer::serve(vec![
+            ("/timemap/https://example.com/story", Response::ok(timemap)),
+        ]);
+
+        let (archive_url, datetime) = call_archive_today_api("https://example.com/story", &server.url("/timemap/"), None, &RequestOptions::default()).unwrap();
+        assert_eq!(archive_url, "https://archive.ph/20240301120000/https://example.com/story");
+        assert_eq!(datetime.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_archive_today_no_snapshots() {
+        let server = MockServer::serve(vec![]);
+
+        let result = call_archive_today_api("https://example.com/story", &server.url("/timemap/"), None, &RequestOptions::default());
+        assert!(matches!(result, Err(ArchiveError::NoSnapshot)));
+    }
+
+    #[test]
+    fn test_access_date() {
+        let page = r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#;
+        let server = MockServer::serve(vec![("/news/harbour", Response::ok(page))]);
+        let url = server.url("/news/harbour");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            access_date: Some(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())),
+            ..Default::default()
+        };
+        let reference = from_url(&url, &options).unwrap();
+
+        assert!(reference.wiki().contains("|access-date=2024-03-01"));
+        assert!(reference.bibtex().contains("urldate = \"2024-03-01\""));
+    }
+
+    #[test]
+    fn test_clock() {
+        let page = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle", "datePublished": "2024-06-01"}
+            </script>
+            </head></html>"#;
+        let server = MockServer::serve(vec![("/news/harbour", Response::ok(page))]);
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            clock: Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()),
+            drop_implausible_dates: true,
+            ..Default::default()
+        };
+        let Reference::NewsArticle { date, access_date, .. } = from_url(&server.url("/news/harbour"), &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(access_date, Some(Attribute::AccessDate(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()))));
+        // Published after the time given by the clock
+        assert_eq!(date, None);
+    }
+
+    #[test]
+    fn test_attribute_transforms() {
+        let page = r#"<html><head>
+            <meta property="og:title" content="Fishing quotas cut">
+            <meta property="og:site_name" content="northernledger.example.org">
+            </head></html>"#;
+        let server = MockServer::serve(vec![("/news/quotas", Response::ok(page))]);
+
+        let rename_site: AttributeTransform = Arc::new(|attribute| match attribute {
+            Attribute::Site(site) if site == "northernledger.example.org" => Attribute::Site("The Northern Ledger".to_string()),
+            other => other,
+        });
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            attribute_transforms: HashMap::from([(AttributeType::Site, rename_site)]),
+            ..Default::default()
+        };
+        let Reference::NewsArticle { title, site, .. } = from_url(&server.url("/news/quotas"), &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Fishing quotas cut".to_string())));
+        assert_eq!(site, Some(Attribute::Site("The Northern Ledger".to_string())));
+    }
+
+    #[test]
+    fn test_http_error_status() {
+        let page = r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#;
+        let not_found = r#"<html><head><title>Page not found</title></head></html>"#;
+        let server = MockServer::serve(vec![
+            ("/news/harbour", Response::ok(page)),
+            ("/news/harbour-expansion", Response::redirect("/news/harbour")),
+            ("/news/missing", Response::status(404).with_body(not_found)),
+            ("/news/down", Response::status(503)),
+        ]);
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            ..Default::default()
+        };
+
+        let result = from_url(&server.url("/news/missing"), &options);
+        assert!(matches!(result, Err(ReferenceGenerationError::HttpStatus { status: 404, .. })));
+        let error = from_url(&server.url("/news/down"), &options).unwrap_err();
+        assert!(matches!(error, ReferenceGenerationError::HttpStatus { status: 503, .. }));
+        assert_eq!(error.url(), Some(server.url("/news/down").as_str()));
+
+        let Reference::NewsArticle { title, .. } = from_url(&server.url("/news/harbour-expansion"), &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+    }
+
+    #[test]
+    fn test_drop_implausible_dates() {
+        let page = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle", "datePublished": "2099-01-01"}
+            </script>
+            </head></html>"#;
+        let server = MockServer::serve(vec![("/news/harbour", Response::ok(page))]);
+        let url = server.url("/news/harbour");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            access_date: Some(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())),
+            ..Default::default()
+        };
+        let Reference::NewsArticle { date, .. } = from_url(&url, &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert!(date.is_some());
+
+        let options = GenerationOptions { drop_implausible_dates: true, ..options };
+        let Reference::NewsArticle { date, .. } = from_url(&url, &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(date, None);
+    }
+
+    #[test]
+    fn test_save_page_now() {
+        let polls = AtomicUsize::new(0);
+        let server = MockServer::start(move |request| match request.path.as_str() {
+            "/save" if request.body == "url=https%3A%2F%2Fexample.com%2Fstory" => {
+                Response::ok(r#"{"url": "https://example.com/story", "job_id": "spn2-1234"}"#)
+            }
+            "/save/status/spn2-1234" if polls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                Response::ok(r#"{"status": "pending"}"#)
+            }
+            "/save/status/spn2-1234" => Response::ok(
+                r#"{"status": "success", "timestamp": "20240301120000", "original_url": "https://example.com/story"}"#,
+            ),
+            _ => Response::status(404),
+        });
+
+        let (archive_url, datetime) = save_page_now(
+            "https://example.com/story",
+            &server.url("/save"),
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+            &RequestOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(archive_url, "https://web.archive.org/web/20240301120000/https://example.com/story");
+        assert_eq!(datetime.to_rfc3339(), "2024-03-01T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_save_page_now_timeout() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/save" => Response::ok(r#"{"url": "https://example.com/story", "job_id": "spn2-1234"}"#),
+            _ => Response::ok(r#"{"status": "pending"}"#),
+        });
+
+        let result = save_page_now(
+            "https://example.com/story",
+            &server.url("/save"),
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+            &RequestOptions::default(),
+        );
+        assert!(matches!(result, Err(ArchiveError::SaveTimeout)));
+    }
+
+    #[test]
+    fn test_save_page_now_failure() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/save" => Response::ok(r#"{"url": "https://example.com/story", "job_id": "spn2-1234"}"#),
+            _ => Response::ok(r#"{"status": "error", "message": "Live page is not available: 404"}"#),
+        });
+
+        let result = save_page_now(
+            "https://example.com/story",
+            &server.url("/save"),
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+            &RequestOptions::default(),
+        );
+        assert!(matches!(result, Err(ArchiveError::SaveFailed(message)) if message == "Live page is not available: 404"));
+    }
+
+    fn timeout_options() -> GenerationOptions {
+        GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions {
+                connect_timeout: Duration::from_millis(500),
+                timeout: Duration::from_secs(1),
+                retries: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unroutable_address_times_out() {
+        let started = Instant::now();
+        let result = from_url("http://10.255.255.1/news/harbour", &timeout_options());
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_unresponsive_server_times_out() {
+        let server = MockServer::start(|_| {
+            thread::sleep(Duration::from_secs(10));
+            Response::ok("<html></html>")
+        });
+
+        let started = Instant::now();
+        let result = from_url(&server.url("/news/harbour"), &timeout_options());
+        assert!(matches!(result, Err(ReferenceGenerationError::CurlError(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_request_retried() {
+        let page = r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#;
+        let requests = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| match requests.fetch_add(1, Ordering::SeqCst) {
+            0 => Response::status(503),
+            _ => Response::ok(page),
+        });
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { retry_backoff: Duration::from_millis(10), ..Default::default() },
+            ..Default::default()
+        };
+        let Reference::NewsArticle { title, .. } = from_url(&server.url("/news/harbour"), &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+    }
+
+    #[test]
+    fn test_shared_http_client() {
+        let page = r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#;
+        let server = MockServer::serve(vec![("/news/harbour", Response::ok(page)), ("/news/bridge", Response::ok(page))]);
+
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::OpenGraph])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(Arc::new(CurlClient::new())), ..Default::default() },
+            ..Default::default()
+        };
+        from_url(&server.url("/news/harbour"), &options).unwrap();
+        from_url(&server.url("/news/bridge"), &options).unwrap();
+        assert_eq!(server.connection_count(), 1);
+
+        // Without a shared client, every generation opens a new connection.
+        let options = GenerationOptions { request_options: RequestOptions::default(), ..options };
+        from_url(&server.url("/news/harbour"), &options).unwrap();
+        assert_eq!(server.connection_count(), 2);
+    }
+
+    #[test]
+    fn test_lookups_cached() {
+        let page_url = "https://coastalstudies.example.org/articles/17";
+        let client = Arc::new(MockClient::new(&[
+            (page_url, r#"<html><head><meta property="og:title" content="Sediment transport"></head>
+                          <body>doi:10.5555/coastal.2024.17</body></html>"#),
+            ("https://doi.org/10.5555/coastal.2024.17", "@article{Jensen_2024, title={Sediment transport}, journal={Journal of Coastal Studies}, year={2024}}"),
+            ("https://citoid.example.org/api/", r#"[{"itemType": "journalArticle", "title": "Sediment transport"}]"#),
+            ("http://archive.org/wayback/available", r#"{"archived_snapshots": {"closest": {"status": "200", "available": true,
+                "url": "http://web.archive.org/web/20240301000000/https://coastalstudies.example.org/articles/17", "timestamp": "20240301000000"}}}"#),
+        ]));
+        let directory = std::env::temp_dir().join(format!("url2ref-lookups-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi, MetadataType::Zotero, MetadataType::OpenGraph])),
+            citoid_endpoint: Some("https://citoid.example.org/api/".to_string()),
+            response_cache: Some(super::ResponseCache::new(&directory, Duration::from_secs(3600))),
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        let first = from_url(page_url, &options).unwrap();
+        assert_eq!(client.requested().len(), 4);
+        let Reference::ScholarlyArticle { archive_url, .. } = &first else {
+            panic!("Expected a scholarly article")
+        };
+        assert!(archive_url.is_some());
+
+        // Only the page itself is requested again.
+        client.clear_requested();
+        let second = from_url(page_url, &options).unwrap();
+        assert_eq!(client.requested(), [page_url]);
+        assert_eq!(second, first);
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_lookups_concurrent() {
+        let page_url = "https://coastalstudies.example.org/articles/17";
+        let delay = Duration::from_millis(300);
+        let client = Arc::new(MockClient::new(&[
+            (page_url, r#"<html><head><meta property="og:title" content="Sedimenttransport"></head>
+                          <body>doi:10.5555/coastal.2024.17</body></html>"#),
+            ("https://doi.org/10.5555/coastal.2024.17", "@article{Jensen_2024, title={Sedimenttransport}, journal={Kystforskning}, year={2024}}"),
+            ("https://citoid.example.org/api/", r#"[{"itemType": "journalArticle", "title": "Sedimenttransport"}]"#),
+            ("http://archive.org/wayback/available", r#"{"archived_snapshots": {"closest": {"status": "200", "available": true,
+                "url": "http://web.archive.org/web/20240301000000/https://coastalstudies.example.org/articles/17", "timestamp": "20240301000000"}}}"#),
+            ("https://translation.googleapis.com/", r#"{"data": {"translations": [{"translatedText": "Sediment transport"}]}}"#),
+        ]).with_delay(delay));
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi, MetadataType::Zotero, MetadataType::OpenGraph])),
+            citoid_endpoint: Some("https://citoid.example.org/api/".to_string()),
+            translation_options: TranslationOptions {
+                target: Some("en".to_string()),
+                provider: super::TranslationProvider::Google,
+                google_key: Some("secret".to_string()),
+                ..Default::default()
+            },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let reference = from_url(page_url, &options).unwrap();
+        let elapsed = started.elapsed();
+
+        // The DOI and Zotero lookups, and the archive lookup and the translation,
+        // are made at the same time, so five requests take as long as three.
+        assert_eq!(client.requested().len(), 5);
+        assert!(elapsed < delay * 4, "Took {elapsed:?}");
+        assert_eq!(reference.translated_title().map(|translation| translation.text.as_str()), Some("Sediment transport"));
+    }
+
+    #[test]
+    fn test_translate_attributes() {
+        let mut attributes = AttributeCollection { attributes: HashMap::new(), provenance: HashMap::new() };
+        attributes.attributes.insert(AttributeType::Title, Attribute::Title("Ночные поезда".to_string()));
+        attributes.attributes.insert(AttributeType::Site, Attribute::Site("Коммерсантъ".to_string()));
+
+        // Nothing is translated without a target language.
+        let options = TranslationOptions { attributes: vec![AttributeType::Title, AttributeType::Site], ..Default::default() };
+        let translations = translate_attributes(&attributes, &options, &RequestOptions::default()).unwrap();
+        assert!(translations.is_empty());
+
+        let options = TranslationOptions { target: Some("en".to_string()), ..options };
+        let Err(ReferenceGenerationError::TranslationError(message)) =
+            translate_attributes(&attributes, &options, &RequestOptions::default())
+        else {
+            panic!("Expected a translation error")
+        };
+        assert_eq!(message, "No DeepL API key given");
+    }
+
+    #[test]
+    fn test_generate_all_from_single_fetch() {
+        let page = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <title>Harbour expansion approved | Coastal Times</title>
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle",
+             "headline": "Council approves harbour expansion", "datePublished": "2099-01-01T08:00:00Z",
+             "author": [{"@type": "Person", "name": "Jane Doe"}]}
+            </script>
+            </head><body></body></html>"#;
+        let requests = Arc::new(AtomicUsize::new(0));
+        let received = requests.clone();
+        let server = MockServer::start(move |_| {
+            received.fetch_add(1, Ordering::SeqCst);
+            Response::ok(page)
+        });
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            ..Default::default()
+        };
+        let result = from_url_all(&server.url("/news/harbour"), &options).unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let Reference::NewsArticle { title, author, .. } = result.reference else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+        assert_eq!(author, Some(Attribute::Authors(vec![Author::Person("Jane Doe".to_string())])));
+        assert_eq!(
+            result.sources.get(MetadataType::SchemaOrg, AttributeType::Title),
+            Some(&Attribute::Title("Council approves harbour expansion".to_string()))
+        );
+        assert_eq!(
+            result.sources.get(MetadataType::HtmlMeta, AttributeType::Title),
+            Some(&Attribute::Title("Harbour expansion approved | Coastal Times".to_string()))
+        );
+        assert_eq!(result.provenance.get(&AttributeType::Title), Some(&MetadataType::OpenGraph));
+        assert_eq!(result.provenance.get(&AttributeType::Author), Some(&MetadataType::SchemaOrg));
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("2099"));
+    }
+
+    #[test]
+    fn test_javascript_url_dropped() {
+        let page = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <meta property="og:url" content="javascript:alert(document.cookie)">
+            </head><body></body></html>"#;
+        let server = MockServer::serve(vec![("/news/harbour", Response::ok(page))]);
+
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::OpenGraph])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            ..Default::default()
+        };
+        let (reference, provenance) = from_url_with_provenance(&server.url("/news/harbour"), &options).unwrap();
+        let Reference::NewsArticle { url, .. } = reference else {
+            panic!("Expected a news article")
+        };
+        // The URL the page was requested from is cited instead.
+        assert_eq!(url, Some(Attribute::Url(server.url("/news/harbour"))));
+        assert_eq!(provenance.get(&AttributeType::Url), None);
+
+        assert!(!Attribute::Url("data:text/html;base64,PHNjcmlwdD4=".to_string()).is_allowed());
+        assert!(Attribute::ArchiveUrl("https://web.archive.org/web/2024/https://example.com".to_string()).is_allowed());
+        assert!(Attribute::Url("example.com/news/harbour".to_string()).is_allowed());
+    }
+
+    #[test]
+    fn test_prefer_amp() {
+        let main_page = r#"<html><head><link rel="amphtml" href="/story/amp"></head><body></body></html>"#;
+        let amp_page = r#"<html><head>
+            <meta property="og:title" content="Title from AMP">
+            <meta property="og:url" content="https://example.com/story/amp">
+            </head><body></body></html>"#;
+        let server = MockServer::serve(vec![
+            ("/story", Response::ok(main_page)),
+            ("/story/amp", Response::ok(amp_page)),
+        ]);
+        let url = server.url("/story");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            prefer_amp: true,
+            ..Default::default()
+        };
+        let reference = from_url(&url, &options).unwrap();
+
+        let Reference::NewsArticle { title, url: cited_url, .. } = reference else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Title from AMP".to_string())));
+        assert_eq!(cited_url, Some(Attribute::Url(url)));
+    }
+
+    #[test]
+    fn test_options_from_toml() {
+        let config = r#"
+            normalize_title_case = true
+            tracking_parameters = ["utm_*", "ref"]
+            access_date = { precision = "date", value = "2024-10-11" }
+
+            [translation_options]
+            target = "en"
+            provider = "Google"
+            attributes = ["Title", "Site"]
+
+            [archive_options]
+            include_archived = false
+            archival_timeout = 90
+            url_status = "dead"
+
+            [request_options]
+            timeout = 12
+            retry_backoff = 0.25
+
+            [attribute_config.title]
+            priority = ["SchemaOrg", "OpenGraph"]
+        "#;
+
+        let options = GenerationOptions::from_toml_str(config).unwrap();
+        assert!(options.normalize_title_case);
+        assert_eq!(options.tracking_parameters, ["utm_*", "ref"]);
+        assert_eq!(options.access_date, Some(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 10, 11).unwrap())));
+        assert_eq!(options.translation_options.provider, TranslationProvider::Google);
+        assert_eq!(options.translation_options.attributes, [AttributeType::Title, AttributeType::Site]);
+        assert!(!options.archive_options.include_archived);
+        assert_eq!(options.archive_options.archival_timeout, Duration::from_secs(90));
+        assert_eq!(options.archive_options.url_status, UrlStatus::Dead);
+        assert_eq!(options.request_options.timeout, Duration::from_secs(12));
+        assert_eq!(options.request_options.retry_backoff, Duration::from_millis(250));
+        assert_eq!(options.attribute_config.title.as_ref().unwrap().priority, [MetadataType::SchemaOrg, MetadataType::OpenGraph]);
+        // Options left out keep their defaults.
+        assert!(options.normalize_urls);
+        assert_eq!(options.request_options.connect_timeout, Duration::from_secs(10));
+
+        // The options are written out as they were read.
+        let written = options.to_toml_string().unwrap();
+        let reread = GenerationOptions::from_toml_str(&written).unwrap();
+        assert_eq!(reread.to_toml_string().unwrap(), written);
+        assert_eq!(reread.request_options.retry_backoff, Duration::from_millis(250));
+    }
 }
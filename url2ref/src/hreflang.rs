@@ -0,0 +1,113 @@
+//! Support for citing a specific language edition of multilingual sites,
+//! which advertise their editions using `<link rel="alternate" hreflang>`.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Returns the primary subtag of a language tag, e.g. `de` for `de-AT`.
+fn primary_subtag(language: &str) -> &str {
+    language.split(['-', '_']).next().unwrap_or(language)
+}
+
+/// Finds the absolute URL of the alternate of the page in the preferred
+/// language. Exact matches of the language tag are preferred over matches
+/// of the primary subtag only. Returns `None` if the page itself is the
+/// alternate found.
+pub fn find_alternate_url(document: &Html, url: &str, language: &str) -> Option<String> {
+    let selector = Selector::parse("link[rel~=\"alternate\"][hreflang][href]").unwrap();
+    let alternates: Vec<(&str, &str)> = document
+        .select(&selector)
+        .filter_map(|link| Some((link.value().attr("hreflang")?, link.value().attr("href")?)))
+        .collect();
+
+    let exact = alternates.iter().find(|(hreflang, _)| hreflang.eq_ignore_ascii_case(language));
+    let primary = alternates
+        .iter()
+        .find(|(hreflang, _)| primary_subtag(hreflang).eq_ignore_ascii_case(primary_subtag(language)));
+    let (_, href) = exact.or(primary)?;
+
+    let base = Url::parse(url).ok()?;
+    let alternate_url = base.join(href).ok()?;
+    (alternate_url != base).then(|| alternate_url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::find_alternate_url;
+    use crate::attribute::Attribute;
+    use crate::generator::{from_url, ArchiveOptions};
+    use crate::mock_server::{MockServer, Response};
+    use crate::{GenerationOptions, Reference};
+
+    fn options(language: &str) -> GenerationOptions {
+        GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            preferred_content_language: Some(language.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn title(reference: &Reference) -> Option<Attribute> {
+        let Reference::NewsArticle { title, .. } = reference else {
+            panic!("Expected a news article")
+        };
+        title.clone()
+    }
+
+    #[test]
+    fn alternate_url_selection() {
+        let html = r#"<html><head>
+            <link rel="alternate" hreflang="en" href="/en/story">
+            <link rel="alternate" hreflang="de-AT" href="/at/story">
+            <link rel="alternate" hreflang="de" href="/de/story">
+            </head></html>"#;
+        let html = Html::parse_document(html);
+        let url = "https://example.com/en/story";
+
+        assert_eq!(find_alternate_url(&html, url, "de"), Some("https://example.com/de/story".to_string()));
+        assert_eq!(find_alternate_url(&html, url, "de-CH"), Some("https://example.com/at/story".to_string()));
+        assert_eq!(find_alternate_url(&html, url, "en"), None);
+        assert_eq!(find_alternate_url(&html, url, "fr"), None);
+    }
+
+    #[test]
+    fn accept_language_edition() {
+        let server = MockServer::start(|request| {
+            let title = match request.header("Accept-Language") {
+                Some("de") => "Hafenerweiterung genehmigt",
+                _ => "Harbour expansion approved",
+            };
+            Response::ok(format!(r#"<html><head><meta property="og:title" content="{title}"></head></html>"#))
+        });
+        let url = server.url("/news/harbour");
+
+        let reference = from_url(&url, &options("de")).unwrap();
+        assert_eq!(title(&reference), Some(Attribute::Title("Hafenerweiterung genehmigt".to_string())));
+
+        let reference = from_url(&url, &options("en")).unwrap();
+        assert_eq!(title(&reference), Some(Attribute::Title("Harbour expansion approved".to_string())));
+    }
+
+    #[test]
+    fn hreflang_alternate_edition() {
+        let english = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <link rel="alternate" hreflang="de" href="/de/harbour">
+            </head></html>"#;
+        let german = r#"<html><head>
+            <meta property="og:title" content="Hafenerweiterung genehmigt">
+            </head></html>"#;
+        let server = MockServer::serve(vec![
+            ("/en/harbour", Response::ok(english)),
+            ("/de/harbour", Response::ok(german)),
+        ]);
+
+        let reference = from_url(&server.url("/en/harbour"), &options("de")).unwrap();
+        assert_eq!(title(&reference), Some(Attribute::Title("Hafenerweiterung genehmigt".to_string())));
+
+        let Reference::NewsArticle { url, .. } = reference else { unreachable!() };
+        assert_eq!(url, Some(Attribute::Url(server.url("/de/harbour"))));
+    }
+}
@@ -0,0 +1,145 @@
+//! Parser for the plain HTML metadata of pages without Open Graph or
+//! Schema.org annotations, i.e. the `<title>` element, `<meta name>`
+//! tags, the canonical link and the `lang` attribute. Authors are read
+//! from the Highwire Press `citation_author` tags of scholarly pages.
+
+use scraper::{Html, Selector};
+use webpage::HTML;
+
+use crate::attribute::{Affiliation, Attribute, AttributeType, Author};
+use crate::parser::{AttributeParser, ParseInfo};
+
+/// `<meta name>` tags giving the title, in order of preference.
+const TITLE_META_NAMES: &[&str] = &["title", "dc.title", "DC.title"];
+
+/// `<meta name>` and `<meta http-equiv>` tags giving the language.
+const LANGUAGE_META_NAMES: &[&str] = &["language", "content-language", "Content-Language"];
+
+/// Separators between the title of a page and the name of its site.
+const TITLE_SEPARATORS: &[&str] = &[" - ", " | ", " – ", " — ", " · ", " :: "];
+
+pub struct HtmlMeta;
+
+/// Name of the site, if given by the page.
+fn site_name(html: &HTML) -> Option<&str> {
+    html.meta
+        .get("application-name")
+        .or_else(|| html.opengraph.properties.get("site_name"))
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+}
+
+/// Strips the name of the site from the end of the title,
+/// e.g. `Council votes on bridge | Fjord Post`.
+fn strip_site_suffix<'a>(title: &'a str, site: &str) -> &'a str {
+    for separator in TITLE_SEPARATORS {
+        let Some((rest, suffix)) = title.rsplit_once(separator) else {
+            continue;
+        };
+        if suffix.trim().eq_ignore_ascii_case(site) && !rest.trim().is_empty() {
+            return rest.trim();
+        }
+    }
+    title
+}
+
+fn find_title(html: &HTML) -> Option<String> {
+    let title = TITLE_META_NAMES
+        .iter()
+        .find_map(|name| html.meta.get(*name))
+        .or(html.title.as_ref())
+        .map(|title| title.trim())
+        .filter(|title| !title.is_empty())?;
+
+    let title = match site_name(html) {
+        Some(site) => strip_site_suffix(title, site),
+        None => title,
+    };
+    Some(title.to_string())
+}
+
+/// Language given by a `<meta>` tag or otherwise the `lang` attribute of
+/// the `<html>` element. Region subtags (e.g. `en-GB`) are kept.
+fn find_language(html: &HTML) -> Option<String> {
+    LANGUAGE_META_NAMES
+        .iter()
+        .find_map(|name| html.meta.get(*name))
+        .or(html.language.as_ref())
+        .map(|language| language.trim().to_string())
+        .filter(|language| !language.is_empty())
+}
+
+/// Authors given by Highwire Press `citation_author` tags, each along with
+/// the institutions of the `citation_author_institution` tags following it.
+fn highwire_authors(document: &Html) -> Vec<Affiliation> {
+    let selector = Selector::parse("meta[name][content]").unwrap();
+    let mut authors: Vec<Affiliation> = Vec::new();
+    for element in document.select(&selector) {
+        let (Some(name), Some(content)) = (element.value().attr("name"), element.value().attr("content")) else {
+            continue;
+        };
+        let content = content.trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        if name.eq_ignore_ascii_case("citation_author") {
+            authors.push(Affiliation { author: content.to_string(), institutions: Vec::new() });
+        } else if name.eq_ignore_ascii_case("citation_author_institution") {
+            if let Some(author) = authors.last_mut() {
+                author.institutions.push(content.to_string());
+            }
+        }
+    }
+    authors
+}
+
+fn find_highwire_authors(document: &Html) -> Option<Attribute> {
+    let authors: Vec<Author> = highwire_authors(document)
+        .into_iter()
+        .map(|author| Author::Person(author.author))
+        .collect();
+    (!authors.is_empty()).then_some(Attribute::Authors(authors))
+}
+
+fn find_highwire_affiliations(document: &Html) -> Option<Attribute> {
+    let affiliations: Vec<Affiliation> = highwire_authors(document)
+        .into_iter()
+        .filter(|author| !author.institutions.is_empty())
+        .collect();
+    (!affiliations.is_empty()).then_some(Attribute::Affiliations(affiliations))
+}
+
+impl AttributeParser for HtmlMeta {
+    fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
+        // Highwire Press tags are read from the document, as their order is significant.
+        match attribute_type {
+            AttributeType::Author => return find_highwire_authors(&parse_info.document),
+            AttributeType::Affiliations => return find_highwire_affiliations(&parse_info.document),
+            _ => (),
+        }
+
+        let html = parse_info.html.as_ref()?;
+
+        match attribute_type {
+            AttributeType::Title => find_title(html).map(Attribute::Title),
+            AttributeType::Language => find_language(html).map(Attribute::Language),
+            // The canonical link, as the page may be reachable under several URLs.
+            AttributeType::Url => html.url.clone().map(Attribute::Url),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_site_suffix;
+
+    #[test]
+    fn site_suffix_stripped() {
+        assert_eq!(strip_site_suffix("Council votes on bridge | Fjord Post", "Fjord Post"), "Council votes on bridge");
+        assert_eq!(strip_site_suffix("Council votes on bridge - fjord post", "Fjord Post"), "Council votes on bridge");
+        assert_eq!(strip_site_suffix("Review | The best laptops", "Fjord Post"), "Review | The best laptops");
+        assert_eq!(strip_site_suffix("Fjord Post", "Fjord Post"), "Fjord Post");
+    }
+}
@@ -0,0 +1,217 @@
+//! Detection of interstitial pages (e.g. email-gated "view article" pages,
+//! consent walls and captchas) served in place of the actual article, and
+//! retrieval of cached copies of the article from third-party cache providers.
+
+use url::form_urlencoded::byte_serialize;
+
+use crate::curl::get_html;
+use crate::generator::CacheProvider;
+use crate::parser::ParseInfo;
+use crate::GenerationOptions;
+
+/// Phrases commonly found on interstitial pages, including consent walls
+/// and the captchas of bot protection services.
+const INTERSTITIAL_PATTERNS: &[&str] = &[
+    "view article",
+    "enter your email",
+    "sign in to continue",
+    "sign in to read",
+    "register to continue",
+    "subscribe to continue",
+    "subscribe to read",
+    "before you continue",
+    "cookie consent",
+    "are you a robot",
+    "verify you are human",
+    "captcha",
+    "enable javascript",
+    "access denied",
+];
+
+/// [`INTERSTITIAL_PATTERNS`], as used by [`crate::GenerationOptions`].
+pub fn default_patterns() -> Vec<String> {
+    INTERSTITIAL_PATTERNS.iter().map(|pattern| pattern.to_string()).collect()
+}
+
+/// Interstitial pages are short; long pages merely mentioning
+/// one of the patterns are not considered interstitials.
+const MAX_INTERSTITIAL_TEXT_LENGTH: usize = 2000;
+
+fn matches_pattern(text: &str, patterns: &[String]) -> bool {
+    let text = text.to_lowercase();
+    patterns.iter().any(|pattern| text.contains(&pattern.to_lowercase()))
+}
+
+/// Whether the fetched page appears to be an interstitial rather than the
+/// article, i.e. its title or short text contains one of the patterns.
+pub fn is_interstitial(parse_info: &ParseInfo, patterns: &[String]) -> bool {
+    let Some(html) = &parse_info.html else {
+        return false;
+    };
+
+    let title_matches = html.title.as_deref().is_some_and(|title| matches_pattern(title, patterns));
+    let text = html.text_content.trim();
+    let body_matches = text.len() <= MAX_INTERSTITIAL_TEXT_LENGTH && matches_pattern(text, patterns);
+
+    title_matches || body_matches
+}
+
+impl CacheProvider {
+    /// Returns the URL of the cached copy of a page.
+    pub fn cache_url(&self, url: &str) -> String {
+        let encoded: String = byte_serialize(url.as_bytes()).collect();
+        match self {
+            CacheProvider::Custom(template) => template.replace("{url}", &encoded),
+        }
+    }
+}
+
+/// Attempts to retrieve a cached copy of the page from each of the configured
+/// cache providers in turn. The returned [`ParseInfo`] is associated with the
+/// original URL so that the original page, not the cache, is cited.
+pub fn fetch_cached<'a>(url: &'a str, options: &GenerationOptions) -> Option<ParseInfo<'a>> {
+    let language = options.preferred_content_language.as_deref();
+    options.fallback_cache_providers.iter().find_map(|provider| {
+        let raw_html = get_html(&provider.cache_url(url), language, &options.request_options).ok()?;
+        let parse_info = ParseInfo::from_html(url, raw_html, options).ok()?;
+        (!is_interstitial(&parse_info, &options.blocked_content_patterns)).then_some(parse_info)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::is_interstitial;
+    use crate::attribute::Attribute;
+    use crate::generator::{from_url, ArchiveOptions, CacheProvider, ReferenceGenerationError, RequestOptions};
+    use crate::mock_client::MockClient;
+    use crate::mock_server::{MockServer, Response};
+    use crate::parser::ParseInfo;
+    use crate::schema_org::is_paywalled;
+    use crate::{GenerationOptions, Reference};
+
+    const CONSENT_PAGE: &str = r#"<html><head><title>Before you continue</title></head>
+        <body><p>We use cookies to deliver our services.</p><button>Accept all</button></body></html>"#;
+
+    #[test]
+    fn cached_copy_of_interstitial() {
+        let interstitial = r#"<html><head><title>View article</title></head>
+            <body><p>Enter your email to keep reading.</p></body></html>"#;
+        let article = r#"<html><head>
+            <title>Harbour expansion approved</title>
+            <meta property="og:title" content="Harbour expansion approved">
+            <meta property="og:site_name" content="Coastal Times">
+            </head><body><p>The council approved the expansion.</p></body></html>"#;
+        let server = MockServer::start(move |request| {
+            if request.path.starts_with("/cache?url=") {
+                Response::ok(article)
+            } else {
+                Response::ok(interstitial)
+            }
+        });
+        let url = server.url("/news/harbour");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            fallback_cache_providers: vec![CacheProvider::Custom(server.url("/cache?url={url}"))],
+            ..Default::default()
+        };
+        let reference = from_url(&url, &options).unwrap();
+
+        let Reference::NewsArticle { title, url: cited_url, .. } = reference else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+        assert_eq!(cited_url, Some(Attribute::Url(url)));
+    }
+
+    #[test]
+    fn cached_copy_of_paywalled_page() {
+        let teaser = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle",
+             "headline": "Harbour expansion approved", "isAccessibleForFree": false}
+            </script>
+            </head><body><p>Subscribe for full access.</p></body></html>"#;
+        let article = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <meta property="og:site_name" content="Coastal Times">
+            </head><body><p>The council approved the expansion.</p></body></html>"#;
+        let server = MockServer::start(move |request| {
+            if request.path.starts_with("/cache?url=") {
+                Response::ok(article)
+            } else {
+                Response::ok(teaser)
+            }
+        });
+        let url = server.url("/news/harbour");
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            fallback_cache_providers: vec![CacheProvider::Custom(server.url("/cache?url={url}"))],
+            ..Default::default()
+        };
+        let parse_info = ParseInfo::from_url(&url, &options).unwrap();
+        assert!(is_paywalled(&parse_info));
+        assert!(!is_interstitial(&parse_info, &options.blocked_content_patterns));
+
+        let Reference::NewsArticle { site, .. } = from_url(&url, &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(site, Some(Attribute::Site("Coastal Times".to_string())));
+    }
+
+    #[test]
+    fn archived_copy_of_consent_page() {
+        let url = "https://coastaltimes.example.com/news/harbour";
+        let availability = r#"{"archived_snapshots": {"closest": {"status": "200", "available": true,
+            "url": "http://web.archive.org/web/20241001120000/https://coastaltimes.example.com/news/harbour",
+            "timestamp": "20241001120000"}}}"#;
+        let article = r#"<html><head>
+            <meta property="og:title" content="Harbour expansion approved">
+            <meta property="og:site_name" content="Coastal Times">
+            </head><body><p>The council approved the expansion.</p></body></html>"#;
+        let client = Arc::new(MockClient::new(&[
+            (url, CONSENT_PAGE),
+            ("http://archive.org/wayback/available", availability),
+            ("http://web.archive.org/web/20241001120000id_/", article),
+        ]));
+        let options = GenerationOptions {
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        let Reference::NewsArticle { title, site, url: cited_url, .. } = from_url(url, &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+        assert_eq!(site, Some(Attribute::Site("Coastal Times".to_string())));
+        assert_eq!(cited_url, Some(Attribute::Url(url.to_string())));
+        assert!(client.requested().iter().any(|requested| requested.contains("20241001120000id_/")));
+
+        // Without archived snapshots, the blocked page is reported rather than cited.
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            ..options
+        };
+        let error = from_url(url, &options).unwrap_err();
+        assert!(matches!(error, ReferenceGenerationError::BlockedContent { .. }));
+        assert_eq!(error.url(), Some(url));
+    }
+
+    #[test]
+    fn custom_blocked_content_patterns() {
+        let server = MockServer::start(|_| {
+            Response::ok("<html><head><title>Bitte aktivieren Sie JavaScript</title></head><body></body></html>")
+        });
+        let url = server.url("/nachrichten/hafen");
+        let mut options = GenerationOptions::default();
+        let parse_info = ParseInfo::from_url(&url, &options).unwrap();
+        assert!(!is_interstitial(&parse_info, &options.blocked_content_patterns));
+
+        options.blocked_content_patterns.push("Aktivieren Sie JavaScript".to_string());
+        assert!(is_interstitial(&parse_info, &options.blocked_content_patterns));
+    }
+}
@@ -11,31 +11,182 @@
 //! [BibTeX]: https://www.bibtex.org/
 //! [MediaWiki]: https://www.mediawiki.org/wiki/Help:Cite
 
+use std::collections::HashMap;
 use std::result;
+use attribute::{AttributeType, Date};
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
 
 pub mod attribute;
 pub mod generator;
 mod schema_org;
+mod spa;
 mod opengraph;
+mod html_meta;
+mod dublin_core;
+mod rdfa;
+mod sanitize;
 mod doi;
+mod zotero;
 mod curl;
+mod charset;
 mod citation;
+mod copyright;
 mod parser;
+mod pagination;
+mod amp;
+mod batch;
+mod interstitial;
+mod fediverse;
+mod qa_page;
+mod syndication;
+mod hreflang;
+mod redirect;
+mod response_cache;
+mod title_case;
+mod translation;
+mod url_normalization;
+mod url_site;
+mod date_check;
+mod dateline;
+mod person_name;
 mod reference;
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(test)]
+mod mock_server;
+#[cfg(test)]
+mod mock_client;
 
-use generator::{attribute_config::{AttributeConfig, AttributeConfigBuilder}, TranslationOptions, ReferenceGenerationError, ArchiveOptions};
+use generator::{attribute_config::{AttributeConfig, AttributeConfigBuilder}, TranslationOptions, ReferenceGenerationError, ArchiveOptions, RequestOptions, AttributeTransform, CacheProvider, Provenance, ResponseCache, FullResult};
 pub use reference::*;
+pub use redirect::expand_url;
+pub use curl::{CurlClient, CurlError, HttpClient};
+#[cfg(feature = "async")]
+pub use asynchronous::{generate_all_async, generate_async, generate_batch_async};
 
 type Result<T> = result::Result<T, ReferenceGenerationError>;
 
-/// Options for reference generation.
-#[derive(Builder)]
+/// Options for reference generation. They may be saved to and loaded from
+/// config files, see [`GenerationOptions::from_toml_str`], in which options
+/// left out take their default values. The API keys of the translation
+/// options, the clock, the attribute transforms and the HTTP client of the
+/// request options are not serialized.
+#[derive(Builder, Clone, Serialize, Deserialize)]
 #[builder(setter(into))]
+#[serde(default)]
 pub struct GenerationOptions {
     pub attribute_config: AttributeConfig,
     pub translation_options: TranslationOptions,
     pub archive_options: ArchiveOptions,
+    /// Whether to extract metadata from the AMP version of a page when
+    /// the page itself is metadata-poor. The canonical URL is still cited.
+    #[builder(default)]
+    pub prefer_amp: bool,
+    /// Cache providers, given by their URL templates, from which a copy of
+    /// the page is retrieved when the page turns out to be an interstitial.
+    /// Opt-in, as the URL being cited is sent to the third-party provider.
+    /// Empty by default.
+    #[builder(default)]
+    pub fallback_cache_providers: Vec<CacheProvider>,
+    /// Phrases, matched case-insensitively against the title and the text
+    /// of short pages, by which pages blocking their content are recognized,
+    /// e.g. consent walls and captchas. These are cited using a cached copy,
+    /// the Zotero item or an archived snapshot of the page, if available.
+    /// Defaults to phrases such as `before you continue`, `are you a robot`
+    /// and `enable javascript`.
+    #[builder(default = "interstitial::default_patterns()")]
+    pub blocked_content_patterns: Vec<String>,
+    /// On-disk cache for DOI, Citoid and archive lookups, reused between runs. Disabled by default.
+    #[builder(default)]
+    pub response_cache: Option<ResponseCache>,
+    /// Whether to convert titles written entirely in uppercase to title case.
+    #[builder(default)]
+    pub normalize_title_case: bool,
+    /// Date on which the page is considered accessed. Defaults to the current date.
+    #[builder(default)]
+    pub access_date: Option<Date>,
+    /// Time used as the current time during generation, e.g. for the access
+    /// date and the archived snapshot looked up, so that generation can be
+    /// reproduced. Defaults to the current time.
+    #[builder(default)]
+    #[serde(skip)]
+    pub clock: Option<DateTime<Utc>>,
+    /// Language (e.g. `de`) of the edition of multilingual sites to cite.
+    /// Requested using the `Accept-Language` header and used to pick among
+    /// the language alternates advertised by the page.
+    #[builder(default)]
+    pub preferred_content_language: Option<String>,
+    /// Whether to ask for the JSON-LD representation of the page using the
+    /// `Accept` header, whose Schema.org metadata is then used without the
+    /// HTML page. Servers not supporting content negotiation serve the page.
+    #[builder(default)]
+    pub request_json_ld: bool,
+    /// Whether to omit publication dates which are likely to be wrong,
+    /// i.e. dates after the access date or before the web existed.
+    /// A warning is returned in [`FullResult::warnings`] for such dates either way.
+    #[builder(default)]
+    pub drop_implausible_dates: bool,
+    /// Whether to follow the redirects of the URL before generating the
+    /// reference, so that e.g. `t.co` and `bit.ly` links are cited using the
+    /// URL they redirect to. Costs an additional request.
+    #[builder(default)]
+    pub expand_short_urls: bool,
+    /// Transformations applied to the extracted attributes of the given
+    /// types, e.g. to always rewrite a site name. Empty by default.
+    #[builder(default)]
+    #[serde(skip)]
+    pub attribute_transforms: HashMap<AttributeType, AttributeTransform>,
+    /// Endpoint of the Citoid service used by [`generator::MetadataType::Zotero`],
+    /// to which the percent-encoded URL is appended. Defaults to Wikipedia's.
+    #[builder(default)]
+    pub citoid_endpoint: Option<String>,
+    /// Whether to extract the place the article was reported from out of
+    /// the dateline beginning its body, e.g. `WASHINGTON —`. Heuristic,
+    /// so disabled by default.
+    #[builder(default)]
+    pub extract_dateline: bool,
+    /// Whether to take the publisher from the copyright notice in the
+    /// footer of the page, e.g. `© 2024 Example Media Ltd.`, when the
+    /// metadata does not name it. Heuristic, so disabled by default.
+    #[builder(default)]
+    pub extract_copyright_publisher: bool,
+    /// Whether to derive the site from the host of the URL, e.g. `bbc.co.uk`,
+    /// when the metadata does not name it. Well-known sites are given by
+    /// their name, e.g. `BBC`. Disabled by default.
+    #[builder(default)]
+    pub derive_site_from_host: bool,
+    /// Whether to record the SHA-256 hash of the HTML of the page, so that
+    /// the cited content can later be verified. It is only rendered in
+    /// formats having a field for notes, e.g. BibTeX. Disabled by default.
+    #[builder(default)]
+    pub include_content_hash: bool,
+    /// Whether to ask CrossRef whether the DOI of a scholarly article has
+    /// been retracted or corrected, in which case its status is given and a
+    /// warning returned in [`FullResult::warnings`]. Costs an additional
+    /// request. Disabled by default.
+    #[builder(default)]
+    pub check_doi_updates: bool,
+    /// Whether to fail with [`ReferenceGenerationError::InsufficientMetadata`]
+    /// rather than generate a reference lacking a title or URL. Disabled by
+    /// default, so that e.g. a reference without a title can be completed by hand.
+    #[builder(default)]
+    pub strict: bool,
+    /// Whether to normalize the cited and archived URLs: relative URLs are
+    /// resolved, `https` is added to URLs lacking a scheme and tracking
+    /// parameters and fragments are removed. Enabled by default.
+    #[builder(default = "true")]
+    pub normalize_urls: bool,
+    /// Query parameters removed when normalizing URLs, where a trailing `*`
+    /// matches any suffix. Defaults to `utm_*`, `fbclid`, `gclid`, `mc_cid`
+    /// and `mc_eid`.
+    #[builder(default = "url_normalization::default_tracking_parameters()")]
+    pub tracking_parameters: Vec<String>,
+    /// Timeouts, retries and User-Agent of the HTTP requests made,
+    /// so that an unresponsive server cannot stall generation.
+    #[builder(default)]
+    pub request_options: RequestOptions,
 }
 impl Default for GenerationOptions {
     fn default() -> Self {
@@ -49,6 +200,28 @@ impl Default for GenerationOptions {
             attribute_config,
             translation_options,
             archive_options,
+            prefer_amp: false,
+            fallback_cache_providers: Vec::new(),
+            blocked_content_patterns: interstitial::default_patterns(),
+            response_cache: None,
+            normalize_title_case: false,
+            access_date: None,
+            clock: None,
+            preferred_content_language: None,
+            request_json_ld: false,
+            drop_implausible_dates: false,
+            expand_short_urls: false,
+            attribute_transforms: HashMap::new(),
+            citoid_endpoint: None,
+            extract_dateline: false,
+            extract_copyright_publisher: false,
+            derive_site_from_host: false,
+            include_content_hash: false,
+            check_doi_updates: false,
+            strict: false,
+            normalize_urls: true,
+            tracking_parameters: url_normalization::default_tracking_parameters(),
+            request_options: RequestOptions::default(),
         }
     }
 }
@@ -58,8 +231,29 @@ impl GenerationOptions {
             attribute_config,
             translation_options,
             archive_options,
+            ..Default::default()
         }
     }
+
+    /// Reads the options from a TOML config file, e.g.
+    ///
+    /// ```toml
+    /// normalize_title_case = true
+    ///
+    /// [archive_options]
+    /// include_archived = false
+    ///
+    /// [attribute_config.title]
+    /// priority = ["SchemaOrg", "OpenGraph"]
+    /// ```
+    pub fn from_toml_str(toml: &str) -> result::Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// Writes the options in the format read by [`GenerationOptions::from_toml_str`].
+    pub fn to_toml_string(&self) -> result::Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
 }
 
 pub fn generate(url: &str, options: &GenerationOptions) -> Result<Reference> {
@@ -68,4 +262,55 @@ pub fn generate(url: &str, options: &GenerationOptions) -> Result<Reference> {
 
 pub fn generate_from_file(path: &str, options: &GenerationOptions) -> Result<Reference> {
     generator::from_file(path, options)
+}
+
+/// Like [`generate_from_file`], but for HTML saved from the given URL,
+/// which is cited if the metadata lacks one and used to look up the DOI
+/// and Zotero item, as done by [`generate`].
+pub fn generate_from_file_with_url(path: &str, url: &str, options: &GenerationOptions) -> Result<Reference> {
+    generator::from_file_with_url(path, url, options)
+}
+
+/// Generates references to several URLs, whose pages are fetched by up to
+/// [`RequestOptions::batch_concurrency`] threads at once. The results are
+/// returned in the order of the URLs, and failing to generate one of the
+/// references does not affect the others.
+pub fn generate_batch(urls: &[&str], options: &GenerationOptions) -> Vec<Result<Reference>> {
+    batch::map_concurrently(urls, options.request_options.batch_concurrency, |url| generate(url, options))
+}
+
+/// Like [`generate_batch`], but for raw HTML read from files.
+pub fn generate_batch_from_files(paths: &[&str], options: &GenerationOptions) -> Vec<Result<Reference>> {
+    batch::map_concurrently(paths, options.request_options.batch_concurrency, |path| generate_from_file(path, options))
+}
+
+/// Like [`generate`], but also returns the metadata type
+/// (e.g. Open Graph) each attribute was taken from.
+pub fn generate_with_provenance(url: &str, options: &GenerationOptions) -> Result<(Reference, Provenance)> {
+    generator::from_url_with_provenance(url, options)
+}
+
+/// Like [`generate_from_file`], but also returns the metadata type
+/// (e.g. Open Graph) each attribute was taken from.
+pub fn generate_from_file_with_provenance(path: &str, options: &GenerationOptions) -> Result<(Reference, Provenance)> {
+    generator::from_file_with_provenance(path, options)
+}
+
+/// Like [`generate`], but also returns the attributes found in every
+/// metadata type, their provenance and any warnings, all from a single
+/// fetch of the page. Warnings are never printed, so this and the other
+/// `*_all` variants are the way to receive them.
+pub fn generate_all(url: &str, options: &GenerationOptions) -> Result<FullResult> {
+    generator::from_url_all(url, options)
+}
+
+/// Like [`generate_all`], but for raw HTML read from a file.
+pub fn generate_all_from_file(path: &str, options: &GenerationOptions) -> Result<FullResult> {
+    generator::from_file_all(path, options)
+}
+
+/// Like [`generate_batch`], but returns everything [`generate_all`] does
+/// for each of the URLs, including its warnings.
+pub fn generate_batch_all(urls: &[&str], options: &GenerationOptions) -> Vec<Result<FullResult>> {
+    batch::map_concurrently(urls, options.request_options.batch_concurrency, |url| generate_all(url, options))
 }
\ No newline at end of file
@@ -0,0 +1,68 @@
+//! [`HttpClient`] answering requests with canned responses. Used for testing
+//! code paths requesting fixed hosts, e.g. `doi.org`, without access to the
+//! internet, whereas [`crate::mock_server::MockServer`] serves local URLs.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::curl::{CurlError, HttpClient};
+use crate::generator::RequestOptions;
+
+/// Client answering each request with the response of the first URL prefix
+/// matching the requested URL, or HTTP status 404 if none does. The URLs
+/// requested are recorded.
+#[derive(Debug, Default)]
+pub struct MockClient {
+    responses: Vec<(String, String)>,
+    requested: Mutex<Vec<String>>,
+    delay: Duration,
+}
+impl MockClient {
+    pub fn new(responses: &[(&str, &str)]) -> Self {
+        Self {
+            responses: responses.iter().map(|(prefix, response)| (prefix.to_string(), response.to_string())).collect(),
+            requested: Mutex::new(Vec::new()),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Delays every response, e.g. to tell whether requests are made concurrently.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// URLs requested so far, in order.
+    pub fn requested(&self) -> Vec<String> {
+        self.requested.lock().unwrap().clone()
+    }
+
+    pub fn clear_requested(&self) {
+        self.requested.lock().unwrap().clear();
+    }
+
+    fn respond(&self, url: &str) -> Result<String, CurlError> {
+        self.requested.lock().unwrap().push(url.to_string());
+        thread::sleep(self.delay);
+        self.responses
+            .iter()
+            .find(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .map(|(_, response)| response.clone())
+            .ok_or(CurlError::HttpStatus(404))
+    }
+}
+
+impl HttpClient for MockClient {
+    fn get(&self, url: &str, _: Option<&str>, _: bool, _: &RequestOptions) -> Result<String, CurlError> {
+        self.respond(url)
+    }
+
+    fn post(&self, url: &str, _: &str, _: Option<&str>, _: &RequestOptions) -> Result<String, CurlError> {
+        self.respond(url)
+    }
+
+    fn resolve_redirects(&self, url: &str, _: bool, _: &RequestOptions) -> Result<(u32, String), CurlError> {
+        Ok((200, url.to_string()))
+    }
+}
@@ -0,0 +1,164 @@
+//! Minimal HTTP server serving canned responses. Used for testing the
+//! network-dependent code paths without access to the internet.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Request received by the [`MockServer`].
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+impl Request {
+    /// Returns the value of a header; header names are case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Canned response returned by the [`MockServer`].
+#[derive(Clone)]
+pub struct Response {
+    pub status: u32,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+impl Response {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self { status: 200, headers: Vec::new(), body: body.into() }
+    }
+
+    pub fn status(status: u32) -> Self {
+        Self { status, headers: Vec::new(), body: Vec::new() }
+    }
+
+    /// Permanent redirect to the given location.
+    pub fn redirect(location: impl Into<String>) -> Self {
+        Self::status(301).with_header("Location", location)
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+}
+
+/// HTTP server listening on a random local port for the lifetime of the test process.
+pub struct MockServer {
+    address: SocketAddr,
+    connections: Arc<AtomicUsize>,
+}
+impl MockServer {
+    /// Starts a server answering every request using the provided handler.
+    /// Connections are kept alive until closed by the client.
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let handler = Arc::new(handler);
+        let accepted = connections.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let handler = handler.clone();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    while let Some(request) = read_request(&mut reader) {
+                        let head_only = request.method == "HEAD";
+                        write_response(&stream, &handler(&request), head_only);
+                    }
+                });
+            }
+        });
+
+        Self { address, connections }
+    }
+
+    /// Starts a server answering requests for the given paths with
+    /// fixed responses, and with 404 for every other path.
+    pub fn serve(routes: Vec<(&'static str, Response)>) -> Self {
+        Self::start(move |request| {
+            routes
+                .iter()
+                .find(|(path, _)| *path == request.path)
+                .map(|(_, response)| response.clone())
+                .unwrap_or(Response::status(404))
+        })
+    }
+
+    /// Returns the absolute URL of a path on the server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.address, path)
+    }
+
+    /// Returns the number of connections accepted so far.
+    pub fn connection_count(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+/// Reads the next request of the connection, if it has not been closed.
+fn read_request(reader: &mut BufReader<&TcpStream>) -> Option<Request> {
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).ok()? == 0 {
+        return None;
+    }
+    let mut request_parts = request_line.split_whitespace();
+    let method = request_parts.next()?.to_string();
+    let path = request_parts.next()?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = Request { method, path, headers, body: String::new() };
+    let length: usize = request
+        .header("Content-Length")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    request.body = String::from_utf8_lossy(&body).into_owned();
+
+    Some(request)
+}
+
+/// Writes the response; the body is omitted in responses to HEAD requests.
+fn write_response(mut stream: &TcpStream, response: &Response, head_only: bool) {
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!("Content-Length: {}\r\n\r\n", response.body.len()));
+
+    let _ = stream.write_all(head.as_bytes());
+    if !head_only {
+        let _ = stream.write_all(&response.body);
+    }
+}
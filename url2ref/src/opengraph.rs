@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use scraper::{Html, Selector};
+use url::Url;
+
 use crate::attribute::{Attribute, AttributeType, Author};
 use crate::parser::{parse_date, AttributeParser, ParseInfo, MetadataKey};
 
@@ -12,6 +15,7 @@ pub const fn keys(key: AttributeType) -> &'static [MetadataKey] {
         AttributeType::Author   => &[MetadataKey{key: "article:author"}],
         AttributeType::Locale   => &[MetadataKey{key: "locale"}],
         AttributeType::Site     => &[MetadataKey{key: "site_name"}],
+        AttributeType::Section  => &[MetadataKey{key: "article:section"}],
         AttributeType::Url      => &[MetadataKey{key: "url"}],
         AttributeType::Date     => &[MetadataKey{key: "article:published_time"},
                                      MetadataKey{key: "article:modified_time"},
@@ -25,23 +29,70 @@ pub struct OpenGraph;
 
 fn try_find_attribute(
     og: &HashMap<String, String>,
+    document: &Html,
     external_keys: &[MetadataKey],
 ) -> Option<String> {
     for external_key in external_keys.iter() {
         if og.contains_key(external_key.key) {
             return og.get(external_key.key).cloned();
         }
+        if let Some(value) = find_article_property(document, external_key.key) {
+            return Some(value);
+        }
     }
 
     None
 }
 
+/// Value of an `article:` property, e.g. `article:section`. Such properties
+/// are read from the document, as [`webpage`] ignores properties without
+/// `og:`, which the article namespace usually lacks.
+fn find_article_property(document: &Html, key: &str) -> Option<String> {
+    if !key.starts_with("article:") {
+        return None;
+    }
+    let selector = Selector::parse(&format!("meta[property=\"{key}\"][content], meta[property=\"og:{key}\"][content]")).unwrap();
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("content"))
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Whether the value is a web address, as `article:author` often links
+/// to the profile page of the author rather than giving their name.
+fn is_url(value: &str) -> bool {
+    Url::parse(value.trim()).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Authors given by the `article:author` tags, of which there is one per
+/// author. They are read from the document, as [`webpage`] also only keeps
+/// the last value of repeated properties.
+fn find_authors(document: &Html) -> Option<Attribute> {
+    let selector = Selector::parse(
+        "meta[property=\"article:author\"][content], meta[property=\"og:article:author\"][content]",
+    )
+    .unwrap();
+    let authors: Vec<Author> = document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("content"))
+        .map(str::trim)
+        // Profile URLs are skipped in favor of the authors given by other metadata.
+        .filter(|author| !author.is_empty() && !is_url(author))
+        .map(|author| Author::Generic(author.to_string()))
+        .collect();
+    (!authors.is_empty()).then_some(Attribute::Authors(authors))
+}
+
 fn attribute_type_to_attribute(
     attribute_type: AttributeType,
     attribute_value: String,
 ) -> Option<Attribute> {
     match attribute_type {
         AttributeType::Title => Some(Attribute::Title(attribute_value)),
+        // Profile URLs are skipped in favor of the authors given by other metadata.
+        AttributeType::Author if is_url(&attribute_value) => None,
         AttributeType::Author => {
             let author = Author::Generic(attribute_value);
             Some(Attribute::Authors(vec![author]))
@@ -53,7 +104,10 @@ fn attribute_type_to_attribute(
         AttributeType::Locale => Some(Attribute::Locale(attribute_value)),
         AttributeType::Language => Some(Attribute::Language(attribute_value)),
         AttributeType::Site => Some(Attribute::Site(attribute_value)),
+        AttributeType::Section => Some(Attribute::Section(attribute_value)),
         AttributeType::Url => Some(Attribute::Url(attribute_value)),
+        AttributeType::Type => Some(Attribute::Type(attribute_value)),
+        AttributeType::ImageCredit => Some(Attribute::ImageCredit(attribute_value)),
         _ => None,
     }
 }
@@ -61,9 +115,21 @@ fn attribute_type_to_attribute(
 impl AttributeParser for OpenGraph {
     fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
         let html = parse_info.html.as_ref()?;
+        if attribute_type == AttributeType::Author {
+            if let Some(authors) = find_authors(&parse_info.document) {
+                return Some(authors);
+            }
+        }
+
+        // The alternative text is kept by [`webpage`] along with the image.
+        if attribute_type == AttributeType::ImageCredit {
+            let alt = html.opengraph.images.first()?.properties.get("alt")?.trim();
+            return (!alt.is_empty()).then(|| Attribute::ImageCredit(alt.to_string()));
+        }
+
         let og = &html.opengraph.properties;
         let external_keys = keys(attribute_type);
-        let attribute_value = try_find_attribute(&og, external_keys)?;
+        let attribute_value = try_find_attribute(og, &parse_info.document, external_keys)?;
 
         attribute_type_to_attribute(attribute_type, attribute_value)
     }
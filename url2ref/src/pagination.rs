@@ -0,0 +1,193 @@
+//! Detection of paginated (multi-part) articles.
+//!
+//! Some articles are split across several pages (e.g. `?page=2`). A citation
+//! should always point to the canonical or first page of such an article, so
+//! pagination is detected from `<link rel="next">`/`<link rel="prev">`
+//! elements as well as common pagination query parameters and path segments.
+
+use regex::Regex;
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Query parameters commonly used to denote the page of an article.
+/// `p` is deliberately left out, as WordPress uses it for post IDs.
+const PAGE_PARAMS: &[&str] = &["page", "pg", "pagenum", "page_num"];
+
+/// Pagination information for a multi-part article.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pagination {
+    /// URL of the canonical or first page, used when citing the article.
+    pub first_page_url: String,
+    /// Total number of pages, if advertised by the page.
+    pub total_pages: Option<u32>,
+}
+
+/// Pagination-related `<link>` elements found in the document.
+#[derive(Default)]
+struct PageLinks {
+    canonical: Option<Url>,
+    prev: Option<Url>,
+    next: Option<Url>,
+    last: Option<Url>,
+}
+
+/// Resolves a possibly relative `href` against the base URL.
+fn resolve(href: &str, base: Option<&Url>) -> Option<Url> {
+    match base {
+        Some(base) => base.join(href).ok(),
+        None => Url::parse(href).ok(),
+    }
+}
+
+fn find_links(document: &Html, base: Option<&Url>) -> PageLinks {
+    let selector = Selector::parse("link[rel][href]").unwrap();
+    let mut links = PageLinks::default();
+
+    for element in document.select(&selector) {
+        let value = element.value();
+        let href = value.attr("href").unwrap_or_default();
+        let rel = value.attr("rel").unwrap_or_default().to_lowercase();
+
+        let slot = match rel.as_str() {
+            "canonical" => &mut links.canonical,
+            "prev" | "previous" => &mut links.prev,
+            "next" => &mut links.next,
+            "last" => &mut links.last,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = resolve(href, base);
+        }
+    }
+
+    links
+}
+
+/// Returns the page number encoded in a URL, either as a query parameter
+/// (`?page=2`) or as a trailing path segment (`/page/2/`).
+fn page_number(url: &Url) -> Option<u32> {
+    let from_query = url
+        .query_pairs()
+        .find(|(key, _)| PAGE_PARAMS.contains(&key.to_lowercase().as_str()))
+        .and_then(|(_, value)| value.parse().ok());
+
+    from_query.or_else(|| {
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            [.., "page", number] => number.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+/// Removes pagination query parameters and path segments from a URL.
+fn strip_page(url: &Url) -> Url {
+    let mut stripped = url.clone();
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !PAGE_PARAMS.contains(&key.to_lowercase().as_str()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        stripped.set_query(None);
+    } else {
+        stripped.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    if let [rest @ .., "page", number] = segments.as_slice() {
+        if number.parse::<u32>().is_ok() {
+            stripped.set_path(&format!("/{}/", rest.join("/")));
+        }
+    }
+
+    stripped
+}
+
+/// Finds the total number of pages, either from a `<link rel="last">`
+/// element or from a "Page 2 of 5" style indicator in the page body.
+fn find_total_pages(links: &PageLinks, raw_html: &str) -> Option<u32> {
+    let from_last = links.last.as_ref().and_then(page_number);
+
+    from_last.or_else(|| {
+        let re = Regex::new(r"(?i)\bpage\s+\d+\s+(?:of|/)\s+(\d+)\b").unwrap();
+        re.captures(raw_html)?.get(1)?.as_str().parse().ok()
+    })
+}
+
+/// Detects whether the page is part of a multi-part article and, if so,
+/// determines the URL of its canonical or first page.
+///
+/// `url` is the URL the reference would otherwise cite; it is also used
+/// to resolve relative links.
+pub fn detect(document: &Html, raw_html: &str, url: Option<&str>) -> Option<Pagination> {
+    let current = url.and_then(|u| Url::parse(u).ok());
+    let links = find_links(document, current.as_ref());
+
+    let current_page = current.as_ref().and_then(page_number);
+    let paginated = links.prev.is_some()
+        || links.next.is_some()
+        || current_page.is_some_and(|page| page > 1);
+    if !paginated {
+        return None;
+    }
+
+    // Prefer the canonical link, unless it points to a later page itself.
+    let canonical = links
+        .canonical
+        .as_ref()
+        .filter(|canonical| page_number(canonical).unwrap_or(1) <= 1);
+    let first_page = canonical
+        .cloned()
+        .or_else(|| current.as_ref().map(strip_page))
+        .or_else(|| links.canonical.as_ref().map(strip_page))?;
+
+    Some(Pagination {
+        first_page_url: first_page.to_string(),
+        total_pages: find_total_pages(&links, raw_html),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_page_from_query_parameter() {
+        let html = r#"<html><head>
+            <link rel="prev" href="/story?id=7&page=1">
+            <link rel="next" href="/story?id=7&page=3">
+            <link rel="last" href="/story?id=7&page=4">
+            </head><body></body></html>"#;
+
+        let pagination = detect(&Html::parse_document(html), html, Some("https://example.com/story?id=7&page=2"));
+        let expected = Pagination {
+            first_page_url: "https://example.com/story?id=7".to_string(),
+            total_pages: Some(4),
+        };
+        assert_eq!(pagination, Some(expected));
+    }
+
+    #[test]
+    fn first_page_from_path_segment() {
+        let html = r#"<html><body><p>Page 2 of 3</p></body></html>"#;
+
+        let pagination = detect(&Html::parse_document(html), html, Some("https://example.com/2024/01/story/page/2/"));
+        let expected = Pagination {
+            first_page_url: "https://example.com/2024/01/story/".to_string(),
+            total_pages: Some(3),
+        };
+        assert_eq!(pagination, Some(expected));
+    }
+
+    #[test]
+    fn single_page_article() {
+        let html = r#"<html><head><link rel="canonical" href="https://example.com/story"></head></html>"#;
+
+        assert_eq!(detect(&Html::parse_document(html), html, Some("https://example.com/story")), None);
+    }
+}
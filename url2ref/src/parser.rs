@@ -1,18 +1,30 @@
 //! Parser which extracts the metadata to be combined into a [`crate::reference::Reference`].
 
 use std::collections::HashMap;
-use std::{fs, result};
+use std::{fs, result, thread};
 
 use crate::attribute::{Attribute, AttributeType, Date};
-use crate::curl::get_html;
+use crate::charset;
+use crate::curl::{get_html, get_html_or_json_ld, CurlError};
 use crate::doi::{self, Doi};
 use crate::generator::attribute_config::{AttributeConfig, AttributePriority};
-use crate::generator::{MetadataType, ReferenceGenerationError};
+use crate::generator::{
+    AttributeTransform, MetadataType, MultiSourceAttributeCollection, Provenance, ReferenceGenerationError,
+};
+use crate::GenerationOptions;
+use crate::dublin_core::DublinCore;
+use crate::rdfa::Rdfa;
+use crate::sanitize::sanitize;
+use crate::html_meta::HtmlMeta;
 use crate::opengraph::OpenGraph;
-use crate::schema_org::SchemaOrg;
+use crate::schema_org::{self, SchemaOrg};
+use crate::zotero::{self, Zotero, ZoteroResult};
 
 use biblatex::Bibliography;
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde_json::Value;
 use strum::IntoEnumIterator;
 use webpage::HTML;
 
@@ -26,64 +38,227 @@ pub struct MetadataKey {
 pub struct ParseInfo<'a> {
     pub url: Option<&'a str>,
     pub raw_html: String,
+    /// Document tree of the page, parsed once and shared by the lookups
+    /// of links (e.g. pagination, AMP and language alternates).
+    pub document: Html,
     pub html: Option<HTML>,
+    /// Entities of all JSON-LD blocks of the page, see [`schema_org::collect_json_ld`].
+    pub json_ld: Vec<Value>,
     pub bibliography: Option<Bibliography>,
+    /// Zotero item for the URL, only retrieved if Zotero is among the parsers used.
+    pub zotero: Option<ZoteroResult>,
+    /// Metadata types used, see [`AttributeConfig::parsers_used`].
+    pub parsers: Vec<MetadataType>,
+    /// Problems encountered while retrieving the metadata, e.g. BibTeX
+    /// entries ignored, which are returned along with the reference.
+    pub warnings: Vec<String>,
 }
 
 impl ParseInfo<'_> {
-    pub fn from_url<'a>(url: &'a str, parsers: &[MetadataType]) -> Result<ParseInfo<'a>> {
+    pub fn from_url<'a>(url: &'a str, options: &GenerationOptions) -> Result<ParseInfo<'a>> {
+        let language = options.preferred_content_language.as_deref();
+        let response = if options.request_json_ld {
+            get_html_or_json_ld(url, language, &options.request_options)
+                .map(|response| json_ld_document(&response).unwrap_or(response))
+        } else {
+            get_html(url, language, &options.request_options)
+        };
+        let raw_html = response.map_err(|error| match error {
+            CurlError::HttpStatus(status) => ReferenceGenerationError::HttpStatus { url: url.to_string(), status },
+            error => ReferenceGenerationError::CurlError(error),
+        })?;
+        Self::from_html(url, raw_html, options)
+    }
+
+    /// Parses HTML retrieved on behalf of the given URL,
+    /// e.g. from a cache or an archive.
+    pub fn from_html<'a>(url: &'a str, raw_html: String, options: &GenerationOptions) -> Result<ParseInfo<'a>> {
         use MetadataType::*;
-        let raw_html = get_html(url)?;
 
-        let schema_or_og = parsers.contains(&OpenGraph) || parsers.contains(&SchemaOrg);
+        let parsers = options.attribute_config.parsers_used();
+        let cache = options.response_cache.as_ref();
+
+        let schema_or_og = parsers.contains(&OpenGraph) || parsers.contains(&SchemaOrg) || parsers.contains(&HtmlMeta);
         let doi = parsers.contains(&Doi);
 
         let html = parse_html_from_string(raw_html.clone(), &schema_or_og);
-        let bib = doi::try_doi_to_bib(url, raw_html.as_str(), &doi);
+
+        // The DOI and the Zotero item are looked up concurrently.
+        let mut warnings = Vec::new();
+        let (bib, zotero) = thread::scope(|scope| {
+            let zotero = scope.spawn(|| {
+                (parsers.contains(&Zotero) && !zotero::should_skip_zotero(Some(url)))
+                    .then(|| {
+                        let endpoint = options.citoid_endpoint.as_deref().unwrap_or(zotero::CITOID_ENDPOINT);
+                        zotero::fetch_result(url, endpoint, cache, &options.request_options)
+                    })
+                    .flatten()
+            });
+            let bib = doi::try_doi_to_bib(url, raw_html.as_str(), &doi, cache, &options.request_options, &mut warnings);
+            (bib, zotero.join().unwrap())
+        });
 
         if (schema_or_og && html.is_err()) && (doi && bib.is_err()) {
-            return Err(ReferenceGenerationError::ParseFailure);
+            return Err(ReferenceGenerationError::ParseFailure {
+                url: Some(url.to_string()),
+                failures: parse_failures(&parsers, &html, &bib),
+            });
         }
 
+        let document = Html::parse_document(&raw_html);
         Ok(ParseInfo {
             url: Some(url),
+            json_ld: schema_org::collect_json_ld(&document),
+            document,
             raw_html: raw_html,
             html: html.ok(),
-            bibliography: bib.ok()
+            bibliography: bib.ok(),
+            zotero,
+            parsers,
+            warnings,
         })
     }
 
-    pub fn from_file(path: &str) -> Result<ParseInfo> {
-        let raw_html = fs::read_to_string(path)?;
+    /// Parses HTML read from a file, which was saved from the given URL.
+    /// As for pages retrieved from the URL, the DOI may be found in the
+    /// URL and Zotero is asked about it unless the site is blacklisted.
+    pub fn from_file_with_url<'a>(path: &str, url: &'a str, options: &GenerationOptions) -> Result<ParseInfo<'a>> {
+        let raw_html = charset::decode(&fs::read(path)?, None);
+        Self::from_html(url, raw_html, options)
+    }
+
+    pub fn from_file<'a>(path: &str, options: &GenerationOptions) -> Result<ParseInfo<'a>> {
+        let raw_html = charset::decode(&fs::read(path)?, None);
 
         // TODO: should we return ParseFailure here?
         let html = parse_html_from_string(raw_html.clone(), &true)?;
 
+        // Without a URL, a DOI can only be found in the HTML itself.
+        let parsers = options.attribute_config.parsers_used();
+        let doi = parsers.contains(&MetadataType::Doi);
+        let mut warnings = Vec::new();
+        let bib = doi::try_doi_to_bib("", raw_html.as_str(), &doi, options.response_cache.as_ref(), &options.request_options, &mut warnings);
+
+        let document = Html::parse_document(&raw_html);
         Ok(ParseInfo {
             url: None,
+            json_ld: schema_org::collect_json_ld(&document),
+            document,
             raw_html: raw_html,
             html: Some(html),
-            bibliography: None
+            bibliography: bib.ok(),
+            // Without a URL, there is nothing to ask Citoid about.
+            zotero: None,
+            parsers,
+            warnings,
         })
     }
 }
 
+/// Wraps a JSON-LD response, as returned when asking for the JSON-LD
+/// representation of a page, in a document from which the Schema.org
+/// parser reads it like inline JSON-LD. Returns None for HTML responses.
+fn json_ld_document(response: &str) -> Option<String> {
+    let json_ld: serde_json::Value = serde_json::from_str(response).ok()?;
+    if !(json_ld.is_object() || json_ld.is_array()) {
+        return None;
+    }
+    // `</` is escaped so that the JSON cannot end the script element early.
+    let script = json_ld.to_string().replace("</", "<\\/");
+    Some(format!("<html><head><script type=\"application/ld+json\">{script}</script></head></html>"))
+}
+
+/// Reason for which each of the parsers failed, in the order of the parsers.
+/// The parsers of HTML metadata share the failure to parse the page.
+fn parse_failures(
+    parsers: &[MetadataType],
+    html: &Result<HTML>,
+    bib: &Result<Bibliography>,
+) -> Vec<(MetadataType, String)> {
+    use MetadataType::*;
+
+    let reason = |error: &ReferenceGenerationError| match error {
+        ReferenceGenerationError::DoiError(error) => error.to_string(),
+        ReferenceGenerationError::HTMLParseError(error) => format!("HTML failed to parse ({error})"),
+        error => error.to_string(),
+    };
+    parsers
+        .iter()
+        .filter_map(|parser| {
+            let error = match parser {
+                OpenGraph | SchemaOrg | HtmlMeta => html.as_ref().err()?,
+                Doi => bib.as_ref().err()?,
+                DublinCore | Rdfa | Zotero => return None,
+            };
+            Some((*parser, reason(error)))
+        })
+        .collect()
+}
+
 /// Parses the web page into an HTML object using [`webpage`].
 pub fn parse_html_from_string(raw_html: String, contained: &bool) -> Result<HTML> {
     if !contained {
         return Err(ReferenceGenerationError::ParseSkip);
     }
 
-    let html = HTML::from_string(raw_html, None)?;
+    let fragment = is_fragment(&raw_html).then(|| Html::parse_fragment(&raw_html));
+    let mut html = HTML::from_string(raw_html, None)?;
+    if let Some(fragment) = fragment {
+        add_fragment_meta_tags(&fragment, &mut html);
+    }
     Ok(html)
 }
 
-/// Parse a string into a [`NaiveDate`] object
+/// Whether the page is a fragment lacking a `<head>`, e.g. an embed snippet.
+fn is_fragment(raw_html: &str) -> bool {
+    let re = Regex::new(r"(?i)<head[\s>/]").unwrap();
+    !re.is_match(raw_html)
+}
+
+/// Adds the meta tags of a fragment, which [`webpage`] only reads from
+/// the `<head>`. Inline JSON-LD is read by [`webpage`] wherever it is.
+fn add_fragment_meta_tags(fragment: &Html, html: &mut HTML) {
+    let selector = Selector::parse("meta[content]").unwrap();
+    for element in fragment.select(&selector) {
+        let element = element.value();
+        let property = element.attr("property").or_else(|| element.attr("name"));
+        let (Some(property), Some(content)) = (property, element.attr("content")) else {
+            continue;
+        };
+        if html.meta.contains_key(property) {
+            continue;
+        }
+
+        html.meta.insert(property.to_string(), content.to_string());
+        match property.strip_prefix("og:") {
+            Some(og_property) if !og_property.is_empty() => html.opengraph.extend(og_property, content.to_string()),
+            _ if property == "description" => html.description = Some(content.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Parse a string into a [`Date`] object. Besides RFC 3339 timestamps,
+/// partial dates (`2023-12-11`, `2023-12` and `2023`) are accepted.
 pub fn parse_date(date_str: &str) -> Option<Date> {
-    let dt = DateTime::parse_from_rfc3339(date_str).ok()?;
-    let dt_utc = Utc.from_utc_datetime(&dt.naive_utc());
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
+        let dt_utc = Utc.from_utc_datetime(&dt.naive_utc());
+        return Some(Date::DateTime(dt_utc));
+    }
 
-    Some(Date::DateTime(dt_utc))
+    if let Ok(naive_date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        return Some(Date::YearMonthDay(naive_date));
+    }
+
+    let parts: Vec<&str> = date_str.split('-').collect();
+    match parts.as_slice() {
+        [year, month] if year.len() == 4 && month.len() == 2 => {
+            let (year, month) = (year.parse().ok()?, month.parse().ok()?);
+            (1..=12).contains(&month).then_some(Date::YearMonth { year, month })
+        }
+        [year] if year.len() == 4 => year.parse().ok().map(Date::Year),
+        _ => None,
+    }
 }
 
 /// Implemented by parsers of different metadata formats
@@ -92,29 +267,57 @@ pub trait AttributeParser {
     fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute>;
 }
 
-/// Attempt to parse a single attribute
-fn parse(
+/// Attempt to parse a single attribute, returning it along
+/// with the metadata type it was found in.
+pub(crate) fn parse(
     parse_info: &ParseInfo,
     attribute_type: AttributeType,
     formats: &AttributePriority,
-) -> Option<Attribute> {
+) -> Option<(Attribute, MetadataType)> {
     for format in &formats.priority {
         let attribute = match format {
             MetadataType::OpenGraph => OpenGraph::parse_attribute(parse_info, attribute_type),
             MetadataType::SchemaOrg => SchemaOrg::parse_attribute(parse_info, attribute_type),
-            MetadataType::Doi => Doi::parse_attribute(parse_info, attribute_type)
-        };
-        if attribute.is_some() {
-            return attribute;
+            MetadataType::Doi => Doi::parse_attribute(parse_info, attribute_type),
+            MetadataType::HtmlMeta => HtmlMeta::parse_attribute(parse_info, attribute_type),
+            MetadataType::DublinCore => DublinCore::parse_attribute(parse_info, attribute_type),
+            MetadataType::Rdfa => Rdfa::parse_attribute(parse_info, attribute_type),
+            MetadataType::Zotero => Zotero::parse_attribute(parse_info, attribute_type),
+        }
+        .filter(Attribute::is_allowed)
+        .and_then(sanitize);
+        if let Some(attribute) = attribute {
+            return Some((attribute, *format));
         }
     }
 
     None
 }
 
+/// Parses every attribute from each metadata type on its own,
+/// regardless of the configured priorities.
+pub fn parse_all_sources(parse_info: &ParseInfo) -> MultiSourceAttributeCollection {
+    let sources = MetadataType::iter()
+        .map(|metadata_type| {
+            let priority = AttributePriority::new(&[metadata_type]);
+            let attributes = AttributeType::iter()
+                .filter_map(|attribute_type| {
+                    parse(parse_info, attribute_type, &priority).map(|(attribute, _)| (attribute_type, attribute))
+                })
+                .collect();
+            (metadata_type, attributes)
+        })
+        .collect();
+
+    MultiSourceAttributeCollection { sources }
+}
+
 #[derive(Clone)]
 pub struct AttributeCollection {
     pub attributes: HashMap<AttributeType, Attribute>,
+    /// Metadata type each parsed attribute was found in. Attributes
+    /// obtained otherwise, e.g. from a Fediverse post, have no entry.
+    pub provenance: Provenance,
 }
 impl AttributeCollection {
     /// Initialize an [`AttributeCollection`] from the supplied
@@ -122,6 +325,7 @@ impl AttributeCollection {
     pub fn initialize(config: &AttributeConfig, parse_info: &ParseInfo) -> Self {
         Self {
             attributes: HashMap::new(),
+            provenance: HashMap::new(),
         }
         .add_all(config, parse_info)
     }
@@ -131,36 +335,114 @@ impl AttributeCollection {
         self.attributes.get(&attribute_type)
     }
 
-    /// Adds a single [`Attribute`] to the collection.
-    fn add(
-        mut self,
-        attribute_type: AttributeType,
-        config: &AttributeConfig,
-        parse_info: &ParseInfo,
-    ) -> Self {
-        let priorities = config.get(attribute_type);
-        let attribute = parse(
-            parse_info,
-            attribute_type,
-            &priorities.clone().unwrap_or_default(),
-        );
-        self.insert_if(attribute_type, attribute);
+    /// Merges another collection into this one. Attributes already
+    /// present in this collection take precedence.
+    pub fn merge(mut self, other: AttributeCollection) -> Self {
+        for (attribute_type, attribute) in other.attributes {
+            if self.attributes.contains_key(&attribute_type) {
+                continue;
+            }
+            self.attributes.insert(attribute_type, attribute);
+            if let Some(metadata_type) = other.provenance.get(&attribute_type) {
+                self.provenance.insert(attribute_type, *metadata_type);
+            }
+        }
+        self
+    }
 
+    /// Applies the transformation registered for each attribute type, if any.
+    pub fn transform(mut self, transforms: &HashMap<AttributeType, AttributeTransform>) -> Self {
+        for (attribute_type, transform) in transforms {
+            if let Some(attribute) = self.attributes.remove(attribute_type) {
+                self.attributes.insert(*attribute_type, transform(attribute));
+            }
+        }
         self
     }
 
+    /// Adds a single [`Attribute`] to the collection.
+    fn add(&mut self, attribute_type: AttributeType, config: &AttributeConfig, parse_info: &ParseInfo) {
+        let default_priority = AttributePriority::default();
+        let priority = config.get(attribute_type).as_ref().unwrap_or(&default_priority);
+        let parsed = parse(parse_info, attribute_type, priority)
+            .or_else(|| parse(parse_info, attribute_type, config.fallback.as_ref()?));
+        if let Some((attribute, metadata_type)) = parsed {
+            self.attributes.insert(attribute_type, attribute);
+            self.provenance.insert(attribute_type, metadata_type);
+        }
+    }
+
     /// Adds the [`Attribute`]s corresponding to all [`AttributeType`] variants to
     /// the collection.
     fn add_all(mut self, config: &AttributeConfig, parse_info: &ParseInfo) -> Self {
-        AttributeType::iter().for_each(|x| {
-            self = self.clone().add(x, config, parse_info);
-        });
+        AttributeType::iter().for_each(|x| self.add(x, config, parse_info));
         self
     }
+}
 
-    fn insert_if(&mut self, attribute_type: AttributeType, attribute: Option<Attribute>) {
-        if attribute.is_some() {
-            self.attributes.insert(attribute_type, attribute.unwrap());
-        }
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::parse_failures;
+    use crate::doi::DoiError;
+    use crate::generator::{from_url, ArchiveOptions, MetadataType, ReferenceGenerationError};
+    use crate::mock_server::{MockServer, Response};
+    use crate::GenerationOptions;
+
+    #[test]
+    fn json_ld_requested_using_accept_header() {
+        let server = MockServer::start(|request| {
+            let accept = request.header("Accept").unwrap_or_default();
+            if accept.starts_with("application/ld+json") {
+                assert_eq!(request.header("Accept-Language"), Some("nb"));
+                Response::ok(
+                    r#"{"@context": "https://schema.org", "@type": "NewsArticle", "headline": "Ferry route reopens </script> today",
+                        "author": {"@type": "Person", "name": "Ingrid Solberg"}, "datePublished": "2024-04-03"}"#,
+                )
+                .with_header("Content-Type", "application/ld+json")
+            } else {
+                Response::ok(r#"<html><head><meta property="og:title" content="Ferry route reopens | Tidewatch"></head></html>"#)
+            }
+        });
+        let mut options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            preferred_content_language: Some("nb".to_string()),
+            ..Default::default()
+        };
+
+        let reference = from_url(&server.url("/news/ferry"), &options).unwrap();
+        assert_eq!(reference.title(), Some("Ferry route reopens | Tidewatch"));
+
+        options.request_json_ld = true;
+        let reference = from_url(&server.url("/news/ferry"), &options).unwrap();
+        assert_eq!(reference.title(), Some("Ferry route reopens </script> today"));
+        assert_eq!(reference.authors().len(), 1);
+    }
+
+    #[test]
+    fn parse_failures_collected_per_parser() {
+        let html = Err(ReferenceGenerationError::HTMLParseError(io::Error::other("unexpected end of input")));
+        let bib = Err(ReferenceGenerationError::DoiError(DoiError::DoiNotInHtmlError));
+        let parsers = [MetadataType::OpenGraph, MetadataType::Doi, MetadataType::DublinCore, MetadataType::SchemaOrg];
+
+        let failures = parse_failures(&parsers, &html, &bib);
+        assert_eq!(
+            failures,
+            [
+                (MetadataType::OpenGraph, "HTML failed to parse (unexpected end of input)".to_string()),
+                (MetadataType::Doi, "DOI was not found in HTML".to_string()),
+                (MetadataType::SchemaOrg, "HTML failed to parse (unexpected end of input)".to_string()),
+            ]
+        );
+
+        let error = ReferenceGenerationError::ParseFailure { url: Some("https://example.com/news".to_string()), failures };
+        assert_eq!(error.url(), Some("https://example.com/news"));
+        assert_eq!(error.parse_failures().len(), 3);
+        assert_eq!(
+            error.to_string(),
+            "All provided parsers failed for https://example.com/news: opengraph: HTML failed to parse (unexpected end of input); \
+             doi: DOI was not found in HTML; schema_org: HTML failed to parse (unexpected end of input)"
+        );
     }
 }
@@ -0,0 +1,151 @@
+//! Splitting of the names of persons into given and family names for
+//! the citation formats which list them separately, e.g. the `|last=`
+//! and `|first=` parameters of Wiki citations.
+
+/// Particles which belong to the family name, e.g. `van Beethoven`.
+const PARTICLES: &[&str] = &["van", "von", "de", "der", "den", "da", "di", "del", "della", "la", "le", "du", "ten", "ter"];
+
+/// Generational suffixes, which follow the family name but are not part of it.
+const SUFFIXES: &[&str] = &["jr", "jr.", "sr", "sr.", "ii", "iii", "iv"];
+
+/// Name of a person split into its parts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersonName {
+    /// Given names, which may be initials, e.g. `J. R. R.`
+    pub given: String,
+    /// Family name including any particles, e.g. `van Beethoven`
+    pub family: String,
+    /// Generational suffix, e.g. `Jr.`
+    pub suffix: Option<String>,
+}
+
+impl PersonName {
+    /// Parses names in the `First Last` and `Last, First` orders. Names
+    /// which cannot be split, e.g. single-word bylines, are not parsed.
+    pub fn parse(name: &str) -> Option<PersonName> {
+        let parts: Vec<&str> = name.split(',').map(str::trim).filter(|part| !part.is_empty()).collect();
+
+        match parts.as_slice() {
+            // `Martin Luther King, Jr.`
+            [name, suffix] if is_suffix(suffix) => Self::parse_natural_order(name, Some(suffix)),
+            // `Doe, Jane` or `Doe, Jr., Jane`
+            [family, given] | [family, _, given] if !family.contains(' ') || has_particle(family) => {
+                let suffix = (parts.len() == 3).then(|| parts[1].to_string());
+                Some(PersonName { given: given.to_string(), family: family.to_string(), suffix })
+            }
+            [name] => Self::parse_natural_order(name, None),
+            _ => None,
+        }
+    }
+
+    fn parse_natural_order(name: &str, suffix: Option<&str>) -> Option<PersonName> {
+        let mut words: Vec<&str> = name.split_whitespace().collect();
+        let mut suffix = suffix.map(str::to_string);
+        if suffix.is_none() && words.len() > 2 && words.last().is_some_and(|word| is_suffix(word)) {
+            suffix = words.pop().map(str::to_string);
+        }
+
+        // The family name starts at the first particle following the given
+        // names, e.g. `Ludwig van Beethoven`, or is otherwise the last word.
+        let family_start = (1..words.len())
+            .find(|&i| is_particle(words[i]) && i + 1 < words.len())
+            .unwrap_or(words.len().checked_sub(1)?);
+        if family_start == 0 {
+            return None;
+        }
+
+        Some(PersonName {
+            given: words[..family_start].join(" "),
+            family: words[family_start..].join(" "),
+            suffix,
+        })
+    }
+
+    /// Given names followed by the suffix, e.g. `Martin Luther Jr.`,
+    /// as the suffix is put along with the given names by Wiki citations.
+    pub fn given_with_suffix(&self) -> String {
+        match &self.suffix {
+            Some(suffix) => format!("{} {}", self.given, suffix),
+            None => self.given.clone(),
+        }
+    }
+
+    /// Full name in the `First Last` order, e.g. `Martin Luther King Jr.`
+    pub fn full_name(&self) -> String {
+        match &self.suffix {
+            Some(suffix) => format!("{} {} {}", self.given, self.family, suffix),
+            None => format!("{} {}", self.given, self.family),
+        }
+    }
+
+    /// Initials of the given names without periods, e.g. `JRR`.
+    pub fn initials(&self) -> String {
+        self.given
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '.')
+            .filter_map(|name| name.chars().find(|c| c.is_alphabetic()))
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+}
+
+fn is_particle(word: &str) -> bool {
+    PARTICLES.contains(&word.to_lowercase().as_str())
+}
+
+fn has_particle(name: &str) -> bool {
+    name.split_whitespace().any(is_particle)
+}
+
+fn is_suffix(word: &str) -> bool {
+    SUFFIXES.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersonName;
+
+    #[test]
+    fn parse_names() {
+        #[rustfmt::skip]
+        let cases = [
+            ("Jane Doe",                     Some(("Jane", "Doe", None))),
+            ("Henry S. Frank",               Some(("Henry S.", "Frank", None))),
+            ("J. R. R. Tolkien",             Some(("J. R. R.", "Tolkien", None))),
+            ("J.R.R. Tolkien",               Some(("J.R.R.", "Tolkien", None))),
+            ("Ludwig van Beethoven",         Some(("Ludwig", "van Beethoven", None))),
+            ("Guido van der Berg",           Some(("Guido", "van der Berg", None))),
+            ("Leonardo da Vinci",            Some(("Leonardo", "da Vinci", None))),
+            ("Maria de la Cruz",             Some(("Maria", "de la Cruz", None))),
+            ("Dick Van Dyke",                Some(("Dick", "Van Dyke", None))),
+            ("Sammy Davis Jr.",              Some(("Sammy", "Davis", Some("Jr.")))),
+            ("John Paul Jones III",          Some(("John Paul", "Jones", Some("III")))),
+            ("Martin Luther King, Jr.",      Some(("Martin Luther", "King", Some("Jr.")))),
+            ("Doe, Jane",                    Some(("Jane", "Doe", None))),
+            ("Tolkien, J. R. R.",            Some(("J. R. R.", "Tolkien", None))),
+            ("van Beethoven, Ludwig",        Some(("Ludwig", "van Beethoven", None))),
+            ("King, Jr., Martin Luther",     Some(("Martin Luther", "King", Some("Jr.")))),
+            ("Anne-Marie Dijkstra",          Some(("Anne-Marie", "Dijkstra", None))),
+            ("Cher",                         None),
+            ("  ",                           None),
+            ("Reuters staff, Copenhagen bureau", None),
+        ];
+
+        for (name, expected) in cases {
+            let expected = expected.map(|(given, family, suffix): (&str, &str, Option<&str>)| PersonName {
+                given: given.to_string(),
+                family: family.to_string(),
+                suffix: suffix.map(str::to_string),
+            });
+            assert_eq!(PersonName::parse(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn name_forms() {
+        let name = PersonName::parse("King, Jr., Martin Luther").unwrap();
+        assert_eq!(name.given_with_suffix(), "Martin Luther Jr.");
+        assert_eq!(name.full_name(), "Martin Luther King Jr.");
+        assert_eq!(name.initials(), "ML");
+        assert_eq!(PersonName::parse("J.R.R. Tolkien").unwrap().initials(), "JRR");
+    }
+}
@@ -0,0 +1,67 @@
+//! Support for citing questions on Q&A sites such as Stack Overflow.
+//!
+//! Q&A pages describe the question using a Schema.org `Question`, usually
+//! the `mainEntity` of a `QAPage`, whereas their Open Graph metadata is
+//! generic, e.g. a title prefixed by a tag and suffixed by the site name.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::attribute::{Attribute, AttributeType};
+use crate::generator::MetadataType;
+use crate::parser::{parse_date, AttributeCollection, MetadataKey, ParseInfo};
+use crate::schema_org::author::create_author_attribute;
+use crate::schema_org::{entities, has_type};
+use crate::url_site::site_from_url;
+
+/// Finds the question, given either as the main entity of the `QAPage`
+/// or as an entity of its own, along with the `QAPage` if any.
+fn find_question<'a>(schema_values: &[&'a Value]) -> Option<(Option<&'a Value>, &'a Value)> {
+    let entities = entities(schema_values);
+    let main_entity = entities
+        .iter()
+        .filter(|entity| has_type(entity, &["QAPage"]))
+        .map(|qa_page| (Some(*qa_page), &qa_page["mainEntity"]))
+        .find(|(_, main_entity)| has_type(main_entity, &["Question"]));
+
+    main_entity.or_else(|| {
+        let question = entities.into_iter().find(|entity| has_type(entity, &["Question"]))?;
+        Some((None, question))
+    })
+}
+
+/// If the page describes a question, creates the attributes describing it:
+/// the question as the title and its asker as the author.
+pub fn find_attributes(parse_info: &ParseInfo) -> Option<AttributeCollection> {
+    let schema_values: Vec<&Value> = parse_info.json_ld.iter().collect();
+    let (qa_page, question) = find_question(&schema_values)?;
+
+    let mut attributes = HashMap::new();
+    if let Some(title) = question["name"].as_str().map(str::trim).filter(|title| !title.is_empty()) {
+        attributes.insert(AttributeType::Title, Attribute::Title(title.to_string()));
+    }
+    if let Some(authors) = create_author_attribute(question, &[MetadataKey { key: "author" }]) {
+        attributes.insert(AttributeType::Author, authors);
+    }
+    // Stack Exchange gives the date without a time zone, so only the day is kept.
+    let date = ["dateCreated", "datePublished"].iter().find_map(|key| {
+        let date = question[key].as_str()?;
+        parse_date(date).or_else(|| parse_date(date.split_once('T')?.0))
+    });
+    if let Some(date) = date {
+        attributes.insert(AttributeType::Date, Attribute::Date(date));
+    }
+
+    // The URL of the page is preferred, as the question may lack one.
+    let url = parse_info
+        .url
+        .or_else(|| question["url"].as_str())
+        .or_else(|| qa_page?["url"].as_str());
+    if let Some(site) = url.and_then(site_from_url) {
+        attributes.insert(AttributeType::Site, Attribute::Site(site));
+    }
+
+    let provenance = attributes.keys().map(|attribute_type| (*attribute_type, MetadataType::SchemaOrg)).collect();
+    Some(AttributeCollection { attributes, provenance })
+}
@@ -0,0 +1,242 @@
+//! Parser for [RDFa] annotations, i.e. `property` and `typeof` attributes
+//! on the elements of the page (e.g. `<h1 property="dc:title">`), with
+//! which e.g. government and EU institution pages give their metadata.
+//!
+//! Only the Dublin Core and Schema.org vocabularies are recognized, given
+//! by their usual prefixes (`dc:`, `dcterms:` and `schema:`), by full IRIs
+//! or by a `vocab` attribute naming Schema.org.
+//!
+//! [RDFa]: https://www.w3.org/TR/rdfa-lite/
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::attribute::{Attribute, AttributeType, Author};
+use crate::parser::{parse_date, AttributeParser, ParseInfo};
+
+/// Prefixes and IRIs of the recognized vocabularies, by the prefix
+/// their properties are normalized to. Dublin Core elements and terms
+/// are treated alike.
+#[rustfmt::skip]
+const VOCABULARIES: &[(&str, &[&str])] = &[
+    ("dc:",     &["dc:", "dcterms:", "http://purl.org/dc/terms/", "http://purl.org/dc/elements/1.1/"]),
+    ("schema:", &["schema:", "http://schema.org/", "https://schema.org/"]),
+];
+
+/// Properties giving each attribute, in order of preference.
+fn properties(attribute_type: AttributeType) -> &'static [&'static str] {
+    match attribute_type {
+        AttributeType::Title     => &["dc:title", "schema:headline", "schema:name"],
+        AttributeType::Author    => &["dc:creator", "schema:author", "schema:creator"],
+        AttributeType::Date      => &["dc:issued", "schema:datePublished", "dc:date", "dc:created", "schema:dateCreated"],
+        AttributeType::Publisher => &["dc:publisher", "schema:publisher"],
+        AttributeType::Language  => &["dc:language", "schema:inLanguage"],
+        _ => &[],
+    }
+}
+
+pub struct Rdfa;
+
+/// Normalizes a property or type, e.g. `dcterms:title` or
+/// `http://purl.org/dc/terms/title` to `dc:title`. Terms without a
+/// prefix belong to the vocabulary given by the `vocab` attribute.
+fn normalize(term: &str, vocab: Option<&str>) -> Option<String> {
+    let term = match vocab {
+        Some(vocab) if !term.contains(':') => format!("{vocab}{term}"),
+        _ => term.to_string(),
+    };
+    VOCABULARIES.iter().find_map(|(prefix, namespaces)| {
+        let name = namespaces.iter().find_map(|namespace| term.strip_prefix(namespace))?;
+        Some(format!("{prefix}{name}"))
+    })
+}
+
+/// The vocabulary in effect for the element, given by the nearest `vocab` attribute.
+fn vocab<'a>(element: &ElementRef<'a>) -> Option<&'a str> {
+    std::iter::once(*element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .find_map(|element| element.value().attr("vocab"))
+}
+
+/// Normalized terms of the given attribute (`property` or `typeof`) of the element.
+fn terms(element: &ElementRef, attribute: &str) -> Vec<String> {
+    let vocab = vocab(element);
+    element
+        .value()
+        .attr(attribute)
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|term| normalize(term, vocab))
+        .collect()
+}
+
+/// Elements having a property with a `typeof` describe another resource,
+/// e.g. an author, whose properties are not those of the page.
+fn describes_resource(element: &ElementRef) -> bool {
+    element.value().attr("property").is_some() && element.value().attr("typeof").is_some()
+}
+
+/// Whether the properties of the element are those of the page itself.
+fn is_top_level(element: &ElementRef) -> bool {
+    !element.ancestors().filter_map(ElementRef::wrap).any(|ancestor| describes_resource(&ancestor))
+}
+
+/// Value of the property given by the element: its `content` (or for
+/// `<time>` elements its `datetime`) attribute, or else its text.
+fn value(element: &ElementRef) -> Option<String> {
+    let attribute = element.value().attr("content").or_else(|| element.value().attr("datetime"));
+    let value = match attribute {
+        Some(attribute) => attribute.trim().to_string(),
+        None => element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" "),
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+/// Top-level elements giving the property, in document order.
+fn elements<'a>(document: &'a Html, property: &str) -> Vec<ElementRef<'a>> {
+    let selector = Selector::parse("[property]").unwrap();
+    document
+        .select(&selector)
+        .filter(|element| is_top_level(element))
+        .filter(|element| terms(element, "property").iter().any(|term| term == property))
+        .collect()
+}
+
+/// Name of the resource described by the element, e.g. of a
+/// `schema:Person`, or the value of the element itself.
+fn resource_name(element: &ElementRef) -> Option<String> {
+    if element.value().attr("typeof").is_none() {
+        return value(element);
+    }
+    let selector = Selector::parse("[property]").unwrap();
+    element
+        .select(&selector)
+        .find(|property| terms(property, "property").iter().any(|term| term == "schema:name" || term == "dc:title"))
+        .and_then(|name| value(&name))
+}
+
+fn find_authors(document: &Html, properties: &[&str]) -> Option<Attribute> {
+    let authors: Vec<Author> = properties
+        .iter()
+        .map(|property| {
+            elements(document, property)
+                .iter()
+                .filter_map(|element| {
+                    let name = resource_name(element)?;
+                    let types = terms(element, "typeof");
+                    Some(if types.iter().any(|t| t == "schema:Person") {
+                        Author::Person(name)
+                    } else if types.iter().any(|t| t.ends_with("Organization")) {
+                        Author::Organization(name)
+                    } else {
+                        Author::Generic(name)
+                    })
+                })
+                .collect::<Vec<Author>>()
+        })
+        .find(|authors| !authors.is_empty())?;
+    Some(Attribute::Authors(authors))
+}
+
+fn first_value(document: &Html, properties: &[&str]) -> Option<String> {
+    properties
+        .iter()
+        .flat_map(|property| elements(document, property))
+        .find_map(|element| resource_name(&element))
+}
+
+/// Type of the page, given by the first top-level `typeof`, e.g. `NewsArticle`.
+fn find_type(document: &Html) -> Option<String> {
+    let selector = Selector::parse("[typeof]").unwrap();
+    document
+        .select(&selector)
+        .filter(|element| is_top_level(element) && !describes_resource(element))
+        .flat_map(|element| terms(&element, "typeof"))
+        .find_map(|term| term.strip_prefix("schema:").map(str::to_string))
+}
+
+impl AttributeParser for Rdfa {
+    fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
+        let document = &parse_info.document;
+        let properties = properties(attribute_type);
+
+        match attribute_type {
+            AttributeType::Author => find_authors(document, properties),
+            AttributeType::Date => properties
+                .iter()
+                .flat_map(|property| elements(document, property))
+                .find_map(|element| parse_date(&value(&element)?))
+                .map(Attribute::Date),
+            AttributeType::Type => find_type(document).map(Attribute::Type),
+            AttributeType::Title => first_value(document, properties).map(Attribute::Title),
+            AttributeType::Publisher => first_value(document, properties).map(Attribute::Publisher),
+            AttributeType::Language => first_value(document, properties).map(Attribute::Language),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::Rdfa;
+    use crate::attribute::{Attribute, AttributeType, Author, Date};
+    use crate::parser::{AttributeParser, ParseInfo};
+
+    fn parse_info(body: &str) -> ParseInfo<'static> {
+        let raw_html = format!("<html><head></head><body>{body}</body></html>");
+        ParseInfo {
+            url: None,
+            document: Html::parse_document(&raw_html),
+            raw_html,
+            html: None,
+            json_ld: Vec::new(),
+            bibliography: None,
+            zotero: None,
+            parsers: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn nested_resources() {
+        let parse_info = parse_info(
+            r#"<div vocab="https://schema.org/" typeof="Article">
+                 <h1 property="headline">Offshore wind capacity doubles</h1>
+                 <span property="author" typeof="Person"><span property="name">Lena Berg</span></span>
+                 <span property="author" typeof="Organization"><span property="name">Energy Desk</span></span>
+                 <time property="datePublished" datetime="2024-05-14">14 May 2024</time>
+               </div>"#,
+        );
+        let parse = |attribute_type| Rdfa::parse_attribute(&parse_info, attribute_type);
+
+        // The names of the authors are not mistaken for the title.
+        assert_eq!(parse(AttributeType::Title), Some(Attribute::Title("Offshore wind capacity doubles".to_string())));
+        assert_eq!(
+            parse(AttributeType::Author),
+            Some(Attribute::Authors(vec![
+                Author::Person("Lena Berg".to_string()),
+                Author::Organization("Energy Desk".to_string()),
+            ]))
+        );
+        assert_eq!(
+            parse(AttributeType::Date),
+            Some(Attribute::Date(Date::YearMonthDay(chrono::NaiveDate::from_ymd_opt(2024, 5, 14).unwrap())))
+        );
+        assert_eq!(parse(AttributeType::Type), Some(Attribute::Type("Article".to_string())));
+    }
+
+    #[test]
+    fn dublin_core_iris() {
+        let parse_info = parse_info(
+            r#"<span property="http://purl.org/dc/terms/title">Annual report 2023</span>
+               <meta property="dcterms:language" content="da">
+               <meta property="og:title" content="Annual report">"#,
+        );
+        let parse = |attribute_type| Rdfa::parse_attribute(&parse_info, attribute_type);
+
+        assert_eq!(parse(AttributeType::Title), Some(Attribute::Title("Annual report 2023".to_string())));
+        assert_eq!(parse(AttributeType::Language), Some(Attribute::Language("da".to_string())));
+        assert_eq!(parse(AttributeType::Publisher), None);
+    }
+}
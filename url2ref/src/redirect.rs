@@ -0,0 +1,79 @@
+//! Expansion of shortened URLs (e.g. `t.co` and `bit.ly` links), so that
+//! the URL finally redirected to is the one cited.
+
+use crate::curl;
+use crate::generator::{ReferenceGenerationError, RequestOptions};
+
+/// Follows the redirects of the URL and returns the URL finally arrived at.
+/// A HEAD request is tried first, as the page itself is not needed. Some
+/// servers do not support HEAD requests, so GET is used if it fails.
+pub fn expand_url(short_url: &str, options: &RequestOptions) -> Result<String, ReferenceGenerationError> {
+    match curl::resolve_redirects(short_url, true, options) {
+        Ok((response_code, final_url)) if response_code < 400 => Ok(final_url),
+        _ => {
+            let (_, final_url) = curl::resolve_redirects(short_url, false, options)?;
+            Ok(final_url)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::expand_url;
+    use crate::attribute::Attribute;
+    use crate::generator::{from_url, ArchiveOptions, RequestOptions};
+    use crate::mock_server::{MockServer, Response};
+    use crate::{GenerationOptions, Reference};
+
+    #[test]
+    fn expand_short_url() {
+        let methods = Arc::new(Mutex::new(Vec::new()));
+        let received = methods.clone();
+        let server = MockServer::start(move |request| {
+            received.lock().unwrap().push(request.method.clone());
+            match request.path.as_str() {
+                "/x7Kq" => Response::redirect("/story?utm_source=short"),
+                "/story?utm_source=short" => Response::redirect("/news/harbour"),
+                "/news/harbour" => Response::ok("<html></html>"),
+                _ => Response::status(404),
+            }
+        });
+
+        assert_eq!(expand_url(&server.url("/x7Kq"), &RequestOptions::default()).unwrap(), server.url("/news/harbour"));
+        assert_eq!(*methods.lock().unwrap(), ["HEAD", "HEAD", "HEAD"]);
+    }
+
+    #[test]
+    fn expand_short_url_without_head_support() {
+        let server = MockServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("HEAD", _) => Response::status(405),
+            (_, "/x7Kq") => Response::redirect("/news/harbour"),
+            (_, "/news/harbour") => Response::ok("<html></html>"),
+            _ => Response::status(404),
+        });
+
+        assert_eq!(expand_url(&server.url("/x7Kq"), &RequestOptions::default()).unwrap(), server.url("/news/harbour"));
+    }
+
+    #[test]
+    fn generate_from_short_url() {
+        let page = r#"<html><head><meta property="og:title" content="Harbour expansion approved"></head></html>"#;
+        let server = MockServer::serve(vec![
+            ("/x7Kq", Response::redirect("/news/harbour")),
+            ("/news/harbour", Response::ok(page)),
+        ]);
+
+        let options = GenerationOptions {
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            expand_short_urls: true,
+            ..Default::default()
+        };
+        let Reference::NewsArticle { title, url, .. } = from_url(&server.url("/x7Kq"), &options).unwrap() else {
+            panic!("Expected a news article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Harbour expansion approved".to_string())));
+        assert_eq!(url, Some(Attribute::Url(server.url("/news/harbour"))));
+    }
+}
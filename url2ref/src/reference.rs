@@ -1,105 +1,1271 @@
 //! [`Reference`] definitions
 
-use crate::attribute::Attribute;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::attribute::{Attribute, AttributeType, Author, Date, Translation};
 use crate::citation::*;
+use crate::person_name::PersonName;
+
+/// Citation formats a [`Reference`] can be rendered in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CitationFormat {
+    /// MediaWiki markup using the {{cite web}} family of templates
+    #[default]
+    Wiki,
+    /// BibTeX markup
+    BibTeX,
+    /// Plain text without markup
+    Plain,
+    /// Vancouver style, common in medicine
+    Vancouver,
+    /// Harvard style, as described by Cite Them Right
+    Harvard,
+    /// Markdown link followed by the author and date
+    Markdown,
+    /// The raw attribute values as JSON, see [`Reference::to_json`]
+    Json,
+    /// CSL-JSON, as used by Zotero and Pandoc
+    CslJson,
+}
+impl CitationFormat {
+    /// All supported formats.
+    pub const ALL: &'static [CitationFormat] = &[
+        CitationFormat::Wiki,
+        CitationFormat::BibTeX,
+        CitationFormat::Plain,
+        CitationFormat::Vancouver,
+        CitationFormat::Harvard,
+        CitationFormat::Markdown,
+        CitationFormat::Json,
+        CitationFormat::CslJson,
+    ];
+
+    /// Lowercase name of the format, as accepted by [`CitationFormat::from_str`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            CitationFormat::Wiki => "wiki",
+            CitationFormat::BibTeX => "bibtex",
+            CitationFormat::Plain => "plain",
+            CitationFormat::Vancouver => "vancouver",
+            CitationFormat::Harvard => "harvard",
+            CitationFormat::Markdown => "markdown",
+            CitationFormat::Json => "json",
+            CitationFormat::CslJson => "csl-json",
+        }
+    }
+
+    /// Whether the format is meant to be read as is rather than processed
+    /// further, e.g. by LaTeX or MediaWiki.
+    pub fn is_human_readable(&self) -> bool {
+        matches!(self, CitationFormat::Plain | CitationFormat::Vancouver | CitationFormat::Harvard)
+    }
+}
+impl fmt::Display for CitationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options for rendering citations.
+#[derive(Clone, Debug, Default)]
+pub struct FormatOptions {
+    /// Render dates of the last week relative to now (e.g. `3 days ago`)
+    /// in plain citations only, for informal references. Other formats,
+    /// e.g. Harvard, whose citations are found by their year, keep
+    /// absolute dates.
+    pub relative_dates: bool,
+    /// Maximum length of titles in characters. Longer titles are cut at a
+    /// word boundary and marked with `…` in human-readable formats, while
+    /// titles in markup formats (e.g. BibTeX) are always kept whole.
+    pub max_title_chars: Option<usize>,
+    /// Time from which relative dates are computed. Defaults to the current
+    /// time, see [`crate::GenerationOptions::clock`].
+    pub now: Option<DateTime<Utc>>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Unknown citation format: {0}")]
+pub struct UnknownCitationFormat(pub String);
+
+impl FromStr for CitationFormat {
+    type Err = UnknownCitationFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CitationFormat::ALL
+            .iter()
+            .find(|format| format.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| UnknownCitationFormat(s.to_string()))
+    }
+}
+
+/// Kinds of references, corresponding to the variants of [`Reference`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferenceKind {
+    NewsArticle,
+    ScholarlyArticle,
+    VideoObject,
+    PodcastEpisode,
+    GenericReference,
+}
+impl ReferenceKind {
+    /// Types of the attributes which references of this kind hold, e.g. for
+    /// building forms to edit them. Attributes derived during generation,
+    /// e.g. translations, are left out, as they have no type.
+    pub fn supported_attributes(&self) -> &'static [AttributeType] {
+        use AttributeType::*;
+        match self {
+            ReferenceKind::NewsArticle => &[
+                Title, Author, Date, OriginalDate, Language, Site, Section, Place, Url, Publisher,
+                ArchiveUrl, ArchiveDate, AccessDate, ImageCredit,
+            ],
+            ReferenceKind::ScholarlyArticle => &[
+                Title, Author, Affiliations, Date, OriginalDate, Language, Url, Journal, Volume, Issue, Pages,
+                Publisher, ArchiveUrl, ArchiveDate, AccessDate,
+            ],
+            ReferenceKind::VideoObject => &[
+                Title, Author, Date, OriginalDate, Language, Site, Url, Duration, ArchiveUrl, ArchiveDate, AccessDate,
+            ],
+            // The episode number is given as the issue.
+            ReferenceKind::PodcastEpisode => &[
+                Title, Author, Date, OriginalDate, Language, Site, Series, Issue, Url, Duration,
+                ArchiveUrl, ArchiveDate, AccessDate,
+            ],
+            ReferenceKind::GenericReference => &[
+                Title, Author, Date, OriginalDate, Language, Site, Section, Url, ArchiveUrl, ArchiveDate, AccessDate,
+                ImageCredit,
+            ],
+        }
+    }
+}
 
 /// Enum for types of references.
 /// The names generally mirror the ones in the Schema.org vocabulary.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum Reference {
     NewsArticle {
         title: Option<Attribute>,
         translated_title: Option<Attribute>,
         author: Option<Attribute>,
         date: Option<Attribute>,
+        original_date: Option<Attribute>,
         language: Option<Attribute>,
         site: Option<Attribute>,
+        /// Translation of the site name, as an [`Attribute::TranslatedSite`]
+        translated_site: Option<Attribute>,
+        section: Option<Attribute>,
+        /// Place the article was reported from
+        place: Option<Attribute>,
         url: Option<Attribute>,
         publisher: Option<Attribute>,
         archive_url: Option<Attribute>,
         archive_date: Option<Attribute>,
+        /// Status of the URL, as an [`Attribute::UrlStatus`], given along with the archive URL
+        url_status: Option<Attribute>,
+        access_date: Option<Attribute>,
+        page_count: Option<Attribute>,
+        /// Credit of the main image, as an [`Attribute::ImageCredit`]
+        image_credit: Option<Attribute>,
+        /// Hash of the fetched page, as an [`Attribute::ContentHash`]
+        content_hash: Option<Attribute>,
     },
     ScholarlyArticle {
         title: Option<Attribute>,
         translated_title: Option<Attribute>,
         author: Option<Attribute>,
+        /// Institutions of the authors, as an [`Attribute::Affiliations`]
+        affiliations: Option<Attribute>,
         date: Option<Attribute>,
+        original_date: Option<Attribute>,
         language: Option<Attribute>,
         url: Option<Attribute>,
         journal: Option<Attribute>,
+        volume: Option<Attribute>,
+        issue: Option<Attribute>,
+        pages: Option<Attribute>,
         publisher: Option<Attribute>,
+        /// Status given by a notice updating the article, as an [`Attribute::Status`], e.g. `Retracted`
+        status: Option<Attribute>,
         archive_url: Option<Attribute>,
         archive_date: Option<Attribute>,
+        /// Status of the URL, as an [`Attribute::UrlStatus`], given along with the archive URL
+        url_status: Option<Attribute>,
+        access_date: Option<Attribute>,
+        /// Hash of the fetched page, as an [`Attribute::ContentHash`]
+        content_hash: Option<Attribute>,
+    },
+    VideoObject {
+        title: Option<Attribute>,
+        translated_title: Option<Attribute>,
+        author: Option<Attribute>,
+        date: Option<Attribute>,
+        original_date: Option<Attribute>,
+        language: Option<Attribute>,
+        site: Option<Attribute>,
+        /// Translation of the site name, as an [`Attribute::TranslatedSite`]
+        translated_site: Option<Attribute>,
+        url: Option<Attribute>,
+        duration: Option<Attribute>,
+        archive_url: Option<Attribute>,
+        archive_date: Option<Attribute>,
+        /// Status of the URL, as an [`Attribute::UrlStatus`], given along with the archive URL
+        url_status: Option<Attribute>,
+        access_date: Option<Attribute>,
+        /// Hash of the fetched page, as an [`Attribute::ContentHash`]
+        content_hash: Option<Attribute>,
+    },
+    PodcastEpisode {
+        title: Option<Attribute>,
+        translated_title: Option<Attribute>,
+        author: Option<Attribute>,
+        date: Option<Attribute>,
+        original_date: Option<Attribute>,
+        language: Option<Attribute>,
+        site: Option<Attribute>,
+        /// Translation of the site name, as an [`Attribute::TranslatedSite`]
+        translated_site: Option<Attribute>,
+        /// Show the episode is part of
+        series: Option<Attribute>,
+        /// Episode number, as an [`Attribute::Issue`]
+        episode: Option<Attribute>,
+        url: Option<Attribute>,
+        duration: Option<Attribute>,
+        archive_url: Option<Attribute>,
+        archive_date: Option<Attribute>,
+        /// Status of the URL, as an [`Attribute::UrlStatus`], given along with the archive URL
+        url_status: Option<Attribute>,
+        access_date: Option<Attribute>,
+        /// Hash of the fetched page, as an [`Attribute::ContentHash`]
+        content_hash: Option<Attribute>,
     },
     GenericReference {
         title: Option<Attribute>,
         translated_title: Option<Attribute>,
         author: Option<Attribute>,
         date: Option<Attribute>,
+        original_date: Option<Attribute>,
         language: Option<Attribute>,
         site: Option<Attribute>,
+        /// Translation of the site name, as an [`Attribute::TranslatedSite`]
+        translated_site: Option<Attribute>,
+        section: Option<Attribute>,
         url: Option<Attribute>,
         archive_url: Option<Attribute>,
         archive_date: Option<Attribute>,
+        /// Status of the URL, as an [`Attribute::UrlStatus`], given along with the archive URL
+        url_status: Option<Attribute>,
+        access_date: Option<Attribute>,
+        page_count: Option<Attribute>,
+        /// Credit of the main image, as an [`Attribute::ImageCredit`]
+        image_credit: Option<Attribute>,
+        /// Hash of the fetched page, as an [`Attribute::ContentHash`]
+        content_hash: Option<Attribute>,
     }
 }
 impl Reference {
+    /// Returns the kind of the reference.
+    pub fn kind(&self) -> ReferenceKind {
+        match self {
+            Reference::NewsArticle { .. } => ReferenceKind::NewsArticle,
+            Reference::ScholarlyArticle { .. } => ReferenceKind::ScholarlyArticle,
+            Reference::VideoObject { .. } => ReferenceKind::VideoObject,
+            Reference::PodcastEpisode { .. } => ReferenceKind::PodcastEpisode,
+            Reference::GenericReference { .. } => ReferenceKind::GenericReference,
+        }
+    }
+
     fn build_citation<T: CitationBuilder>(&self, builder: T) -> String {
+        let builder = builder.kind(self.kind());
         match self {
-            Reference::NewsArticle { title, translated_title, author, date, language, site, url, archive_url, archive_date, publisher } => {
+            Reference::NewsArticle { title, translated_title, author, date, original_date, language, site, translated_site, section, place, url, archive_url, archive_date, url_status, access_date, publisher, page_count, image_credit: _, content_hash } => {
                 let formatted_string = builder
                     .try_add(title)
                     .try_add(translated_title)
                     .try_add(author)
                     .try_add(date)
+                    .try_add(original_date)
                     .try_add(language)
                     .try_add(site)
+                    .try_add(translated_site)
+                    .try_add(section)
+                    .try_add(place)
                     .try_add(url)
                     .try_add(archive_url)
                     .try_add(archive_date)
+                    .try_add(url_status)
+                    .try_add(access_date)
                     .try_add(publisher)
+                    .try_add(page_count)
+                    .try_add(content_hash)
                     .build();
                 formatted_string
             }
-            Reference::ScholarlyArticle { title, translated_title, author, date, language, url, archive_url, archive_date, publisher, journal } => {
+            Reference::ScholarlyArticle { title, translated_title, author, affiliations, date, original_date, language, url, archive_url, archive_date, url_status, access_date, publisher, journal, volume, issue, pages, status, content_hash } => {
                 let formatted_string = builder
                     .try_add(title)
                     .try_add(translated_title)
                     .try_add(author)
+                    .try_add(affiliations)
                     .try_add(date)
+                    .try_add(original_date)
                     .try_add(language)
                     .try_add(url)
                     .try_add(archive_url)
                     .try_add(archive_date)
+                    .try_add(url_status)
+                    .try_add(access_date)
                     .try_add(journal)
+                    .try_add(volume)
+                    .try_add(issue)
+                    .try_add(pages)
                     .try_add(publisher)
+                    .try_add(status)
+                    .try_add(content_hash)
+                    .build();
+                formatted_string
+            }
+            Reference::VideoObject { title, translated_title, author, date, original_date, language, site, translated_site, url, duration, archive_url, archive_date, url_status, access_date, content_hash } => {
+                let formatted_string = builder
+                    .try_add(title)
+                    .try_add(translated_title)
+                    .try_add(author)
+                    .try_add(date)
+                    .try_add(original_date)
+                    .try_add(language)
+                    .try_add(site)
+                    .try_add(translated_site)
+                    .try_add(url)
+                    .try_add(duration)
+                    .try_add(archive_url)
+                    .try_add(archive_date)
+                    .try_add(url_status)
+                    .try_add(access_date)
+                    .try_add(content_hash)
                     .build();
                 formatted_string
             }
-            Reference::GenericReference { title, translated_title, author, date, language, site, url, archive_url, archive_date } => {
+            Reference::PodcastEpisode { title, translated_title, author, date, original_date, language, site, translated_site, series, episode, url, duration, archive_url, archive_date, url_status, access_date, content_hash } => {
+                builder
+                    .try_add(title)
+                    .try_add(translated_title)
+                    .try_add(author)
+                    .try_add(date)
+                    .try_add(original_date)
+                    .try_add(language)
+                    .try_add(series)
+                    .try_add(episode)
+                    .try_add(site)
+                    .try_add(translated_site)
+                    .try_add(url)
+                    .try_add(duration)
+                    .try_add(archive_url)
+                    .try_add(archive_date)
+                    .try_add(url_status)
+                    .try_add(access_date)
+                    .try_add(content_hash)
+                    .build()
+            }
+            Reference::GenericReference { title, translated_title, author, date, original_date, language, site, translated_site, section, url, archive_url, archive_date, url_status, access_date, page_count, image_credit: _, content_hash } => {
                 let formatted_string = builder
                     .try_add(title)
                     .try_add(translated_title)
                     .try_add(author)
                     .try_add(date)
+                    .try_add(original_date)
                     .try_add(language)
                     .try_add(site)
+                    .try_add(translated_site)
+                    .try_add(section)
                     .try_add(url)
                     .try_add(archive_url)
                     .try_add(archive_date)
+                    .try_add(url_status)
+                    .try_add(access_date)
+                    .try_add(page_count)
+                    .try_add(content_hash)
                     .build();
                 formatted_string
             }
         }
     }
 
-    /// Returns a citation in BibTeX markup
+    /// Returns a citation in the given format, rendered according to the
+    /// options. Relative dates only apply to plain citations, and the
+    /// title is only truncated in human-readable formats.
+    pub fn format_with(&self, format: CitationFormat, options: &FormatOptions) -> String {
+        let truncated;
+        let reference = match options.max_title_chars {
+            Some(max_chars) if format.is_human_readable() => {
+                truncated = self.with_truncated_title(max_chars);
+                &truncated
+            }
+            _ => self,
+        };
+
+        match format {
+            CitationFormat::Plain if options.relative_dates => {
+                reference.build_citation(PlainCitation::new().with_relative_dates(options.now.unwrap_or_else(Utc::now)))
+            }
+            _ => reference.format(format),
+        }
+    }
+
+    /// Returns a copy of the reference whose title is truncated
+    /// to at most `max_chars` characters.
+    fn with_truncated_title(&self, max_chars: usize) -> Reference {
+        let mut reference = self.clone();
+        let (Reference::NewsArticle { title, .. }
+        | Reference::ScholarlyArticle { title, .. }
+        | Reference::VideoObject { title, .. }
+        | Reference::PodcastEpisode { title, .. }
+        | Reference::GenericReference { title, .. }) = &mut reference;
+        if let Some(Attribute::Title(title)) = title {
+            *title = truncate_title(title, max_chars);
+        }
+        reference
+    }
+
+    /// Returns a citation in the given format
+    pub fn format(&self, format: CitationFormat) -> String {
+        match format {
+            CitationFormat::Wiki => self.wiki(),
+            CitationFormat::BibTeX => self.bibtex(),
+            CitationFormat::Plain => self.plain(),
+            CitationFormat::Vancouver => self.vancouver(),
+            CitationFormat::Harvard => self.harvard(),
+            CitationFormat::Markdown => self.markdown(),
+            CitationFormat::Json => self.to_json(),
+            CitationFormat::CslJson => self.csl_json(),
+        }
+    }
+
+    /// Returns the raw attribute values as JSON rather than a formatted
+    /// citation. The kind of reference is given by `type` (e.g.
+    /// `NewsArticle`), followed by a field per attribute, which is `null`
+    /// if missing. Attributes are objects holding their `type` and `value`,
+    /// e.g. `{"type": "title", "value": "..."}`. Dates are given as ISO 8601
+    /// strings along with their precision, which is one of `date_time`,
+    /// `date`, `year_month` and `year`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("References are always serializable")
+    }
+
+    /// Parses a reference from the JSON produced by [`Reference::to_json`].
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Returns a citation in BibTeX markup, keyed by the family name of
+    /// the first author and the year, e.g. `astrup2023`.
     pub fn bibtex(&self) -> String {
         self.build_citation(BibTeXCitation::new())
     }
 
+    /// Returns the key of the BibTeX entry of the reference, see [`bibtex_keys`]
+    /// for keys which are unique within a bibliography.
+    pub fn bibtex_key(&self) -> String {
+        bibtex_key(self.authors(), self.date().map(Date::year), self.site(), self.url())
+    }
+
+    /// Returns a citation in BibTeX markup using the given key, with the
+    /// extra fields (e.g. `("note", "...")`) added to the entry. Generated
+    /// fields of the same name as an extra field are replaced by it.
+    pub fn bibtex_with(&self, key: &str, extra_fields: &[(&str, &str)]) -> String {
+        self.build_citation(BibTeXCitation::new().with_key(key).with_fields(extra_fields))
+    }
+
     /// Returns a citation in Wiki markup
     pub fn wiki(&self) -> String {
         self.build_citation(WikiCitation::new())
     }
-}
\ No newline at end of file
+
+    /// Returns a plain-text citation without markup
+    pub fn plain(&self) -> String {
+        self.build_citation(PlainCitation::new())
+    }
+
+    /// Returns a citation in the Vancouver style
+    pub fn vancouver(&self) -> String {
+        self.build_citation(VancouverCitation::new())
+    }
+
+    /// Returns a citation in the Harvard style
+    pub fn harvard(&self) -> String {
+        self.build_citation(HarvardCitation::new())
+    }
+
+    /// Returns a Markdown link to the work, e.g. for notes and blog posts
+    pub fn markdown(&self) -> String {
+        self.build_citation(MarkdownCitation::new())
+    }
+
+    /// Returns the reference as a CSL-JSON bibliography
+    /// holding a single item, e.g. for use with Pandoc
+    pub fn csl_json(&self) -> String {
+        self.build_citation(CslCitation::new())
+    }
+
+    /// Returns the translated title along with its language,
+    /// if the title was translated.
+    pub fn translated_title(&self) -> Option<&Translation> {
+        let translated_title = match self {
+            Reference::NewsArticle { translated_title, .. }
+            | Reference::ScholarlyArticle { translated_title, .. }
+            | Reference::VideoObject { translated_title, .. }
+            | Reference::PodcastEpisode { translated_title, .. }
+            | Reference::GenericReference { translated_title, .. } => translated_title,
+        };
+
+        match translated_title {
+            Some(Attribute::TranslatedTitle(translation)) => Some(translation),
+            _ => None,
+        }
+    }
+
+    /// Returns the title, if any.
+    pub fn title(&self) -> Option<&str> {
+        let title = match self {
+            Reference::NewsArticle { title, .. }
+            | Reference::ScholarlyArticle { title, .. }
+            | Reference::VideoObject { title, .. }
+            | Reference::PodcastEpisode { title, .. }
+            | Reference::GenericReference { title, .. } => title,
+        };
+
+        match title {
+            Some(Attribute::Title(title)) => Some(title),
+            _ => None,
+        }
+    }
+
+    /// Returns the authors, which are empty if none were found.
+    pub fn authors(&self) -> &[Author] {
+        let author = match self {
+            Reference::NewsArticle { author, .. }
+            | Reference::ScholarlyArticle { author, .. }
+            | Reference::VideoObject { author, .. }
+            | Reference::PodcastEpisode { author, .. }
+            | Reference::GenericReference { author, .. } => author,
+        };
+
+        match author {
+            Some(Attribute::Authors(authors)) => authors,
+            _ => &[],
+        }
+    }
+
+    /// Returns the publication date, if any.
+    pub fn date(&self) -> Option<&Date> {
+        let date = match self {
+            Reference::NewsArticle { date, .. }
+            | Reference::ScholarlyArticle { date, .. }
+            | Reference::VideoObject { date, .. }
+            | Reference::PodcastEpisode { date, .. }
+            | Reference::GenericReference { date, .. } => date,
+        };
+
+        match date {
+            Some(Attribute::Date(date)) => Some(date),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the site, if any. Scholarly articles are
+    /// published in journals rather than on sites.
+    pub fn site(&self) -> Option<&str> {
+        let site = match self {
+            Reference::NewsArticle { site, .. }
+            | Reference::VideoObject { site, .. }
+            | Reference::PodcastEpisode { site, .. }
+            | Reference::GenericReference { site, .. } => site,
+            Reference::ScholarlyArticle { .. } => &None,
+        };
+
+        match site {
+            Some(Attribute::Site(site)) => Some(site),
+            _ => None,
+        }
+    }
+
+    /// Returns the URL, if any.
+    pub fn url(&self) -> Option<&str> {
+        let url = match self {
+            Reference::NewsArticle { url, .. }
+            | Reference::ScholarlyArticle { url, .. }
+            | Reference::VideoObject { url, .. }
+            | Reference::PodcastEpisode { url, .. }
+            | Reference::GenericReference { url, .. } => url,
+        };
+
+        match url {
+            Some(Attribute::Url(url)) => Some(url),
+            _ => None,
+        }
+    }
+
+    /// Returns the caption or credit of the main image, if any. It is not
+    /// part of the citations, but may be noted by specialized ones.
+    pub fn image_credit(&self) -> Option<&str> {
+        let image_credit = match self {
+            Reference::NewsArticle { image_credit, .. }
+            | Reference::GenericReference { image_credit, .. } => image_credit,
+            Reference::ScholarlyArticle { .. } | Reference::VideoObject { .. } | Reference::PodcastEpisode { .. } => &None,
+        };
+
+        match image_credit {
+            Some(Attribute::ImageCredit(image_credit)) => Some(image_credit),
+            _ => None,
+        }
+    }
+
+    /// Returns the attributes of the reference along with the names of
+    /// their fields, which are also their keys in [`Reference::to_json`],
+    /// in the order the fields are declared in. Missing attributes are left out.
+    pub fn fields(&self) -> Vec<(&'static str, &Attribute)> {
+        let fields = match self {
+            Reference::NewsArticle { title, translated_title, author, date, original_date, language, site, translated_site, section, place, url, publisher, archive_url, archive_date, url_status, access_date, page_count, image_credit, content_hash } => vec![
+                ("title", title),
+                ("translated_title", translated_title),
+                ("author", author),
+                ("date", date),
+                ("original_date", original_date),
+                ("language", language),
+                ("site", site),
+                ("translated_site", translated_site),
+                ("section", section),
+                ("place", place),
+                ("url", url),
+                ("publisher", publisher),
+                ("archive_url", archive_url),
+                ("archive_date", archive_date),
+                ("url_status", url_status),
+                ("access_date", access_date),
+                ("page_count", page_count),
+                ("image_credit", image_credit),
+                ("content_hash", content_hash),
+            ],
+            Reference::ScholarlyArticle { title, translated_title, author, affiliations, date, original_date, language, url, journal, volume, issue, pages, publisher, status, archive_url, archive_date, url_status, access_date, content_hash } => vec![
+                ("title", title),
+                ("translated_title", translated_title),
+                ("author", author),
+                ("affiliations", affiliations),
+                ("date", date),
+                ("original_date", original_date),
+                ("language", language),
+                ("url", url),
+                ("journal", journal),
+                ("volume", volume),
+                ("issue", issue),
+                ("pages", pages),
+                ("publisher", publisher),
+                ("status", status),
+                ("archive_url", archive_url),
+                ("archive_date", archive_date),
+                ("url_status", url_status),
+                ("access_date", access_date),
+                ("content_hash", content_hash),
+            ],
+            Reference::VideoObject { title, translated_title, author, date, original_date, language, site, translated_site, url, duration, archive_url, archive_date, url_status, access_date, content_hash } => vec![
+                ("title", title),
+                ("translated_title", translated_title),
+                ("author", author),
+                ("date", date),
+                ("original_date", original_date),
+                ("language", language),
+                ("site", site),
+                ("translated_site", translated_site),
+                ("url", url),
+                ("duration", duration),
+                ("archive_url", archive_url),
+                ("archive_date", archive_date),
+                ("url_status", url_status),
+                ("access_date", access_date),
+                ("content_hash", content_hash),
+            ],
+            Reference::PodcastEpisode { title, translated_title, author, date, original_date, language, site, translated_site, series, episode, url, duration, archive_url, archive_date, url_status, access_date, content_hash } => vec![
+                ("title", title),
+                ("translated_title", translated_title),
+                ("author", author),
+                ("date", date),
+                ("original_date", original_date),
+                ("language", language),
+                ("site", site),
+                ("translated_site", translated_site),
+                ("series", series),
+                ("episode", episode),
+                ("url", url),
+                ("duration", duration),
+                ("archive_url", archive_url),
+                ("archive_date", archive_date),
+                ("url_status", url_status),
+                ("access_date", access_date),
+                ("content_hash", content_hash),
+            ],
+            Reference::GenericReference { title, translated_title, author, date, original_date, language, site, translated_site, section, url, archive_url, archive_date, url_status, access_date, page_count, image_credit, content_hash } => vec![
+                ("title", title),
+                ("translated_title", translated_title),
+                ("author", author),
+                ("date", date),
+                ("original_date", original_date),
+                ("language", language),
+                ("site", site),
+                ("translated_site", translated_site),
+                ("section", section),
+                ("url", url),
+                ("archive_url", archive_url),
+                ("archive_date", archive_date),
+                ("url_status", url_status),
+                ("access_date", access_date),
+                ("page_count", page_count),
+                ("image_credit", image_credit),
+                ("content_hash", content_hash),
+            ],
+        };
+
+        fields
+            .into_iter()
+            .filter_map(|(name, attribute)| Some((name, attribute.as_ref()?)))
+            .collect()
+    }
+
+    /// Returns the attributes of the reference by their type, regardless of
+    /// its kind, e.g. the episode of a podcast as [`AttributeType::Issue`].
+    /// Translations and page counts have no type, see [`Reference::fields`].
+    pub fn attributes(&self) -> HashMap<AttributeType, &Attribute> {
+        self.fields()
+            .into_iter()
+            .filter_map(|(_, attribute)| Some((attribute.attribute_type()?, attribute)))
+            .collect()
+    }
+
+    /// Returns the attribute of the given type, if the reference has one.
+    pub fn get(&self, attribute_type: AttributeType) -> Option<&Attribute> {
+        self.fields()
+            .into_iter()
+            .map(|(_, attribute)| attribute)
+            .find(|attribute| attribute.attribute_type() == Some(attribute_type))
+    }
+
+    /// Returns a citation following a custom template, in which the
+    /// placeholders `{{title}}`, `{{author}}`, `{{date}}`, `{{year}}`,
+    /// `{{site}}` and `{{url}}` are replaced by the attributes of the
+    /// reference, e.g. `{{author}} ({{year}}). {{title}}. {{url}}`.
+    /// Placeholders of missing attributes are left out, while unknown
+    /// placeholders are kept as they are.
+    pub fn format_template(&self, template: &str) -> String {
+        let placeholder = Regex::new(r"\{\{\s*(\w+)\s*\}\}").unwrap();
+
+        placeholder
+            .replace_all(template, |captures: &Captures| {
+                let value = match &captures[1] {
+                    "title" => self.title().map(str::to_string),
+                    "author" => {
+                        let names: Vec<&str> = self.authors().iter().map(author_name).collect();
+                        Some(names.join(", "))
+                    }
+                    "date" => self.date().map(Date::to_iso_date),
+                    "year" => self.date().map(|date| date.year().to_string()),
+                    "site" => self.site().map(str::to_string),
+                    "url" => self.url().map(str::to_string),
+                    _ => return captures[0].to_string(),
+                };
+                value.unwrap_or_default()
+            })
+            .into_owned()
+    }
+}
+
+/// Full name of an author, regardless of the kind of author.
+fn author_name(author: &Author) -> &str {
+    match author {
+        Author::Person(name) | Author::Organization(name) | Author::Generic(name) => name,
+    }
+}
+
+/// Keys by which a bibliography of references can be sorted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SortKey {
+    /// Family name of the first author, or the full name of organizations
+    #[default]
+    Author,
+    /// Publication date, oldest first
+    Date,
+    /// Title, ignoring case
+    Title,
+}
+
+/// Name by which an author is sorted, i.e. the family name of persons.
+fn author_sort_name(author: &Author) -> String {
+    let name = match author {
+        Author::Person(name) => PersonName::parse(name).map_or_else(|| name.clone(), |name| name.family),
+        Author::Organization(name) | Author::Generic(name) => name.clone(),
+    };
+    name.to_lowercase()
+}
+
+/// Orders present values before missing ones, so that
+/// references lacking the key end up last.
+fn cmp_present_first<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Sorts references by the given key, e.g. to produce a bibliography.
+/// References lacking the key are placed last. The sort is stable, so
+/// references with equal keys keep their original order.
+pub fn sort_references(mut references: Vec<Reference>, key: SortKey) -> Vec<Reference> {
+    let title = |reference: &Reference| reference.title().map(str::to_lowercase);
+    match key {
+        SortKey::Author => references.sort_by(|a, b| {
+            let authors = |reference: &Reference| {
+                let names: Vec<String> = reference.authors().iter().map(author_sort_name).collect();
+                (!names.is_empty()).then_some(names)
+            };
+            cmp_present_first(authors(a), authors(b))
+        }),
+        SortKey::Date => references.sort_by(|a, b| cmp_present_first(a.date(), b.date())),
+        SortKey::Title => references.sort_by(|a, b| cmp_present_first(title(a), title(b))),
+    }
+    references
+}
+
+/// Returns the BibTeX keys of the references in their order. Keys shared
+/// by several references are told apart by appending letters in the order
+/// of the references, e.g. `doe2024a` and `doe2024b`.
+pub fn bibtex_keys(references: &[Reference]) -> Vec<String> {
+    let keys: Vec<String> = references.iter().map(Reference::bibtex_key).collect();
+
+    // Letters following `z` are `aa`, `ab` and so on
+    fn suffix(index: usize) -> String {
+        let letter = char::from(b'a' + (index % 26) as u8);
+        match index / 26 {
+            0 => letter.to_string(),
+            rest => format!("{}{letter}", suffix(rest - 1)),
+        }
+    }
+
+    let mut seen: Vec<&String> = Vec::new();
+    keys.iter()
+        .map(|key| {
+            let shared = keys.iter().filter(|other| *other == key).count() > 1;
+            let index = seen.iter().filter(|other| **other == key).count();
+            seen.push(key);
+            match shared {
+                true => format!("{key}{}", suffix(index)),
+                false => key.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the citations of the references in the given format as a
+/// single document: the JSON formats give one array of all references,
+/// while the citations of the other formats are separated by blank lines.
+/// BibTeX entries are keyed as by [`bibtex_keys`], so that keys are unique.
+pub fn format_references(references: &[Reference], format: CitationFormat, options: &FormatOptions) -> String {
+    match format {
+        CitationFormat::Json => {
+            serde_json::to_string_pretty(references).expect("References are always serializable")
+        }
+        CitationFormat::CslJson => {
+            // Items are cited by their `id`, which must be unique within the bibliography.
+            let items: Vec<serde_json::Value> = references
+                .iter()
+                .flat_map(|reference| {
+                    serde_json::from_str::<Vec<serde_json::Value>>(&reference.csl_json())
+                        .expect("CSL-JSON citations are always valid JSON")
+                })
+                .enumerate()
+                .map(|(index, mut item)| {
+                    item["id"] = format!("url2ref-{}", index + 1).into();
+                    item
+                })
+                .collect();
+            serde_json::to_string_pretty(&items).expect("JSON values are always serializable")
+        }
+        CitationFormat::BibTeX => references
+            .iter()
+            .zip(bibtex_keys(references))
+            .map(|(reference, key)| reference.bibtex_with(&key, &[]))
+            .collect::<Vec<String>>()
+            .join("\n\n"),
+        _ => references
+            .iter()
+            .map(|reference| reference.format_with(format, options))
+            .collect::<Vec<String>>()
+            .join("\n\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translated_title_accessor() {
+        let translation = Translation {
+            text: "Climate deal in place in Dubai".to_string(),
+            language: "en".to_string(),
+        };
+        let reference = Reference::GenericReference {
+            title: Some(Attribute::Title("Klimaaftale på plads i Dubai".to_string())),
+            translated_title: Some(Attribute::TranslatedTitle(translation.clone())),
+            author: None,
+            date: None,
+            original_date: None,
+            language: None,
+            site: None,
+            translated_site: None,
+            section: None,
+            url: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            page_count: None,
+            image_credit: None,
+            content_hash: None,
+        };
+
+        assert_eq!(reference.translated_title(), Some(&translation));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        use chrono::{NaiveDate, TimeZone, Utc};
+        use crate::attribute::{Author, Date};
+
+        let reference = Reference::NewsArticle {
+            title: Some(Attribute::Title("Harbour expansion approved".to_string())),
+            translated_title: Some(Attribute::TranslatedTitle(Translation {
+                text: "Hafenerweiterung genehmigt".to_string(),
+                language: "de".to_string(),
+            })),
+            author: Some(Attribute::Authors(vec![
+                Author::Person("Jane Doe".to_string()),
+                Author::Organization("Coastal Times".to_string()),
+            ])),
+            date: Some(Attribute::Date(Date::DateTime(Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap()))),
+            original_date: Some(Attribute::OriginalDate(Date::YearMonth { year: 1998, month: 5 })),
+            language: Some(Attribute::Language("en".to_string())),
+            site: Some(Attribute::Site("Coastal Times".to_string())),
+            translated_site: None,
+            section: None,
+            place: Some(Attribute::Place("Copenhagen".to_string())),
+            url: Some(Attribute::Url("https://example.com/news/harbour".to_string())),
+            publisher: None,
+            archive_url: None,
+            archive_date: Some(Attribute::ArchiveDate(Date::Year(2024))),
+            url_status: None,
+            access_date: Some(Attribute::AccessDate(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()))),
+            page_count: Some(Attribute::PageCount(3)),
+            image_credit: Some(Attribute::ImageCredit("Photo: Jens Hansen/Ritzau Scanpix".to_string())),
+            content_hash: Some(Attribute::ContentHash("8c38a69a7cdc891d37097ed23847e12b736e2b0d7f8a7a8c05e873fe188e98e4".to_string())),
+        };
+
+        let json = reference.to_json();
+        assert!(json.contains(r#""type": "NewsArticle""#));
+        assert!(json.contains(r#""precision": "year_month""#));
+        assert!(json.contains(r#""value": "1998-05""#));
+        assert_eq!(Reference::from_json(&json).unwrap(), reference);
+    }
+
+    fn generic_reference(title: &str, authors: &[&str], date: Option<Date>) -> Reference {
+        Reference::GenericReference {
+            title: Some(Attribute::Title(title.to_string())),
+            translated_title: None,
+            author: (!authors.is_empty()).then(|| {
+                Attribute::Authors(authors.iter().map(|name| Author::Person(name.to_string())).collect())
+            }),
+            date: date.map(Attribute::Date),
+            original_date: None,
+            language: None,
+            site: None,
+            translated_site: None,
+            section: None,
+            url: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            page_count: None,
+            image_credit: None,
+            content_hash: None,
+        }
+    }
+
+    fn bibliography() -> Vec<Reference> {
+        use chrono::{NaiveDate, TimeZone, Utc};
+
+        vec![
+            generic_reference("harbour expansion approved", &["Jane Doe"], Some(Date::Year(2024))),
+            generic_reference("Tides of the North Sea", &[], Some(Date::YearMonth { year: 2021, month: 5 })),
+            generic_reference(
+                "Council votes on bridge",
+                &["Erik Aalto", "Jane Doe"],
+                Some(Date::DateTime(Utc.with_ymd_and_hms(2024, 1, 3, 9, 0, 0).unwrap())),
+            ),
+            generic_reference(
+                "Bridge opens",
+                &["Mary Beck"],
+                Some(Date::YearMonthDay(NaiveDate::from_ymd_opt(2023, 11, 20).unwrap())),
+            ),
+            generic_reference("Ferry timetable", &["Anna Zorn"], None),
+        ]
+    }
+
+    fn titles(references: &[Reference]) -> Vec<&str> {
+        references.iter().filter_map(Reference::title).collect()
+    }
+
+    #[test]
+    fn sort_by_author() {
+        let sorted = sort_references(bibliography(), SortKey::Author);
+        assert_eq!(
+            titles(&sorted),
+            [
+                "Council votes on bridge",
+                "Bridge opens",
+                "harbour expansion approved",
+                "Ferry timetable",
+                "Tides of the North Sea"
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_by_date() {
+        let sorted = sort_references(bibliography(), SortKey::Date);
+        assert_eq!(
+            titles(&sorted),
+            [
+                "Tides of the North Sea",
+                "Bridge opens",
+                "harbour expansion approved",
+                "Council votes on bridge",
+                "Ferry timetable"
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_by_title() {
+        let sorted = sort_references(bibliography(), SortKey::Title);
+        assert_eq!(
+            titles(&sorted),
+            [
+                "Bridge opens",
+                "Council votes on bridge",
+                "Ferry timetable",
+                "harbour expansion approved",
+                "Tides of the North Sea"
+            ]
+        );
+    }
+
+    #[test]
+    fn bibliography_formatted_as_one_document() {
+        let references = &bibliography()[..2];
+        let options = FormatOptions::default();
+
+        let csl: serde_json::Value = serde_json::from_str(&format_references(references, CitationFormat::CslJson, &options)).unwrap();
+        let ids: Vec<&str> = csl.as_array().unwrap().iter().filter_map(|item| item["id"].as_str()).collect();
+        assert_eq!(ids, ["url2ref-1", "url2ref-2"]);
+        let json = format_references(references, CitationFormat::Json, &options);
+        assert_eq!(serde_json::from_str::<Vec<Reference>>(&json).unwrap(), references);
+
+        let plain = format_references(references, CitationFormat::Plain, &options);
+        assert_eq!(plain, format!("{}\n\n{}", references[0].plain(), references[1].plain()));
+
+        // Works by the same author in the same year are told apart.
+        let references = [
+            generic_reference("Havnen udvides", &["Søren Åkjær"], Some(Date::Year(2023))),
+            generic_reference("Færgen sejler igen", &["Søren Åkjær"], Some(Date::Year(2023))),
+        ];
+        let bibtex = format_references(&references, CitationFormat::BibTeX, &options);
+        assert!(bibtex.starts_with("@misc{ aakjaer2023a,\n"));
+        assert!(bibtex.contains("\n\n@misc{ aakjaer2023b,\n"));
+    }
+
+    #[test]
+    fn bibtex_with_key_and_fields() {
+        let reference = generic_reference("Bridge opens", &["Mary Beck"], Some(Date::Year(2023)));
+
+        let bibtex = reference.bibtex_with("beck2023bridge", &[("note", "Accessed via {mirror}"), ("title", "Bridge Opens")]);
+        assert_eq!(
+            bibtex,
+            "@misc{ beck2023bridge,\nauthor = \"Beck, Mary\",\nyear = \"2023\",\nnote = \"Accessed via \\{mirror\\}\",\ntitle = \"Bridge Opens\",\n}"
+        );
+    }
+
+    #[test]
+    fn bibtex_keys_from_danish_names() {
+        let mut bibliography = vec![
+            generic_reference("Havnen udvides", &["Søren Åkjær"], Some(Date::Year(2023))),
+            generic_reference("Broen åbner", &["Mette Ærø-Astrup"], Some(Date::Year(2023))),
+            generic_reference("Færgen sejler igen", &["Søren Åkjær"], Some(Date::Year(2023))),
+            generic_reference("Vejret", &[], None),
+        ];
+        if let Reference::GenericReference { url, .. } = &mut bibliography[3] {
+            *url = Some(Attribute::Url("https://www.dmi.dk/vejret".to_string()));
+        }
+
+        assert_eq!(bibliography[0].bibtex_key(), "aakjaer2023");
+        assert!(bibliography[1].bibtex().starts_with("@misc{ aeroeastrup2023,\n"));
+        assert_eq!(bibtex_keys(&bibliography), ["aakjaer2023a", "aeroeastrup2023", "aakjaer2023b", "dmidk"]);
+    }
+
+    #[test]
+    fn complex_bibtex_parses_back() {
+        use chrono::NaiveDate;
+
+        use crate::attribute::Affiliation;
+
+        let reference = Reference::ScholarlyArticle {
+            title: Some(Attribute::Title("Tides of {Change}: \"Storm surges\" & sea-level rise in 50% of ports".to_string())),
+            translated_title: None,
+            author: Some(Attribute::Authors(vec![
+                Author::Person("Søren Åkjær".to_string()),
+                Author::Person("King, Jr., Martin Luther".to_string()),
+                Author::Organization("Danish Meteorological Institute {DMI}".to_string()),
+            ])),
+            affiliations: Some(Attribute::Affiliations(vec![Affiliation {
+                author: "Søren Åkjær".to_string(),
+                institutions: vec!["Aarhus University".to_string(), "DTU \"Aqua\"".to_string()],
+            }])),
+            date: Some(Attribute::Date(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 14).unwrap()))),
+            original_date: Some(Attribute::OriginalDate(Date::Year(2019))),
+            language: Some(Attribute::Language("da".to_string())),
+            url: Some(Attribute::Url("https://example.org/articles/tides?id=17&lang=da#results".to_string())),
+            journal: Some(Attribute::Journal("Journal of Coastal Research".to_string())),
+            volume: Some(Attribute::Volume("40".to_string())),
+            issue: Some(Attribute::Issue("2".to_string())),
+            pages: Some(Attribute::Pages("101-118".to_string())),
+            publisher: Some(Attribute::Publisher("Coastal Education & Research Foundation".to_string())),
+            status: None,
+            archive_url: Some(Attribute::ArchiveUrl("https://web.archive.org/web/20240315000000/https://example.org/articles/tides".to_string())),
+            archive_date: Some(Attribute::ArchiveDate(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()))),
+            url_status: None,
+            access_date: Some(Attribute::AccessDate(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()))),
+            content_hash: None,
+        };
+
+        let bibtex = reference.bibtex();
+        assert!(bibtex.starts_with("@article{ aakjaer2024,\n"), "{bibtex}");
+        validate_bibtex(&bibtex).unwrap();
+    }
+
+    #[test]
+    fn titles_truncated_in_human_readable_formats() {
+        let title = "Harbour expansion approved after years of debate in the council";
+        let reference = generic_reference(title, &["Jane Doe"], Some(Date::Year(2024)));
+        let options = FormatOptions { max_title_chars: Some(30), ..Default::default() };
+
+        assert_eq!(
+            reference.format_with(CitationFormat::Plain, &options),
+            "Jane Doe, \"Harbour expansion approved…,\" 2024."
+        );
+        assert_eq!(reference.format_with(CitationFormat::Harvard, &options), "Doe, J. (2024) Harbour expansion approved….");
+        assert!(reference.format_with(CitationFormat::BibTeX, &options).contains(title));
+        assert!(reference.format_with(CitationFormat::Wiki, &options).contains(title));
+    }
+
+    #[test]
+    fn format_template() {
+        let template = "{{author}} ({{year}}). {{title}}. {{site}} {{url}}";
+
+        let mut reference = generic_reference("Bridge opens", &["Mary Beck", "Tom Hale"], Some(Date::YearMonth { year: 2023, month: 6 }));
+        if let Reference::GenericReference { site, url, .. } = &mut reference {
+            *site = Some(Attribute::Site("Fjord Post".to_string()));
+            *url = Some(Attribute::Url("https://fjordpost.example.org/bridge".to_string()));
+        }
+        assert_eq!(
+            reference.format_template(template),
+            "Mary Beck, Tom Hale (2023). Bridge opens. Fjord Post https://fjordpost.example.org/bridge"
+        );
+        assert_eq!(reference.format_template("{{ date }}"), "2023-06");
+
+        let reference = generic_reference("Bridge opens", &[], None);
+        assert_eq!(reference.format_template(template), " (). Bridge opens.  ");
+        assert_eq!(reference.format_template("{{title}} {{publisher}}"), "Bridge opens {{publisher}}");
+    }
+
+    #[test]
+    fn attributes_by_type() {
+        let reference = Reference::PodcastEpisode {
+            title: Some(Attribute::Title("The harbour at dawn".to_string())),
+            translated_title: None,
+            author: None,
+            date: None,
+            original_date: None,
+            language: None,
+            site: Some(Attribute::Site("Fjord Radio".to_string())),
+            translated_site: Some(Attribute::TranslatedSite(Translation {
+                text: "Fjord Radio".to_string(),
+                language: "en".to_string(),
+            })),
+            series: None,
+            episode: Some(Attribute::Issue("12".to_string())),
+            url: None,
+            duration: None,
+            archive_url: None,
+            archive_date: None,
+            url_status: None,
+            access_date: None,
+            content_hash: None,
+        };
+
+        let names: Vec<&str> = reference.fields().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["title", "site", "translated_site", "episode"]);
+
+        let attributes = reference.attributes();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(attributes[&AttributeType::Issue], &Attribute::Issue("12".to_string()));
+        assert_eq!(reference.get(AttributeType::Site), Some(&Attribute::Site("Fjord Radio".to_string())));
+        assert_eq!(reference.get(AttributeType::Author), None);
+    }
+
+    #[test]
+    fn supported_attributes() {
+        let scholarly = ReferenceKind::ScholarlyArticle.supported_attributes();
+        assert!(scholarly.contains(&AttributeType::Journal));
+        assert!(scholarly.contains(&AttributeType::Volume));
+        assert!(!scholarly.contains(&AttributeType::Site));
+        assert!(!scholarly.contains(&AttributeType::Section));
+
+        let news = ReferenceKind::NewsArticle.supported_attributes();
+        assert!(news.contains(&AttributeType::Site));
+        assert!(!news.contains(&AttributeType::Journal));
+
+        // The attributes of a reference are among those supported by its kind.
+        let reference = generic_reference("Bridge opens", &["Mary Beck"], Some(Date::Year(2023)));
+        let supported = reference.kind().supported_attributes();
+        assert!(reference.attributes().keys().all(|attribute_type| supported.contains(attribute_type)));
+    }
+
+    #[test]
+    fn citation_format_from_str() {
+        assert_eq!("wiki".parse(), Ok(CitationFormat::Wiki));
+        assert_eq!("BibTeX".parse(), Ok(CitationFormat::BibTeX));
+        assert_eq!("plain".parse(), Ok(CitationFormat::Plain));
+        assert_eq!("mla".parse::<CitationFormat>(), Err(UnknownCitationFormat("mla".to_string())));
+
+        for format in CitationFormat::ALL {
+            assert_eq!(format.to_string().parse(), Ok(*format));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! On-disk cache of responses to network lookups, persisted between runs.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::generator::ResponseCache;
+
+/// Contents of a single cache file.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Key of the response, as the file is named by its hash.
+    key: String,
+    /// Seconds since the Unix epoch at which the response was stored.
+    stored_at: u64,
+    response: String,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+impl ResponseCache {
+    pub fn new(directory: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self { directory: directory.into(), ttl }
+    }
+
+    /// Path of the file holding the response for the key. The file is
+    /// named by the hexadecimal SHA-256 hash of the key (e.g. a URL), so
+    /// that file names are valid and short, however long the URL.
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let file_name: String = Sha256::digest(key.as_bytes()).iter().map(|byte| format!("{byte:02x}")).collect();
+        self.directory.join(format!("{file_name}.json"))
+    }
+
+    /// Returns the cached response for the key, unless
+    /// it is missing, unreadable or has expired.
+    pub fn load(&self, key: &str) -> Option<String> {
+        let contents = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+        if entry.key != key {
+            return None;
+        }
+
+        let age = Duration::from_secs(now().saturating_sub(entry.stored_at));
+        (age <= self.ttl).then_some(entry.response)
+    }
+
+    /// Stores the response for the key. Failing to write to the cache
+    /// is not an error, as the response can always be retrieved again.
+    pub fn store(&self, key: &str, response: &str) {
+        let entry = CacheEntry { key: key.to_string(), stored_at: now(), response: response.to_string() };
+        let Ok(contents) = serde_json::to_string(&entry) else {
+            return;
+        };
+
+        if fs::create_dir_all(&self.directory).is_ok() {
+            let _ = fs::write(self.entry_path(key), contents);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::time::Duration;
+
+    use crate::generator::ResponseCache;
+
+    fn test_cache(name: &str, ttl: Duration) -> ResponseCache {
+        let directory = env::temp_dir().join(format!("url2ref-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        ResponseCache::new(directory, ttl)
+    }
+
+    #[test]
+    fn store_and_load() {
+        let cache = test_cache("store-and-load", Duration::from_secs(3600));
+
+        assert_eq!(cache.load("doi:10.1000/182"), None);
+        cache.store("doi:10.1000/182", "@misc{handbook}");
+        assert_eq!(cache.load("doi:10.1000/182"), Some("@misc{handbook}".to_string()));
+    }
+
+    #[test]
+    fn long_keys_stored() {
+        let cache = test_cache("long-keys", Duration::from_secs(3600));
+        let key = format!("zotero:https://news.example.com/2024/10/01/harbour?{}", "utm_source=newsletter&".repeat(20));
+
+        cache.store(&key, "[]");
+        assert_eq!(cache.load(&key), Some("[]".to_string()));
+        // The file is named by the 64 hexadecimal digits of the hash.
+        assert_eq!(cache.entry_path(&key).file_name().unwrap().len(), 64 + ".json".len());
+    }
+
+    #[test]
+    fn expired_entries_ignored() {
+        let cache = test_cache("expired", Duration::from_secs(3600));
+        let entry = r#"{"key":"doi:10.1000/182","stored_at":0,"response":"@misc{handbook}"}"#;
+        std::fs::create_dir_all(&cache.directory).unwrap();
+        std::fs::write(cache.entry_path("doi:10.1000/182"), entry).unwrap();
+
+        assert_eq!(cache.load("doi:10.1000/182"), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Normalization of the text of parsed attributes, which metadata gives
+//! with e.g. stray whitespace, line breaks or HTML entities left encoded
+//! (`Q&amp;A`, `Don&#8217;t`), so that these do not end up in citations.
+
+use std::borrow::Cow;
+
+use crate::attribute::{Affiliation, Attribute, Author};
+
+/// Named HTML entities decoded, being those which metadata commonly
+/// leaves encoded. Others are kept as they are.
+#[rustfmt::skip]
+const NAMED_ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'), ("lt", '<'), ("gt", '>'), ("quot", '"'), ("apos", '\''), ("nbsp", '\u{a0}'),
+    ("ndash", '–'), ("mdash", '—'), ("hellip", '…'), ("laquo", '«'), ("raquo", '»'),
+    ("lsquo", '‘'), ("rsquo", '’'), ("sbquo", '‚'), ("ldquo", '“'), ("rdquo", '”'), ("bdquo", '„'),
+    ("copy", '©'), ("reg", '®'), ("trade", '™'), ("euro", '€'), ("pound", '£'),
+    ("aelig", 'æ'), ("AElig", 'Æ'), ("oslash", 'ø'), ("Oslash", 'Ø'), ("aring", 'å'), ("Aring", 'Å'),
+    ("auml", 'ä'), ("Auml", 'Ä'), ("ouml", 'ö'), ("Ouml", 'Ö'), ("uuml", 'ü'), ("Uuml", 'Ü'), ("szlig", 'ß'),
+    ("aacute", 'á'), ("eacute", 'é'), ("Eacute", 'É'), ("iacute", 'í'), ("oacute", 'ó'), ("uacute", 'ú'),
+    ("agrave", 'à'), ("egrave", 'è'), ("ccedil", 'ç'), ("ntilde", 'ñ'),
+];
+
+/// Zero-width characters, with which some sites join or break up names.
+const ZERO_WIDTH_CHARACTERS: &[char] = &['\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}'];
+
+/// Prefixes of bylines given as the name of the author, e.g. `By Jane Doe`.
+const BYLINE_PREFIXES: &[&str] = &["by:", "by "];
+
+/// Decodes the character reference, i.e. the text between `&` and `;`.
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity.strip_prefix('#') {
+        Some(number) => {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+        None => NAMED_ENTITIES.iter().find(|(name, _)| *name == entity).map(|(_, c)| *c),
+    }
+}
+
+/// Decodes numeric and common named HTML entities. Entities are only
+/// decoded once, so that e.g. `&amp;amp;` becomes `&amp;`.
+pub fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].split_once(';').map(|(entity, _)| entity).filter(|entity| entity.len() <= 10);
+        match entity.and_then(|entity| Some((entity, decode_entity(entity)?))) {
+            Some((entity, c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+/// Decodes HTML entities, strips control characters and collapses any
+/// whitespace, including line breaks, into single spaces.
+pub fn sanitize_text(text: &str) -> String {
+    let decoded = decode_entities(text);
+    let cleaned: String = decoded.chars().filter(|c| !c.is_control() || c.is_whitespace()).collect();
+    cleaned.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Like [`sanitize_text`], but also removes zero-width characters and
+/// byline prefixes, e.g. `By Jane Doe`.
+pub fn sanitize_name(name: &str) -> String {
+    let name = sanitize_text(&name.replace(ZERO_WIDTH_CHARACTERS, ""));
+    let prefix = BYLINE_PREFIXES
+        .iter()
+        .find(|prefix| name.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)));
+    match prefix {
+        Some(prefix) => name[prefix.len()..].trim().to_string(),
+        None => name,
+    }
+}
+
+fn sanitize_author(author: Author) -> Option<Author> {
+    let author = match author {
+        Author::Person(name) => Author::Person(sanitize_name(&name)),
+        Author::Organization(name) => Author::Organization(sanitize_name(&name)),
+        Author::Generic(name) => Author::Generic(sanitize_name(&name)),
+    };
+    let (Author::Person(name) | Author::Organization(name) | Author::Generic(name)) = &author;
+    (!name.is_empty()).then_some(author)
+}
+
+fn sanitize_affiliation(affiliation: Affiliation) -> Affiliation {
+    Affiliation {
+        author: sanitize_name(&affiliation.author),
+        institutions: affiliation.institutions.iter().map(|institution| sanitize_text(institution)).collect(),
+    }
+}
+
+/// Sanitizes the text of the attribute, or of each author. URLs are only
+/// trimmed, as decoding them would change the page they lead to.
+/// Attributes left empty are dropped.
+pub fn sanitize(attribute: Attribute) -> Option<Attribute> {
+    let text = |text: String, attribute: fn(String) -> Attribute| {
+        let text = sanitize_text(&text);
+        (!text.is_empty()).then(|| attribute(text))
+    };
+    match attribute {
+        Attribute::Title(val)       => text(val, Attribute::Title),
+        Attribute::Language(val)    => text(val, Attribute::Language),
+        Attribute::Locale(val)      => text(val, Attribute::Locale),
+        Attribute::Site(val)        => text(val, Attribute::Site),
+        Attribute::Section(val)     => text(val, Attribute::Section),
+        Attribute::Type(val)        => text(val, Attribute::Type),
+        Attribute::Journal(val)     => text(val, Attribute::Journal),
+        Attribute::Publisher(val)   => text(val, Attribute::Publisher),
+        Attribute::Institution(val) => text(val, Attribute::Institution),
+        Attribute::Volume(val)      => text(val, Attribute::Volume),
+        Attribute::Issue(val)       => text(val, Attribute::Issue),
+        Attribute::Pages(val)       => text(val, Attribute::Pages),
+        Attribute::Duration(val)    => text(val, Attribute::Duration),
+        Attribute::Series(val)      => text(val, Attribute::Series),
+        Attribute::Place(val)       => text(val, Attribute::Place),
+        Attribute::ImageCredit(val) => text(val, Attribute::ImageCredit),
+        Attribute::Url(val)         => Some(Attribute::Url(val.trim().to_string())),
+        Attribute::ArchiveUrl(val)  => Some(Attribute::ArchiveUrl(val.trim().to_string())),
+        Attribute::Authors(authors) => {
+            let authors: Vec<Author> = authors.into_iter().filter_map(sanitize_author).collect();
+            (!authors.is_empty()).then_some(Attribute::Authors(authors))
+        }
+        Attribute::Affiliations(affiliations) => {
+            Some(Attribute::Affiliations(affiliations.into_iter().map(sanitize_affiliation).collect()))
+        }
+        attribute => Some(attribute),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_entities, sanitize, sanitize_name, sanitize_text};
+    use crate::attribute::{Attribute, Author};
+
+    #[test]
+    fn entities_decoded() {
+        assert_eq!(decode_entities("Q&amp;A: Don&#8217;t panic &#x2014; yet"), "Q&A: Don’t panic — yet");
+        assert_eq!(decode_entities("K&oslash;benhavn &lt;3"), "København <3");
+        // Unknown entities, bare ampersands and double encoding are left alone.
+        assert_eq!(decode_entities("&foo; AT&T &amp;amp; &#xZZ;"), "&foo; AT&T &amp; &#xZZ;");
+        assert_eq!(decode_entities("Fish & chips;"), "Fish & chips;");
+        assert_eq!(decode_entities("&#1114112; &"), "&#1114112; &");
+    }
+
+    #[test]
+    fn text_sanitized() {
+        assert_eq!(sanitize_text("  Council votes\n\ton the\u{a0}bridge  "), "Council votes on the bridge");
+        assert_eq!(sanitize_text("Bell\u{7}s &amp; whistles\r\n"), "Bells & whistles");
+        assert_eq!(sanitize_text("&nbsp;"), "");
+        assert_eq!(sanitize_text("Smörgåsbord"), "Smörgåsbord");
+    }
+
+    #[test]
+    fn names_sanitized() {
+        assert_eq!(sanitize_name("By Jane Doe"), "Jane Doe");
+        assert_eq!(sanitize_name("BY: Jane\u{200b} Doe"), "Jane Doe");
+        assert_eq!(sanitize_name("  by  Jane\nDoe "), "Jane Doe");
+        assert_eq!(sanitize_name("Byron Katie"), "Byron Katie");
+        assert_eq!(sanitize_name("Ma\u{200d}rie D&#39;Arcy"), "Marie D'Arcy");
+    }
+
+    #[test]
+    fn attributes_sanitized() {
+        assert_eq!(
+            sanitize(Attribute::Title(" Rock &amp; roll\n hall of fame ".to_string())),
+            Some(Attribute::Title("Rock & roll hall of fame".to_string()))
+        );
+        assert_eq!(sanitize(Attribute::Site("\n \t".to_string())), None);
+        assert_eq!(
+            sanitize(Attribute::Url(" https://example.com/?a=1&amp;b=2 ".to_string())),
+            Some(Attribute::Url("https://example.com/?a=1&amp;b=2".to_string()))
+        );
+        assert_eq!(
+            sanitize(Attribute::Authors(vec![
+                Author::Person("By Jane Doe".to_string()),
+                Author::Generic("\u{200b}".to_string()),
+                Author::Organization("Reuters ".to_string()),
+            ])),
+            Some(Attribute::Authors(vec![Author::Person("Jane Doe".to_string()), Author::Organization("Reuters".to_string())]))
+        );
+        assert_eq!(sanitize(Attribute::Authors(vec![Author::Generic(" \n ".to_string())])), None);
+    }
+}
@@ -1,11 +1,22 @@
 pub mod generic;
 pub mod author;
 pub mod site;
+pub mod original_date;
+pub mod breadcrumb;
+pub mod podcast;
+pub mod language;
+pub mod image;
 
 use generic::create_generic_attribute;
-use author::create_author_attribute;
+use author::{create_affiliations_attribute, create_author_attribute};
 use site::create_site_attribute;
+use original_date::create_original_date_attribute;
+use breadcrumb::{breadcrumb_section, breadcrumb_site};
+use podcast::{create_episode_attribute, create_series_attribute};
+use language::create_language_attribute;
+use image::create_image_credit_attribute;
 
+use scraper::{Html, Selector};
 use serde_json::Value;
 
 use crate::parser::{AttributeParser, MetadataKey, ParseInfo};
@@ -18,36 +29,205 @@ use crate::attribute::{Attribute, AttributeType};
 pub const fn keys(key: AttributeType) -> &'static [MetadataKey] {
     match key {
         AttributeType::Title    => &[MetadataKey{key: "headline"},
-                                     MetadataKey{key: "alternativeHeadline"}],
+                                     MetadataKey{key: "alternativeHeadline"},
+                                     MetadataKey{key: "name"}],
         AttributeType::Author   => &[MetadataKey{key: "author"}],
+        AttributeType::Affiliations => &[MetadataKey{key: "author"}],
         AttributeType::Language => &[MetadataKey{key: "inLanguage"}],
         AttributeType::Site     => &[MetadataKey{key: "publisher"},
                                      MetadataKey{key: "sourceOrganization"}],
+        AttributeType::Section  => &[MetadataKey{key: "articleSection"}],
         AttributeType::Url      => &[MetadataKey{key: "mainEntityOfPage"},
                                      MetadataKey{key: "url"}],
         AttributeType::Date     => &[MetadataKey{key: "datePublished"},
+                                     MetadataKey{key: "uploadDate"},
                                      MetadataKey{key: "dateModified"}],
+        AttributeType::OriginalDate => &[MetadataKey{key: "translationOfWork"},
+                                         MetadataKey{key: "isBasedOn"},
+                                         MetadataKey{key: "exampleOfWork"}],
         AttributeType::Type     => &[MetadataKey{key: "@type"}],
+        AttributeType::Duration => &[MetadataKey{key: "duration"}],
+        AttributeType::Series   => &[MetadataKey{key: "partOfSeries"}],
+        AttributeType::Issue    => &[MetadataKey{key: "episodeNumber"},
+                                     MetadataKey{key: "issueNumber"}],
+        AttributeType::ImageCredit => &[MetadataKey{key: "image"},
+                                        MetadataKey{key: "primaryImageOfPage"}],
         _                       => &[],
     }
 }
 
+/// Types of the entities describing the cited work itself,
+/// as opposed to e.g. the `WebSite` or a `BreadcrumbList`.
+const MAIN_ENTITY_TYPES: &[&str] = &[
+    "NewsArticle",
+    "Article",
+    "ScholarlyArticle",
+    "BlogPosting",
+    "Report",
+    "VideoObject",
+    "PodcastEpisode",
+];
+
+/// Collects the entities of all JSON-LD blocks of the document, in document
+/// order. Lists and `@graph` arrays are flattened. Unlike [`webpage`], which
+/// drops e.g. entities of several types, every entity is kept, and blocks
+/// which are not valid JSON are skipped rather than failing the others.
+pub fn collect_json_ld(document: &Html) -> Vec<Value> {
+    let selector = Selector::parse("script[type=\"application/ld+json\"]").unwrap();
+    let mut entities = Vec::new();
+    for script in document.select(&selector) {
+        let json: String = script.text().collect();
+        if let Ok(value) = serde_json::from_str(strip_wrappers(&json)) {
+            flatten_json_ld(value, &mut entities);
+        }
+    }
+    entities
+}
+
+/// Strips the CDATA sections and HTML comments which some sites wrap JSON-LD
+/// in for the sake of old browsers, e.g. `//<![CDATA[ {...} //]]>`, along
+/// with comments preceding the JSON, e.g. `<!-- Yoast SEO -->`.
+fn strip_wrappers(json: &str) -> &str {
+    let mut json = json.trim();
+    loop {
+        let length = json.len();
+        if json.starts_with("//") {
+            // Comments hiding the CDATA markers from scripts end with the line.
+            json = json.split_once('\n').map_or("", |(_, rest)| rest);
+        }
+        for prefix in ["<![CDATA[", "/*", "*/"] {
+            json = json.strip_prefix(prefix).unwrap_or(json).trim_start();
+        }
+        if let Some(rest) = json.strip_prefix("<!--") {
+            json = match rest.split_once("-->") {
+                Some((comment, rest)) if !comment.trim_start().starts_with(['{', '[']) => rest,
+                _ => rest,
+            };
+        }
+        for suffix in ["]]>", "-->", "//", "/*", "*/"] {
+            json = json.strip_suffix(suffix).unwrap_or(json).trim_end();
+        }
+
+        json = json.trim();
+        if json.len() == length {
+            return json;
+        }
+    }
+}
+
+fn flatten_json_ld(value: Value, entities: &mut Vec<Value>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                flatten_json_ld(value, entities);
+            }
+        }
+        Value::Object(mut object) => {
+            // The object holding the graph is usually nothing but its `@context`.
+            if let Some(graph) = object.remove("@graph") {
+                flatten_json_ld(graph, entities);
+                if !object.contains_key("@type") {
+                    return;
+                }
+            }
+            entities.push(Value::Object(object));
+        }
+        _ => (),
+    }
+}
+
+/// Returns all entities of the JSON-LD blocks, descending into
+/// any `@graph` arrays not already flattened by [`collect_json_ld`].
+pub fn entities<'a>(schema_values: &[&'a Value]) -> Vec<&'a Value> {
+    schema_values
+        .iter()
+        .flat_map(|value| match (value, &value["@graph"]) {
+            (Value::Array(list), _) | (_, Value::Array(list)) => list.iter().collect(),
+            _ => vec![*value],
+        })
+        .collect()
+}
+
+/// Whether the `@type` of the entity, which may be a list of types, is one of the given types.
+pub fn has_type(entity: &Value, types: &[&str]) -> bool {
+    match &entity["@type"] {
+        Value::String(entity_type) => types.contains(&entity_type.as_str()),
+        Value::Array(entity_types) => entity_types
+            .iter()
+            .any(|entity_type| entity_type.as_str().is_some_and(|t| types.contains(&t))),
+        _ => false,
+    }
+}
+
+/// Finds the entity describing the cited work. Falls back to
+/// the first JSON-LD block if there is no such entity.
+fn main_entity<'a>(schema_values: &[&'a Value]) -> Option<&'a Value> {
+    entities(schema_values)
+        .into_iter()
+        .find(|entity| has_type(entity, MAIN_ENTITY_TYPES))
+        .or_else(|| schema_values.first().copied())
+}
+
+/// Whether the cited work is marked as not accessible for free, in which
+/// case the page is likely a teaser with incomplete metadata.
+pub fn is_paywalled(parse_info: &ParseInfo) -> bool {
+    let schema_values: Vec<&Value> = parse_info.json_ld.iter().collect();
+
+    match main_entity(&schema_values).map(|entity| &entity["isAccessibleForFree"]) {
+        Some(Value::Bool(accessible)) => !accessible,
+        Some(Value::String(accessible)) => accessible.eq_ignore_ascii_case("false"),
+        _ => false,
+    }
+}
+
 pub struct SchemaOrg;
 
 impl AttributeParser for SchemaOrg {
 
     fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
-        let html = parse_info.html.as_ref()?;
-        let schema = html.schema_org.get(0)?;
-        let schema_json: &Value = &schema.value;
+        let schema_values: Vec<&Value> = parse_info.json_ld.iter().collect();
+        let schema_json = main_entity(&schema_values)?;
 
         let external_keys = keys(attribute_type);
 
         // Some fields require explicit handling because of nested structures.
+        // The site and section fall back to the breadcrumb trail, if present.
         match attribute_type {
             AttributeType::Author => create_author_attribute(&schema_json, external_keys),
-            AttributeType::Site => create_site_attribute(&schema_json, external_keys),
+            AttributeType::Affiliations => create_affiliations_attribute(schema_json, external_keys),
+            AttributeType::Site => create_site_attribute(schema_json, external_keys)
+                .or_else(|| breadcrumb_site(&schema_values).map(Attribute::Site)),
+            AttributeType::Section => create_generic_attribute(schema_json, external_keys, attribute_type)
+                .or_else(|| breadcrumb_section(&schema_values).map(Attribute::Section)),
+            AttributeType::OriginalDate => create_original_date_attribute(schema_json, external_keys),
+            AttributeType::Series => create_series_attribute(schema_json, external_keys),
+            AttributeType::Issue => create_episode_attribute(schema_json, external_keys),
+            AttributeType::Language => create_language_attribute(schema_json, external_keys),
+            AttributeType::ImageCredit => create_image_credit_attribute(schema_json, external_keys),
             _ => create_generic_attribute(&schema_json, external_keys, attribute_type),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::strip_wrappers;
+
+    #[test]
+    fn wrappers_stripped() {
+        let json = r#"{"@type": "NewsArticle"}"#;
+        #[rustfmt::skip]
+        let cases = [
+            format!("<![CDATA[{json}]]>"),
+            format!("\n//<![CDATA[\n{json}\n//]]>\n"),
+            format!("/*<![CDATA[*/ {json} /*]]>*/"),
+            format!("<!--\n{json}\n-->"),
+            format!("<!-- Yoast SEO plugin -->\n{json}"),
+            json.to_string(),
+        ];
+
+        for case in cases {
+            assert_eq!(strip_wrappers(&case), json, "{case}");
+        }
+    }
+}
@@ -1,16 +1,16 @@
 //! Strategies for parsing [`Attribute::Author`].
 
 
-use crate::attribute::{Attribute, Author};
+use crate::attribute::{Affiliation, Attribute, Author};
 use crate::schema_org::MetadataKey;
 
 use serde_json::Value;
 
 
-fn match_author_type(author_type: &String, name: &String) -> Option<Author> {
-    match author_type.as_str() {
-        "Person" => Some(Author::Person(name.clone())),
-        "Organization" => Some(Author::Organization(name.clone())),
+fn match_author_type(author_type: &str, name: &str) -> Option<Author> {
+    match author_type {
+        "Person" => Some(Author::Person(name.to_string())),
+        "Organization" => Some(Author::Organization(name.to_string())),
         _ => None
     }
 }
@@ -19,29 +19,40 @@ fn match_author_type(author_type: &String, name: &String) -> Option<Author> {
 fn match_tuple(object_type: &Value, name_value: &Value) -> Option<Author> {
     match (object_type, name_value) {
         (Value::String(author_type), Value::String(name)) => match_author_type(author_type, name),
+        // Types may be given as a list, e.g. `["Person", "Author"]`
+        (Value::Array(author_types), Value::String(name)) => author_types
+            .iter()
+            .find_map(|author_type| match_author_type(author_type.as_str()?, name)),
         (_, _) => None
     }
 }
 
 
-fn try_find_author_array_of_persons_stategy(value_list: &Vec<Value>) -> Option<Vec<Author>> {
-    let mut ret = Vec::new();
-    for value in value_list {
-        match value {
-            Value::Object(map) => {
-                let object_type = &map["@type"];
-                let name_value = &map["name"];
+/// Author given as a bare name, or as a Person or Organization object.
+/// Objects of other types are taken as generic authors if they are named.
+fn try_find_single_author_strategy(value: &Value) -> Option<Author> {
+    match value {
+        Value::String(name) => Some(Author::Generic(name.clone())),
+        Value::Object(map) => {
+            let name_value = map.get("name")?;
+            let object_type = map.get("@type").unwrap_or(&Value::Null);
 
-                let author_option = match_tuple(object_type, name_value);
+            match_tuple(object_type, name_value)
+                .or_else(|| name_value.as_str().map(|name| Author::Generic(name.to_string())))
+        },
+        _ => None
+    }
+    .filter(|author| match author {
+        Author::Person(name) | Author::Organization(name) | Author::Generic(name) => !name.trim().is_empty(),
+    })
+}
 
-                if let Some(author) = author_option {
-                    ret.push(author);
-                }
 
-            },
-            _ => todo!()
-        }
-    }
+fn try_find_author_array_strategy(value_list: &[Value]) -> Option<Vec<Author>> {
+    let ret: Vec<Author> = value_list
+        .iter()
+        .filter_map(try_find_single_author_strategy)
+        .collect();
 
     if ret.is_empty() {
         return None
@@ -58,9 +69,8 @@ fn try_find_author_attribute(
     for external_key in external_keys.iter() {
         let value = &schema_value[external_key.key];
         let found_option = match value {
-            Value::Array(value_list) => try_find_author_array_of_persons_stategy(&value_list),
-            Value::Object(_) => None, // -> Person, Organization
-            _ => None,
+            Value::Array(value_list) => try_find_author_array_strategy(value_list),
+            value => try_find_single_author_strategy(value).map(|author| vec![author]),
         };
 
         if found_option.is_some() {
@@ -74,4 +84,42 @@ fn try_find_author_attribute(
 pub fn create_author_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
     let attribute_option = try_find_author_attribute(&schema_value, external_keys)?;
     Some(Attribute::Authors(attribute_option))
-}
\ No newline at end of file
+}
+
+
+/// Names of the institutions of an author object, given by its `affiliation`
+/// as a name, an Organization object or a list of either.
+fn find_institutions(author: &Value) -> Vec<String> {
+    fn institution_name(value: &Value) -> Option<String> {
+        let name = match value {
+            Value::String(name) => name,
+            Value::Object(map) => map.get("name")?.as_str()?,
+            _ => return None,
+        };
+        Some(name.trim().to_string()).filter(|name| !name.is_empty())
+    }
+
+    match &author["affiliation"] {
+        Value::Array(values) => values.iter().filter_map(institution_name).collect(),
+        value => institution_name(value).into_iter().collect(),
+    }
+}
+
+
+pub fn create_affiliations_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
+    let affiliations: Vec<Affiliation> = external_keys
+        .iter()
+        .flat_map(|external_key| match &schema_value[external_key.key] {
+            Value::Array(value_list) => value_list.iter().collect(),
+            value => vec![value],
+        })
+        .filter_map(|author_value| {
+            let author = try_find_single_author_strategy(author_value)?;
+            let institutions = find_institutions(author_value);
+            let (Author::Person(name) | Author::Organization(name) | Author::Generic(name)) = author;
+            (!institutions.is_empty()).then_some(Affiliation { author: name, institutions })
+        })
+        .collect();
+
+    (!affiliations.is_empty()).then_some(Attribute::Affiliations(affiliations))
+}
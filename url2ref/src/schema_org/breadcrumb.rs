@@ -0,0 +1,48 @@
+use serde_json::Value;
+
+use crate::schema_org::{entities, has_type};
+
+/// Finds the first `BreadcrumbList` among the Schema.org entities.
+fn find_breadcrumb_list<'a>(schema_values: &[&'a Value]) -> Option<&'a Value> {
+    entities(schema_values)
+        .into_iter()
+        .find(|entity| has_type(entity, &["BreadcrumbList"]))
+}
+
+/// Name of a `ListItem`, given either directly or by the linked item.
+fn item_name(list_item: &Value) -> Option<String> {
+    match (&list_item["name"], &list_item["item"]["name"]) {
+        (Value::String(name), _) | (_, Value::String(name)) => Some(name.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the names in the breadcrumb trail, ordered by position.
+fn breadcrumb_trail(schema_values: &[&Value]) -> Vec<String> {
+    let Some(breadcrumb_list) = find_breadcrumb_list(schema_values) else {
+        return Vec::new();
+    };
+    let Value::Array(list_items) = &breadcrumb_list["itemListElement"] else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<&Value> = list_items.iter().collect();
+    items.sort_by_key(|item| item["position"].as_u64().unwrap_or(u64::MAX));
+    items.into_iter().filter_map(item_name).filter(|name| !name.is_empty()).collect()
+}
+
+/// The section is the last-but-one breadcrumb, as the last one is
+/// usually the page itself. Trails of fewer than three breadcrumbs
+/// contain no section.
+pub fn breadcrumb_section(schema_values: &[&Value]) -> Option<String> {
+    let trail = breadcrumb_trail(schema_values);
+    match trail.as_slice() {
+        [_, .., section, _] => Some(section.clone()),
+        _ => None,
+    }
+}
+
+/// The first breadcrumb usually links to the front page of the site.
+pub fn breadcrumb_site(schema_values: &[&Value]) -> Option<String> {
+    breadcrumb_trail(schema_values).into_iter().next()
+}
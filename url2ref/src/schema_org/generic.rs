@@ -39,7 +39,10 @@ pub fn attribute_type_to_attribute(
         AttributeType::Locale => Some(Attribute::Locale(attribute_value)),
         AttributeType::Language => Some(Attribute::Language(attribute_value)),
         AttributeType::Site => panic!("Site should have been handled by specialized method"),
+        AttributeType::Section => Some(Attribute::Section(attribute_value)),
         AttributeType::Url => Some(Attribute::Url(attribute_value)),
+        AttributeType::Type => Some(Attribute::Type(attribute_value)),
+        AttributeType::Duration => Some(Attribute::Duration(attribute_value)),
         _ => None,
     }
 }
@@ -0,0 +1,60 @@
+//! Strategies for parsing [`Attribute::ImageCredit`].
+
+use crate::attribute::Attribute;
+use crate::schema_org::MetadataKey;
+
+use serde_json::Value;
+
+/// The main image, which may be given as a single `ImageObject` or as a
+/// list of images (or of plain URLs), of which the first is used.
+fn main_image(image: &Value) -> Option<&Value> {
+    match image {
+        Value::Array(images) => images.iter().find(|image| image.is_object()),
+        Value::Object(_) => Some(image),
+        _ => None,
+    }
+}
+
+/// The credit of the image is preferred to its caption, which
+/// describes what is shown rather than who took it.
+fn image_credit(image: &Value) -> Option<String> {
+    ["creditText", "caption"]
+        .iter()
+        .filter_map(|key| image[key].as_str())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+pub fn create_image_credit_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
+    external_keys
+        .iter()
+        .filter_map(|external_key| main_image(&schema_value[external_key.key]))
+        .find_map(image_credit)
+        .map(Attribute::ImageCredit)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::create_image_credit_attribute;
+    use crate::attribute::Attribute;
+    use crate::schema_org::MetadataKey;
+
+    #[test]
+    fn image_credit() {
+        let keys = [MetadataKey { key: "image" }];
+        let credit = |value| create_image_credit_attribute(&value, &keys);
+
+        assert_eq!(
+            credit(json!({"image": {"@type": "ImageObject", "caption": "The harbour at dawn", "creditText": "Photo: Jens Hansen"}})),
+            Some(Attribute::ImageCredit("Photo: Jens Hansen".to_string()))
+        );
+        assert_eq!(
+            credit(json!({"image": ["https://example.com/harbour.jpg", {"@type": "ImageObject", "caption": "The harbour at dawn"}]})),
+            Some(Attribute::ImageCredit("The harbour at dawn".to_string()))
+        );
+        assert_eq!(credit(json!({"image": "https://example.com/harbour.jpg"})), None);
+    }
+}
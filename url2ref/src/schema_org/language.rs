@@ -0,0 +1,64 @@
+//! Strategies for parsing [`Attribute::Language`].
+
+use crate::attribute::Attribute;
+use crate::schema_org::MetadataKey;
+
+use serde_json::Value;
+
+/// ISO 639-1 codes of languages by their English and native names.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("danish", "da"), ("dansk", "da"),
+    ("dutch", "nl"), ("nederlands", "nl"),
+    ("english", "en"),
+    ("finnish", "fi"), ("suomi", "fi"),
+    ("french", "fr"), ("français", "fr"),
+    ("german", "de"), ("deutsch", "de"),
+    ("italian", "it"), ("italiano", "it"),
+    ("norwegian", "no"), ("norsk", "no"),
+    ("polish", "pl"), ("polski", "pl"),
+    ("portuguese", "pt"), ("português", "pt"),
+    ("spanish", "es"), ("español", "es"),
+    ("swedish", "sv"), ("svenska", "sv"),
+];
+
+/// Code of a `Language` entity, given by its `alternateName`
+/// or otherwise looked up by its `name`.
+fn language_object_code(language: &Value) -> Option<String> {
+    if let Some(code) = language["alternateName"].as_str().map(str::trim).filter(|code| !code.is_empty()) {
+        return Some(code.to_string());
+    }
+
+    let name = language["name"].as_str()?.trim().to_lowercase();
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(language_name, _)| *language_name == name)
+        .map(|(_, code)| code.to_string())
+}
+
+/// The language may be given as a code (e.g. `da`) or
+/// as a `Language` entity holding the code and name.
+fn try_find_language(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<String> {
+    external_keys.iter().find_map(|external_key| match &schema_value[external_key.key] {
+        Value::String(code) => Some(code.clone()),
+        Value::Object(_) => language_object_code(&schema_value[external_key.key]),
+        _ => None,
+    })
+}
+
+pub fn create_language_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
+    try_find_language(schema_value, external_keys).map(Attribute::Language)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::language_object_code;
+
+    #[test]
+    fn language_object() {
+        assert_eq!(language_object_code(&json!({"@type": "Language", "name": "Danish", "alternateName": "da"})), Some("da".to_string()));
+        assert_eq!(language_object_code(&json!({"@type": "Language", "name": "Svenska"})), Some("sv".to_string()));
+        assert_eq!(language_object_code(&json!({"@type": "Language", "name": "Klingon"})), None);
+    }
+}
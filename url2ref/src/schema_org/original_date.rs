@@ -0,0 +1,36 @@
+//! Strategy for parsing [`Attribute::OriginalDate`] from the work which the
+//! page is a translation, reprint or edition of.
+
+use crate::attribute::{Attribute, Date};
+use crate::parser::parse_date;
+use crate::schema_org::MetadataKey;
+
+use serde_json::Value;
+
+fn date_published(work: &Value) -> Option<Date> {
+    work["datePublished"].as_str().and_then(parse_date)
+}
+
+fn try_find_original_date(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Date> {
+    for external_key in external_keys.iter() {
+        let found_option = match &schema_value[external_key.key] {
+            Value::Object(_) => date_published(&schema_value[external_key.key]),
+            Value::Array(works) => works.iter().find_map(date_published),
+            _ => None,
+        };
+
+        if found_option.is_some() {
+            return found_option;
+        }
+    }
+
+    None
+}
+
+pub fn create_original_date_attribute(
+    schema_value: &Value,
+    external_keys: &[MetadataKey],
+) -> Option<Attribute> {
+    let date = try_find_original_date(schema_value, external_keys)?;
+    Some(Attribute::OriginalDate(date))
+}
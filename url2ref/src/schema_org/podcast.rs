@@ -0,0 +1,31 @@
+use crate::attribute::Attribute;
+use crate::schema_org::MetadataKey;
+
+use serde_json::Value;
+
+/// Name of the series, given either as a `PodcastSeries`
+/// (or other `CreativeWorkSeries`) entity or as a plain name.
+fn try_find_series_name(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<String> {
+    external_keys.iter().find_map(|external_key| match &schema_value[external_key.key] {
+        Value::String(name) => Some(name.clone()),
+        Value::Object(series) => series["name"].as_str().map(str::to_string),
+        _ => None,
+    })
+}
+
+/// Episode numbers may be given as numbers or strings.
+fn try_find_episode_number(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<String> {
+    external_keys.iter().find_map(|external_key| match &schema_value[external_key.key] {
+        Value::String(number) => Some(number.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    })
+}
+
+pub fn create_series_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
+    try_find_series_name(schema_value, external_keys).map(Attribute::Series)
+}
+
+pub fn create_episode_attribute(schema_value: &Value, external_keys: &[MetadataKey]) -> Option<Attribute> {
+    try_find_episode_number(schema_value, external_keys).map(Attribute::Issue)
+}
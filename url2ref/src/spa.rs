@@ -0,0 +1,80 @@
+//! Detection of single-page applications whose HTML is an empty shell,
+//! to which the content and metadata are only added by JavaScript.
+
+use scraper::Selector;
+use thiserror::Error;
+
+use crate::parser::ParseInfo;
+
+/// Elements into which single-page applications are commonly rendered.
+const ROOT_SELECTORS: &str = "div#app, div#root";
+
+/// Shells hold little more than a `<noscript>` notice; pages with more
+/// text than this are considered rendered on the server.
+const MAX_SHELL_TEXT_LENGTH: usize = 500;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("The page requires JavaScript to show its content, so its metadata may be missing (the Zotero metadata type may find it)")]
+pub struct JavaScriptRequired;
+
+/// Whether the page is the shell of a single-page application, i.e. has
+/// an application root, hardly any text and no Open Graph or Schema.org
+/// metadata.
+pub fn is_spa_shell(parse_info: &ParseInfo) -> bool {
+    let document = &parse_info.document;
+
+    let root = Selector::parse(ROOT_SELECTORS).unwrap();
+    if document.select(&root).next().is_none() {
+        return false;
+    }
+
+    let body = Selector::parse("body").unwrap();
+    let text_length: usize = document
+        .select(&body)
+        .flat_map(|body| body.text())
+        .map(|text| text.trim().len())
+        .sum();
+    if text_length > MAX_SHELL_TEXT_LENGTH {
+        return false;
+    }
+
+    let has_opengraph = parse_info.html.as_ref().is_some_and(|html| !html.opengraph.properties.is_empty());
+    let schema_org = Selector::parse("script[type=\"application/ld+json\"], [itemtype]").unwrap();
+    !has_opengraph && document.select(&schema_org).next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::Html;
+
+    use super::is_spa_shell;
+    use crate::parser::ParseInfo;
+
+    fn parse_info(raw_html: &str) -> ParseInfo<'static> {
+        ParseInfo {
+            url: None,
+            document: Html::parse_document(raw_html),
+            raw_html: raw_html.to_string(),
+            html: None,
+            json_ld: Vec::new(),
+            bibliography: None,
+            zotero: None,
+            parsers: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn shells_detected() {
+        let shell = r#"<html><head><title>Tidewatch</title></head>
+            <body><noscript>You need to enable JavaScript to run this app.</noscript><div id="root"></div></body></html>"#;
+        assert!(is_spa_shell(&parse_info(shell)));
+
+        let with_json_ld = r#"<html><head><script type="application/ld+json">{"@type": "NewsArticle"}</script></head>
+            <body><div id="app"></div></body></html>"#;
+        assert!(!is_spa_shell(&parse_info(with_json_ld)));
+
+        let without_root = r#"<html><body><p>Short page.</p></body></html>"#;
+        assert!(!is_spa_shell(&parse_info(without_root)));
+    }
+}
@@ -0,0 +1,118 @@
+//! Support for dating syndicated articles, i.e. articles republished by
+//! another site than the one originally publishing them, e.g. a news
+//! agency story on the site of a newspaper.
+//!
+//! The republished page names the original using `<link rel="canonical">`,
+//! while its metadata types may give either the original or the republished
+//! article, each with its own URL and date. The date given along with the
+//! URL of the original is the one cited.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::attribute::{Attribute, AttributeType};
+use crate::generator::attribute_config::{AttributeConfig, AttributePriority};
+use crate::generator::MetadataType;
+use crate::parser::{parse, ParseInfo};
+
+/// Host of the URL without any `www.` prefix, by which the sites are told apart.
+fn site_host(url: &Url) -> Option<&str> {
+    url.host_str().map(|host| host.trim_start_matches("www."))
+}
+
+/// Finds the absolute URL of the canonical version of the page, if given.
+fn find_canonical_url(document: &Html, base: Option<&str>) -> Option<Url> {
+    let selector = Selector::parse("link[rel~=\"canonical\"][href]").unwrap();
+    let href = document.select(&selector).next()?.value().attr("href")?;
+
+    match base.and_then(|b| Url::parse(b).ok()) {
+        Some(base) => base.join(href).ok(),
+        None => Url::parse(href).ok(),
+    }
+}
+
+/// URL and date given by each metadata type giving both, in the order
+/// in which the metadata types are tried for the date.
+fn dates_by_url(parse_info: &ParseInfo, config: &AttributeConfig) -> Vec<(MetadataType, Url, Attribute)> {
+    let default_priority = AttributePriority::default();
+    let priority = config.get(AttributeType::Date).as_ref().unwrap_or(&default_priority);
+    let fallback = config.fallback.as_ref().map(|fallback| fallback.priority.as_slice()).unwrap_or_default();
+
+    priority
+        .priority
+        .iter()
+        .chain(fallback)
+        .filter_map(|metadata_type| {
+            let priority = AttributePriority::new(&[*metadata_type]);
+            let Some((Attribute::Url(url), _)) = parse(parse_info, AttributeType::Url, &priority) else {
+                return None;
+            };
+            let (date, _) = parse(parse_info, AttributeType::Date, &priority)?;
+            Some((*metadata_type, Url::parse(&url).ok()?, date))
+        })
+        .collect()
+}
+
+/// Date given along with the canonical URL, along with the metadata type
+/// giving it, if the metadata types give the dates of articles on more
+/// than one site and these disagree. The metadata is only parsed for
+/// pages naming a canonical URL.
+pub fn find_canonical_date(parse_info: &ParseInfo, config: &AttributeConfig) -> Option<(Attribute, MetadataType)> {
+    let canonical_url = find_canonical_url(&parse_info.document, parse_info.url)?;
+    let canonical_host = site_host(&canonical_url)?;
+    let dates = dates_by_url(parse_info, config);
+
+    let (canonical, syndicated): (Vec<_>, Vec<_>) =
+        dates.into_iter().partition(|(_, url, _)| site_host(url) == Some(canonical_host));
+    let (metadata_type, _, date) = canonical.into_iter().next()?;
+    syndicated
+        .iter()
+        .any(|(_, _, syndicated_date)| *syndicated_date != date)
+        .then_some((date, metadata_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::find_canonical_date;
+    use crate::attribute::{Attribute, Date};
+    use crate::generator::attribute_config::{AttributeConfig, AttributePriority};
+    use crate::generator::MetadataType;
+    use crate::parser::ParseInfo;
+    use crate::GenerationOptions;
+
+    /// Page whose Open Graph metadata describes the republication on the
+    /// courier's site, and whose Schema.org metadata the original.
+    fn page(canonical: &str) -> String {
+        format!(
+            r#"<html><head>{canonical}
+            <meta property="og:url" content="https://courier.example.com/story">
+            <meta property="article:published_time" content="2024-10-10">
+            <script type="application/ld+json">
+            {{"@context": "https://schema.org", "@type": "NewsArticle",
+             "url": "https://wire.example.com/story", "datePublished": "2024-10-09"}}
+            </script></head><body></body></html>"#
+        )
+    }
+
+    #[test]
+    fn canonical_date_preferred() {
+        let config = AttributeConfig::new(AttributePriority::new(&[MetadataType::OpenGraph, MetadataType::SchemaOrg]));
+        let options = GenerationOptions { attribute_config: config.clone(), ..Default::default() };
+        let url = "https://courier.example.com/story?page=1";
+        let find = |canonical: &str| {
+            let parse_info = ParseInfo::from_html(url, page(canonical), &options).unwrap();
+            find_canonical_date(&parse_info, &config)
+        };
+
+        let (date, metadata_type) = find(r#"<link rel="canonical" href="https://www.wire.example.com/story">"#).unwrap();
+        assert_eq!(date, Attribute::Date(Date::YearMonthDay(NaiveDate::from_ymd_opt(2024, 10, 9).unwrap())));
+        assert_eq!(metadata_type, MetadataType::SchemaOrg);
+
+        // Pages canonical on their own site keep the date given along with their own URL.
+        let canonical = find(r#"<link rel="canonical" href="/story">"#);
+        assert_eq!(canonical.map(|(_, metadata_type)| metadata_type), Some(MetadataType::OpenGraph));
+        assert_eq!(find(""), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Normalization of titles written entirely in uppercase.
+
+/// Short words which are not capitalized in title case,
+/// unless they begin the title or follow a colon.
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor",
+    "of", "on", "or", "per", "the", "to", "up", "via", "vs",
+];
+
+/// Common acronyms which are kept in uppercase. Words without vowels
+/// (e.g. `BBC`) and words containing digits or periods (e.g. `G7`,
+/// `U.S.`) are recognized as acronyms without being listed here.
+const ACRONYMS: &[&str] = &[
+    "AI", "CEO", "COVID", "EU", "FIFA", "IMF", "NASA", "NATO",
+    "OPEC", "UEFA", "UK", "UN", "UNESCO", "UNICEF", "US", "USA",
+];
+
+/// Whether the title contains letters, none of which are lowercase.
+fn is_all_caps(title: &str) -> bool {
+    let mut letters = title.chars().filter(|c| c.is_alphabetic()).peekable();
+    letters.peek().is_some() && letters.all(|c| !c.is_lowercase())
+}
+
+fn is_acronym(word: &str) -> bool {
+    let letters: String = word.chars().filter(|c| c.is_alphanumeric() || *c == '.').collect();
+    let has_vowel = letters.chars().any(|c| "AEIOUY".contains(c));
+    let has_digit_or_period = letters.chars().any(|c| c.is_ascii_digit() || c == '.');
+
+    ACRONYMS.contains(&letters.as_str()) || !has_vowel || has_digit_or_period
+}
+
+/// Capitalizes the first letter of the word and lowercases the rest.
+fn capitalize(word: &str) -> String {
+    let mut capitalized = String::with_capacity(word.len());
+    let mut first_letter = true;
+    for c in word.chars() {
+        if c.is_alphabetic() && first_letter {
+            capitalized.extend(c.to_uppercase());
+            first_letter = false;
+        } else {
+            capitalized.extend(c.to_lowercase());
+        }
+    }
+    capitalized
+}
+
+fn convert_word(word: &str, is_first: bool) -> String {
+    if is_acronym(word) {
+        return word.to_string();
+    }
+
+    let lowercase = word.to_lowercase();
+    if !is_first && MINOR_WORDS.contains(&lowercase.as_str()) {
+        return lowercase;
+    }
+
+    // Each part of a hyphenated compound is capitalized.
+    word.split('-').map(capitalize).collect::<Vec<_>>().join("-")
+}
+
+/// Converts a title written entirely in uppercase to title case, keeping
+/// acronyms in uppercase. Other titles are returned unchanged.
+pub fn normalize_title_case(title: &str) -> String {
+    if !is_all_caps(title) {
+        return title.to_string();
+    }
+
+    let mut is_first = true;
+    let words: Vec<String> = title
+        .split_whitespace()
+        .map(|word| {
+            let converted = convert_word(word, is_first);
+            is_first = word.ends_with(':');
+            converted
+        })
+        .collect();
+
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_title_case;
+
+    #[test]
+    fn all_caps_title() {
+        assert_eq!(
+            normalize_title_case("BREAKING: MAJOR STORM HITS COAST"),
+            "Breaking: Major Storm Hits Coast"
+        );
+        assert_eq!(
+            normalize_title_case("NASA AND THE BBC REPORT ON THE STATE OF THE U.S. SPACE-PROGRAM"),
+            "NASA and the BBC Report on the State of the U.S. Space-Program"
+        );
+    }
+
+    #[test]
+    fn mixed_case_title_unchanged() {
+        let title = "Council approves NHS funding for the iPhone app";
+        assert_eq!(normalize_title_case(title), title);
+    }
+}
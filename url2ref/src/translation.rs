@@ -0,0 +1,263 @@
+//! Translation of titles using the service selected by the
+//! [`TranslationProvider`] of the [`TranslationOptions`].
+
+use deepl_api::{DeepL, TranslatableTextList};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::curl;
+use crate::generator::{LanguageError, ReferenceGenerationError, RequestOptions, TranslationOptions, TranslationProvider};
+
+/// Endpoint of version 2 of the Google Cloud Translation API.
+const GOOGLE_TRANSLATE_ENDPOINT: &str = "https://translation.googleapis.com/language/translate/v2";
+
+/// Languages DeepL translates from. Regional variants are not distinguished.
+pub const DEEPL_SOURCE_LANGUAGES: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "EN", "ES", "ET", "FI", "FR", "HU", "ID", "IT", "JA",
+    "KO", "LT", "LV", "NB", "NL", "PL", "PT", "RO", "RU", "SK", "SL", "SV", "TR", "UK", "ZH",
+];
+
+/// Languages DeepL translates into, including regional variants.
+pub const DEEPL_TARGET_LANGUAGES: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "EN-GB", "EN-US", "ES", "ET", "FI", "FR", "HU", "ID", "IT",
+    "JA", "KO", "LT", "LV", "NB", "NL", "PL", "PT-BR", "PT-PT", "RO", "RU", "SK", "SL", "SV", "TR",
+    "UK", "ZH", "ZH-HANS", "ZH-HANT",
+];
+
+/// Variants used for target languages given without one.
+const DEEPL_DEFAULT_VARIANTS: &[(&str, &str)] = &[("EN", "EN-GB"), ("PT", "PT-PT")];
+
+/// Converts a language code to the uppercase form used by DeepL,
+/// e.g. `pt_br` to `PT-BR`.
+fn to_deepl_case(code: &str) -> String {
+    code.trim().replace('_', "-").to_uppercase()
+}
+
+/// Normalizes the language to translate from, dropping any region.
+pub fn normalize_deepl_source(code: &str) -> Result<String, LanguageError> {
+    let normalized = to_deepl_case(code);
+    let language = normalized.split('-').next().unwrap_or_default();
+    DEEPL_SOURCE_LANGUAGES
+        .contains(&language)
+        .then(|| language.to_string())
+        .ok_or_else(|| LanguageError::UnsupportedSource(code.to_string()))
+}
+
+/// Normalizes the language to translate into, choosing
+/// the default variant of languages which have several.
+pub fn normalize_deepl_target(code: &str) -> Result<String, LanguageError> {
+    let normalized = to_deepl_case(code);
+    let normalized = DEEPL_DEFAULT_VARIANTS
+        .iter()
+        .find(|(language, _)| *language == normalized)
+        .map_or(normalized.as_str(), |(_, variant)| variant);
+    DEEPL_TARGET_LANGUAGES
+        .contains(&normalized)
+        .then(|| normalized.to_string())
+        .ok_or_else(|| LanguageError::UnsupportedTarget(code.to_string()))
+}
+
+/// Service which translates texts from the source language, which is
+/// guessed if not given, to the target language. The texts are sent
+/// in a single request and the translations returned in their order.
+pub trait Translator {
+    fn translate(&self, texts: &[String], source: Option<&str>, target: &str) -> Result<Vec<String>, ReferenceGenerationError>;
+}
+
+/// Checks that a translation was returned for every text.
+fn check_count(translations: Vec<String>, texts: &[String], provider: &str) -> Result<Vec<String>, ReferenceGenerationError> {
+    if translations.len() != texts.len() {
+        return Err(ReferenceGenerationError::TranslationError(format!(
+            "{provider} returned {} translations of {} texts",
+            translations.len(),
+            texts.len()
+        )));
+    }
+    Ok(translations)
+}
+
+pub struct DeepLTranslator {
+    api_key: String,
+}
+
+impl Translator for DeepLTranslator {
+    fn translate(&self, texts: &[String], source: Option<&str>, target: &str) -> Result<Vec<String>, ReferenceGenerationError> {
+        let text_list = TranslatableTextList {
+            source_language: source.map(str::to_string),
+            target_language: target.to_string(),
+            texts: texts.to_vec(),
+        };
+
+        let translated = DeepL::new(self.api_key.clone())
+            .translate(None, text_list)
+            .map_err(|error| ReferenceGenerationError::TranslationError(error.to_string()))?;
+        check_count(translated.into_iter().map(|translation| translation.text).collect(), texts, "DeepL")
+    }
+}
+
+#[derive(Deserialize)]
+struct GoogleTranslation {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct GoogleTranslations {
+    translations: Vec<GoogleTranslation>,
+}
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+    data: GoogleTranslations,
+}
+
+pub struct GoogleTranslator {
+    api_key: String,
+    endpoint: String,
+    request_options: RequestOptions,
+}
+
+impl Translator for GoogleTranslator {
+    fn translate(&self, texts: &[String], source: Option<&str>, target: &str) -> Result<Vec<String>, ReferenceGenerationError> {
+        let mut body = json!({ "q": texts, "target": target, "format": "text" });
+        if let Some(source) = source {
+            body["source"] = json!(source);
+        }
+
+        let url = format!("{}?key={}", self.endpoint, self.api_key);
+        let response = curl::post_json(&url, &body.to_string(), &self.request_options)
+            .map_err(|error| ReferenceGenerationError::TranslationError(error.to_string()))?;
+        let response: GoogleResponse = serde_json::from_str(&response)
+            .map_err(|error| ReferenceGenerationError::TranslationError(error.to_string()))?;
+
+        let translations = response.data.translations.into_iter().map(|translation| translation.translated_text).collect();
+        check_count(translations, texts, "Google")
+    }
+}
+
+/// Returns the translator of the configured provider, given that
+/// its API key is set.
+pub fn translator(
+    options: &TranslationOptions,
+    request_options: &RequestOptions,
+) -> Result<Box<dyn Translator>, ReferenceGenerationError> {
+    let missing_key = |provider: &str| ReferenceGenerationError::TranslationError(format!("No {provider} API key given"));
+
+    match options.provider {
+        TranslationProvider::DeepL => {
+            let api_key = options.deepl_key.clone().ok_or_else(|| missing_key("DeepL"))?;
+            Ok(Box::new(DeepLTranslator { api_key }))
+        }
+        TranslationProvider::Google => {
+            let api_key = options.google_key.clone().ok_or_else(|| missing_key("Google"))?;
+            Ok(Box::new(GoogleTranslator {
+                api_key,
+                endpoint: GOOGLE_TRANSLATE_ENDPOINT.to_string(),
+                request_options: request_options.clone(),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{normalize_deepl_source, normalize_deepl_target, translator, GoogleTranslator, Translator};
+    use crate::generator::{LanguageError, ReferenceGenerationError, RequestOptions, TranslationOptions, TranslationProvider};
+    use crate::mock_server::{MockServer, Response};
+
+    fn google_translator(server: &MockServer) -> GoogleTranslator {
+        GoogleTranslator {
+            api_key: "secret".to_string(),
+            endpoint: server.url("/language/translate/v2"),
+            request_options: RequestOptions { retries: 0, ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn google_translation() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+        let server = MockServer::start(move |request| {
+            received.lock().unwrap().push((request.path.clone(), request.body.clone()));
+            Response::ok(r#"{"data": {"translations": [
+                {"translatedText": "Harbour expansion approved"}, {"translatedText": "The Fjord Newspaper"}
+            ]}}"#)
+        });
+
+        let texts = ["Havneudvidelse godkendt".to_string(), "Fjordavisen".to_string()];
+        let translated = google_translator(&server).translate(&texts, Some("da"), "en").unwrap();
+        assert_eq!(translated, ["Harbour expansion approved", "The Fjord Newspaper"]);
+
+        let (path, body) = requests.lock().unwrap()[0].clone();
+        assert_eq!(path, "/language/translate/v2?key=secret");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["q"], serde_json::json!(["Havneudvidelse godkendt", "Fjordavisen"]));
+        assert_eq!(body["source"], "da");
+        assert_eq!(body["target"], "en");
+    }
+
+    #[test]
+    fn google_translation_error() {
+        let server = MockServer::start(|_| Response::status(403));
+
+        let result = google_translator(&server).translate(&["Havneudvidelse godkendt".to_string()], None, "en");
+        let Err(ReferenceGenerationError::TranslationError(message)) = result else {
+            panic!("Expected a translation error")
+        };
+        assert!(message.contains("403"));
+    }
+
+    #[test]
+    fn deepl_languages_accepted() {
+        assert_eq!(normalize_deepl_source("da"), Ok("DA".to_string()));
+        assert_eq!(normalize_deepl_target("DE"), Ok("DE".to_string()));
+        assert_eq!(normalize_deepl_target("en-US"), Ok("EN-US".to_string()));
+        assert_eq!(normalize_deepl_target("zh-Hans"), Ok("ZH-HANS".to_string()));
+    }
+
+    #[test]
+    fn deepl_languages_normalized() {
+        assert_eq!(normalize_deepl_source("en-GB"), Ok("EN".to_string()));
+        assert_eq!(normalize_deepl_target("pt_br"), Ok("PT-BR".to_string()));
+        assert_eq!(normalize_deepl_target("en"), Ok("EN-GB".to_string()));
+        assert_eq!(normalize_deepl_target("pt"), Ok("PT-PT".to_string()));
+    }
+
+    #[test]
+    fn deepl_languages_rejected() {
+        assert_eq!(normalize_deepl_source("kl"), Err(LanguageError::UnsupportedSource("kl".to_string())));
+        assert_eq!(normalize_deepl_target("fr-CA"), Err(LanguageError::UnsupportedTarget("fr-CA".to_string())));
+        assert_eq!(normalize_deepl_target(""), Err(LanguageError::UnsupportedTarget("".to_string())));
+
+        let options = TranslationOptions {
+            source: Some("da".to_string()),
+            target: Some("xx".to_string()),
+            ..Default::default()
+        };
+        let Err(error) = options.validate() else {
+            panic!("Expected an unsupported language")
+        };
+        assert!(error.to_string().starts_with("DeepL cannot translate into \"xx\", supported target languages are AR, BG"));
+
+        let options = TranslationOptions { provider: TranslationProvider::Google, ..options };
+        assert!(options.validate().is_ok_and(|options| options.target == Some("xx".to_string())));
+    }
+
+    #[test]
+    fn missing_api_key() {
+        let options = TranslationOptions {
+            target: Some("en".to_string()),
+            provider: TranslationProvider::Google,
+            deepl_key: Some("secret".to_string()),
+            ..Default::default()
+        };
+
+        let Err(ReferenceGenerationError::TranslationError(message)) = translator(&options, &RequestOptions::default())
+        else {
+            panic!("Expected a translation error")
+        };
+        assert_eq!(message, "No Google API key given");
+    }
+}
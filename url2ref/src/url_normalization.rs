@@ -0,0 +1,144 @@
+//! Normalization of the cited URLs, which metadata often gives with
+//! tracking parameters (e.g. `?utm_source=twitter`) or without a scheme.
+
+use url::form_urlencoded;
+use url::Url;
+
+/// Query parameters added for tracking rather than to identify the page.
+/// Parameters ending with `*` match every parameter with that prefix.
+pub const DEFAULT_TRACKING_PARAMETERS: &[&str] = &["utm_*", "fbclid", "gclid", "mc_cid", "mc_eid"];
+
+/// Extensions of files, which are told apart from domains by the first
+/// segment of URLs lacking a scheme, e.g. `index.html?page=2`.
+const FILE_EXTENSIONS: &[&str] = &["html", "htm", "shtml", "php", "asp", "aspx", "jsp", "cgi"];
+
+/// [`DEFAULT_TRACKING_PARAMETERS`], as used by [`crate::GenerationOptions`].
+pub fn default_tracking_parameters() -> Vec<String> {
+    DEFAULT_TRACKING_PARAMETERS.iter().map(|parameter| parameter.to_string()).collect()
+}
+
+fn is_tracking_parameter(name: &str, tracking_parameters: &[String]) -> bool {
+    tracking_parameters.iter().any(|parameter| match parameter.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == parameter,
+    })
+}
+
+/// Whether the URL lacking a scheme begins with a domain, as in
+/// `jyllands-posten.dk/jpaarhus/`, rather than a relative path.
+fn starts_with_domain(url: &str) -> bool {
+    let host = url.split(['/', '?', '#']).next().unwrap_or_default();
+    let Some((_, top_level_domain)) = host.rsplit_once('.') else {
+        return false;
+    };
+    top_level_domain.len() >= 2
+        && top_level_domain.chars().all(|c| c.is_ascii_alphabetic())
+        && !FILE_EXTENSIONS.contains(&top_level_domain.to_ascii_lowercase().as_str())
+}
+
+/// Parses the URL, resolving relative URLs against the URL of the page
+/// and adding `https` to URLs lacking a scheme.
+fn parse(url: &str, base: Option<&str>) -> Option<Url> {
+    let base = base.and_then(|base| Url::parse(base).ok());
+    if let Ok(url) = Url::parse(url) {
+        return Some(url);
+    }
+    if let Some(url) = url.strip_prefix("//") {
+        let scheme = base.as_ref().map_or("https", Url::scheme);
+        return Url::parse(&format!("{scheme}://{url}")).ok();
+    }
+    if starts_with_domain(url) {
+        return Url::parse(&format!("https://{url}")).ok();
+    }
+    base?.join(url).ok()
+}
+
+/// Normalizes the URL: relative URLs are resolved against the URL of the
+/// page, `https` is added to URLs lacking a scheme, and the tracking
+/// parameters and fragment are removed. Fragments used for routing (e.g.
+/// `#!/article/17`) are kept. URLs which cannot be parsed are kept as is.
+pub fn normalize_url(url: &str, base: Option<&str>, tracking_parameters: &[String]) -> String {
+    let url = url.trim();
+    let Some(mut normalized) = parse(url, base) else {
+        return url.to_string();
+    };
+    if !matches!(normalized.scheme(), "http" | "https") {
+        return url.to_string();
+    }
+
+    let has_tracking = normalized.query_pairs().any(|(name, _)| is_tracking_parameter(&name, tracking_parameters));
+    if has_tracking {
+        // The remaining parameters are decoded and re-encoded consistently.
+        let query: Vec<(String, String)> = normalized
+            .query_pairs()
+            .filter(|(name, _)| !is_tracking_parameter(name, tracking_parameters))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        let query = form_urlencoded::Serializer::new(String::new()).extend_pairs(query).finish();
+        normalized.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+
+    let is_route = normalized.fragment().is_some_and(|fragment| fragment.starts_with(['!', '/']));
+    if !is_route {
+        normalized.set_fragment(None);
+    }
+
+    normalized.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_tracking_parameters, normalize_url};
+
+    fn normalize(url: &str, base: Option<&str>) -> String {
+        normalize_url(url, base, &default_tracking_parameters())
+    }
+
+    #[test]
+    fn relative_urls_resolved() {
+        let base = Some("https://harbourpost.example.dk/news/2024/harbour");
+        #[rustfmt::skip]
+        let cases = [
+            ("/news/2024/ferry", base, "https://harbourpost.example.dk/news/2024/ferry"),
+            ("ferry?page=2", base, "https://harbourpost.example.dk/news/2024/ferry?page=2"),
+            ("//cdn.example.dk/story", Some("http://example.dk/"), "http://cdn.example.dk/story"),
+            ("jyllands-posten.dk/jpaarhus/ECE16679033/", None, "https://jyllands-posten.dk/jpaarhus/ECE16679033/"),
+            ("index.html", base, "https://harbourpost.example.dk/news/2024/index.html"),
+            ("/news/2024/ferry", None, "/news/2024/ferry"),
+        ];
+
+        for (url, base, expected) in cases {
+            assert_eq!(normalize(url, base), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn tracking_parameters_stripped() {
+        #[rustfmt::skip]
+        let cases = [
+            ("https://example.com/story?utm_source=twitter&utm_medium=social", "https://example.com/story"),
+            ("https://example.com/story?id=17&fbclid=IwAR0abc&utm_campaign=x", "https://example.com/story?id=17"),
+            ("https://example.com/search?q=harbour%20expansion&gclid=abc#comments", "https://example.com/search?q=harbour+expansion"),
+            ("https://example.com/story?mc_cid=1a2b&mc_eid=3c4d", "https://example.com/story"),
+            ("https://example.com/app#!/article/17", "https://example.com/app#!/article/17"),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(normalize(url, None), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn clean_urls_untouched() {
+        let urls = [
+            "https://www.information.dk/udland/2023/12/nyt-kompromis-kan-historisk-stadig-ingen-udfasning-fossile-braendsler",
+            "https://example.com/search?q=harbour%20expansion&page=2",
+            "https://doi.org/10.1000/xyz123",
+            "http://web.archive.org/web/20211026003805/https://www.information.dk/kultur/2018/01/casper",
+        ];
+
+        for url in urls {
+            assert_eq!(normalize(url, Some("https://example.com/")), url);
+        }
+    }
+}
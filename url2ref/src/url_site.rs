@@ -0,0 +1,82 @@
+//! Derivation of the site from the host of the URL, e.g. `bbc.co.uk` for
+//! `https://www.bbc.co.uk/news`, for pages whose metadata names no site.
+
+use url::Url;
+
+/// Second-level domains under which domains are registered, e.g. `co.uk`,
+/// so that the registrable domain of `news.bbc.co.uk` is `bbc.co.uk`.
+const SECOND_LEVEL_SUFFIXES: &[&str] = &[
+    "co.uk", "org.uk", "ac.uk", "gov.uk", "me.uk", "com.au", "net.au", "org.au", "edu.au", "gov.au",
+    "co.nz", "org.nz", "co.jp", "ne.jp", "or.jp", "co.za", "org.za", "com.br", "com.mx", "com.ar",
+    "com.cn", "com.hk", "com.sg", "com.tr", "co.in", "co.kr", "co.il",
+];
+
+/// Names of sites whose domains do not tell how the site writes its name.
+#[rustfmt::skip]
+const SITE_NAMES: &[(&str, &str)] = &[
+    ("bbc.co.uk",          "BBC"),
+    ("bbc.com",            "BBC"),
+    ("nytimes.com",        "The New York Times"),
+    ("washingtonpost.com", "The Washington Post"),
+    ("theguardian.com",    "The Guardian"),
+    ("wsj.com",            "The Wall Street Journal"),
+    ("jyllands-posten.dk", "Jyllands-Posten"),
+    ("information.dk",     "Dagbladet Information"),
+    ("dr.dk",              "DR"),
+    ("stackoverflow.com",  "Stack Overflow"),
+    ("superuser.com",      "Super User"),
+    ("serverfault.com",    "Server Fault"),
+    ("askubuntu.com",      "Ask Ubuntu"),
+    ("mathoverflow.net",   "MathOverflow"),
+];
+
+/// Returns the registrable domain of the host, i.e. the domain directly
+/// below the public suffix.
+fn registrable_domain(host: &str) -> &str {
+    let labels = if SECOND_LEVEL_SUFFIXES.iter().any(|suffix| host.ends_with(&format!(".{suffix}"))) { 3 } else { 2 };
+    match host.match_indices('.').rev().nth(labels - 1) {
+        Some((index, _)) => &host[index + 1..],
+        None => host,
+    }
+}
+
+/// Returns the site of the URL: the name of well-known sites, or else
+/// the registrable domain of its host, e.g. `harbourpost.dk`.
+pub fn site_from_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    if host.parse::<std::net::IpAddr>().is_ok() || !host.contains('.') {
+        return None;
+    }
+
+    let domain = registrable_domain(&host);
+    let site = SITE_NAMES
+        .iter()
+        .find(|(site_domain, _)| *site_domain == domain)
+        .map_or(domain, |(_, name)| name);
+    Some(site.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::site_from_url;
+
+    #[test]
+    fn sites_from_hosts() {
+        #[rustfmt::skip]
+        let cases = [
+            ("https://www.harbourpost.dk/news/2024/harbour", Some("harbourpost.dk")),
+            ("https://fjordlys.no/kultur/", Some("fjordlys.no")),
+            ("https://news.bbc.co.uk/2/hi/uk_news/123.stm", Some("BBC")),
+            ("https://www.abc.net.au/news", Some("abc.net.au")),
+            ("https://www.nytimes.com/2024/03/01/world/europe/harbour.html", Some("The New York Times")),
+            ("https://meta.stackoverflow.com/questions/251758", Some("Stack Overflow")),
+            ("http://127.0.0.1:8000/news", None),
+            ("http://localhost/news", None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(site_from_url(url).as_deref(), expected, "{url}");
+        }
+    }
+}
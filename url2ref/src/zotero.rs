@@ -0,0 +1,281 @@
+//! Parser responsible for producing an [`Attribute`] from the Zotero item
+//! returned for a URL by a [Citoid] service, which runs the Zotero
+//! translators used by Wikipedia's citation tool.
+//!
+//! [Citoid]: https://www.mediawiki.org/wiki/Citoid
+
+use serde::Deserialize;
+use url::form_urlencoded::byte_serialize;
+use url::Url;
+
+use crate::attribute::{Attribute, AttributeType, Author};
+use crate::curl;
+use crate::generator::{RequestOptions, ResponseCache};
+use crate::parser::{parse_date, AttributeParser, ParseInfo};
+
+/// Endpoint of Wikipedia's Citoid service, to which the
+/// percent-encoded URL of the page is appended.
+pub const CITOID_ENDPOINT: &str = "https://en.wikipedia.org/api/rest_v1/data/citation/zotero/";
+
+/// Creator types of Zotero items which are cited as authors,
+/// as opposed to e.g. editors and translators.
+const AUTHOR_CREATOR_TYPES: &[&str] = &["author", "podcaster", "director", "presenter"];
+
+/// Zotero item types of scholarly works.
+const SCHOLARLY_ITEM_TYPES: &[&str] = &["journalArticle", "conferencePaper", "preprint"];
+
+/// Creator of a Zotero item. Persons are given by their first and
+/// last names, organizations by a single name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoteroCreator {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub name: Option<String>,
+    pub creator_type: String,
+}
+
+/// Zotero item describing the page. Only the fields used for
+/// references are deserialized.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ZoteroResult {
+    pub item_type: String,
+    pub title: Option<String>,
+    pub creators: Vec<ZoteroCreator>,
+    pub date: Option<String>,
+    pub url: Option<String>,
+    pub website_title: Option<String>,
+    pub blog_title: Option<String>,
+    pub publication_title: Option<String>,
+    pub series_title: Option<String>,
+    pub section: Option<String>,
+    pub language: Option<String>,
+    pub publisher: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+    pub episode_number: Option<String>,
+}
+
+/// Whether Citoid should not be asked about the URL, i.e. pages read
+/// from files and URLs which Citoid cannot retrieve itself.
+pub fn should_skip_zotero(url: Option<&str>) -> bool {
+    let Some(url) = url.and_then(|url| Url::parse(url).ok()) else {
+        return true;
+    };
+    !matches!(url.scheme(), "http" | "https")
+}
+
+/// Retrieves the Zotero item for the URL from the Citoid service at the
+/// endpoint, consulting the cache first, if provided.
+pub fn fetch_result(
+    url: &str,
+    endpoint: &str,
+    cache: Option<&ResponseCache>,
+    options: &RequestOptions,
+) -> Option<ZoteroResult> {
+    let key = format!("zotero:{url}");
+    let response = match cache.and_then(|cache| cache.load(&key)) {
+        Some(response) => response,
+        None => {
+            let encoded_url: String = byte_serialize(url.as_bytes()).collect();
+            let response = curl::get(&format!("{endpoint}{encoded_url}"), Some("Accept: application/json"), true, options).ok()?;
+            if let Some(cache) = cache {
+                cache.store(&key, &response);
+            }
+            response
+        }
+    };
+
+    // Citoid responds with a list of items, the first of which describes the page.
+    let items: Vec<ZoteroResult> = serde_json::from_str(&response).ok()?;
+    items.into_iter().next()
+}
+
+fn creators_to_attribute(creators: &[ZoteroCreator]) -> Option<Attribute> {
+    let authors: Vec<Author> = creators
+        .iter()
+        .filter(|creator| AUTHOR_CREATOR_TYPES.contains(&creator.creator_type.as_str()))
+        .filter_map(|creator| match (&creator.first_name, &creator.last_name, &creator.name) {
+            (Some(first_name), Some(last_name), _) => Some(Author::Person(format!("{first_name} {last_name}"))),
+            (None, Some(last_name), _) => Some(Author::Person(last_name.clone())),
+            (_, _, Some(name)) => Some(Author::Organization(name.clone())),
+            _ => None,
+        })
+        .collect();
+
+    (!authors.is_empty()).then_some(Attribute::Authors(authors))
+}
+
+/// Schema.org type corresponding to the Zotero item type, so that the
+/// kind of reference is chosen as for pages annotated using Schema.org.
+fn item_type_to_schema_type(item_type: &str) -> Option<&'static str> {
+    match item_type {
+        item_type if SCHOLARLY_ITEM_TYPES.contains(&item_type) => Some("ScholarlyArticle"),
+        "newspaperArticle" | "magazineArticle" => Some("NewsArticle"),
+        "blogPost" => Some("BlogPosting"),
+        "videoRecording" => Some("VideoObject"),
+        "podcast" => Some("PodcastEpisode"),
+        "webpage" => Some("WebPage"),
+        _ => None,
+    }
+}
+
+pub struct Zotero;
+
+impl Zotero {
+    #[rustfmt::skip]
+    pub fn parse_from_result(result: &ZoteroResult, attribute_type: AttributeType) -> Option<Attribute> {
+        let scholarly = SCHOLARLY_ITEM_TYPES.contains(&result.item_type.as_str());
+        let non_empty = |value: &Option<String>| value.clone().filter(|value| !value.trim().is_empty());
+
+        match attribute_type {
+            AttributeType::Title     => non_empty(&result.title).map(Attribute::Title),
+            AttributeType::Author    => creators_to_attribute(&result.creators),
+            AttributeType::Date      => result.date.as_deref().and_then(parse_date).map(Attribute::Date),
+            AttributeType::Url       => non_empty(&result.url).map(Attribute::Url),
+            AttributeType::Site      => non_empty(&result.website_title)
+                .or_else(|| non_empty(&result.blog_title))
+                .or_else(|| non_empty(&result.publication_title).filter(|_| !scholarly))
+                .map(Attribute::Site),
+            AttributeType::Journal   => non_empty(&result.publication_title).filter(|_| scholarly).map(Attribute::Journal),
+            AttributeType::Section   => non_empty(&result.section).map(Attribute::Section),
+            AttributeType::Language  => non_empty(&result.language).map(Attribute::Language),
+            AttributeType::Publisher => non_empty(&result.publisher).map(Attribute::Publisher),
+            AttributeType::Volume    => non_empty(&result.volume).map(Attribute::Volume),
+            AttributeType::Issue     => non_empty(&result.issue).or_else(|| non_empty(&result.episode_number)).map(Attribute::Issue),
+            AttributeType::Pages     => non_empty(&result.pages).map(Attribute::Pages),
+            AttributeType::Series    => non_empty(&result.series_title).map(Attribute::Series),
+            AttributeType::Type      => item_type_to_schema_type(&result.item_type).map(|t| Attribute::Type(t.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl AttributeParser for Zotero {
+    fn parse_attribute(parse_info: &ParseInfo, attribute_type: AttributeType) -> Option<Attribute> {
+        Self::parse_from_result(parse_info.zotero.as_ref()?, attribute_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{fetch_result, should_skip_zotero, Zotero};
+    use crate::attribute::{Attribute, AttributeType, Author, Date};
+    use crate::generator::attribute_config::{AttributeConfig, AttributePriority};
+    use crate::generator::{from_url, ArchiveOptions, MetadataType, RequestOptions};
+    use crate::mock_client::MockClient;
+    use crate::mock_server::{MockServer, Response};
+    use crate::{GenerationOptions, Reference};
+
+    const CITOID_RESPONSE: &str = r#"[{
+        "itemType": "journalArticle",
+        "title": "Tidal mixing in coastal estuaries",
+        "creators": [
+            {"firstName": "Jane", "lastName": "Doe", "creatorType": "author"},
+            {"name": "Coastal Research Group", "creatorType": "author"},
+            {"firstName": "Erik", "lastName": "Aalto", "creatorType": "editor"}
+        ],
+        "date": "2024-03-01",
+        "url": "https://journal.example.org/tidal-mixing",
+        "publicationTitle": "Journal of Coastal Research",
+        "volume": "40",
+        "issue": "2",
+        "pages": "101-118",
+        "ISSN": ["0749-0208"]
+    }]"#;
+
+    #[test]
+    fn skip_urls_unknown_to_citoid() {
+        assert!(should_skip_zotero(None));
+        assert!(should_skip_zotero(Some("file:///tmp/page.html")));
+        assert!(!should_skip_zotero(Some("https://example.com/story")));
+    }
+
+    #[test]
+    fn parse_citoid_response() {
+        let server = MockServer::serve(vec![(
+            "/zotero/https%3A%2F%2Fjournal.example.org%2Ftidal-mixing",
+            Response::ok(CITOID_RESPONSE),
+        )]);
+
+        let result = fetch_result(
+            "https://journal.example.org/tidal-mixing",
+            &server.url("/zotero/"),
+            None,
+            &RequestOptions::default(),
+        )
+        .unwrap();
+
+        let parse = |attribute_type| Zotero::parse_from_result(&result, attribute_type);
+        assert_eq!(
+            parse(AttributeType::Author),
+            Some(Attribute::Authors(vec![
+                Author::Person("Jane Doe".to_string()),
+                Author::Organization("Coastal Research Group".to_string()),
+            ]))
+        );
+        assert_eq!(parse(AttributeType::Date), Some(Attribute::Date(Date::YearMonthDay(
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        ))));
+        assert_eq!(parse(AttributeType::Journal), Some(Attribute::Journal("Journal of Coastal Research".to_string())));
+        assert_eq!(parse(AttributeType::Site), None);
+        assert_eq!(parse(AttributeType::Type), Some(Attribute::Type("ScholarlyArticle".to_string())));
+    }
+
+    #[test]
+    fn zotero_priority() {
+        let page = r#"<html><head><meta property="og:title" content="Tidal mixing | Journal of Coastal Research"></head></html>"#;
+        let page_server = MockServer::serve(vec![("/tidal-mixing", Response::ok(page))]);
+        let citoid_server = MockServer::start(|_| Response::ok(CITOID_RESPONSE));
+
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Zotero, MetadataType::OpenGraph])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            citoid_endpoint: Some(citoid_server.url("/zotero/")),
+            ..Default::default()
+        };
+        let reference = from_url(&page_server.url("/tidal-mixing"), &options).unwrap();
+
+        let Reference::ScholarlyArticle { title, journal, volume, .. } = reference else {
+            panic!("Expected a scholarly article")
+        };
+        assert_eq!(title, Some(Attribute::Title("Tidal mixing in coastal estuaries".to_string())));
+        assert_eq!(journal, Some(Attribute::Journal("Journal of Coastal Research".to_string())));
+        assert_eq!(volume, Some(Attribute::Volume("40".to_string())));
+    }
+
+    #[test]
+    fn citoid_requested_offline() {
+        let page_url = "https://journal.example.org/tidal-mixing";
+        let citoid_url = "https://en.wikipedia.org/api/rest_v1/data/citation/zotero/https%3A%2F%2Fjournal.example.org%2Ftidal-mixing";
+        let client = Arc::new(MockClient::new(&[
+            (page_url, "<html><head><title>Tidal mixing</title></head></html>"),
+            (citoid_url, CITOID_RESPONSE),
+        ]));
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Zotero])),
+            archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+            request_options: RequestOptions { http_client: Some(client.clone()), ..Default::default() },
+            ..Default::default()
+        };
+
+        let Reference::ScholarlyArticle { author, date, issue, pages, .. } = from_url(page_url, &options).unwrap() else {
+            panic!("Expected a scholarly article")
+        };
+        assert_eq!(
+            author,
+            Some(Attribute::Authors(vec![
+                Author::Person("Jane Doe".to_string()),
+                Author::Organization("Coastal Research Group".to_string()),
+            ]))
+        );
+        assert_eq!(date, Some(Attribute::Date(Date::YearMonthDay(chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()))));
+        assert_eq!(issue, Some(Attribute::Issue("2".to_string())));
+        assert_eq!(pages, Some(Attribute::Pages("101-118".to_string())));
+        assert_eq!(client.requested(), [page_url, citoid_url]);
+    }
+}
@@ -0,0 +1,49 @@
+//! Timing of reference generation from large pages. Ignored by default,
+//! run with `cargo test --release --test test_performance -- --ignored --nocapture`.
+
+use std::fs;
+use std::time::Instant;
+
+use url2ref::generator::ArchiveOptions;
+use url2ref::GenerationOptions;
+
+/// Size to which the fixture is padded, comparable to large news pages.
+const PAGE_SIZE: usize = 2_000_000;
+
+const ROUNDS: u32 = 10;
+
+/// Appends a body of article text to an HTML fixture, which only holds the `<head>`.
+fn padded_page(html_path: &str) -> String {
+    let html = fs::read_to_string(html_path).unwrap();
+    let paragraph = "<p>The harbour expansion was approved by the council on Tuesday, \
+                     after months of debate about the cost of the new quay.</p>\n";
+    let padding = paragraph.repeat(PAGE_SIZE / paragraph.len());
+    let (head, tail) = html.rsplit_once("</html>").unwrap();
+    format!("{head}<body>\n{padding}</body>\n</html>{tail}")
+}
+
+#[test]
+#[ignore]
+fn time_generation_from_large_page() {
+    let page = padded_page("./tests/data/case2/jyllands-posten_dk_2023-12-13.html");
+    let path = std::env::temp_dir().join(format!("url2ref-large-page-{}.html", std::process::id()));
+    fs::write(&path, &page).unwrap();
+
+    // Retrieved as a file:// URL, so that the page is processed like any
+    // other retrieved page, including the lookup of language alternates.
+    let url = format!("file://{}", path.display());
+    let options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        preferred_content_language: Some("da".to_string()),
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    for _ in 0..ROUNDS {
+        url2ref::generate(&url, &options).unwrap();
+    }
+    let elapsed = started.elapsed() / ROUNDS;
+    fs::remove_file(&path).unwrap();
+
+    println!("Generated a reference from a {} byte page in {:?} on average", page.len(), elapsed);
+}
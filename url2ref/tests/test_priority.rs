@@ -4,7 +4,8 @@ mod utils;
 use url2ref::generator::attribute_config::{AttributeConfig, AttributePriority};
 use url2ref::generator::MetadataType;
 use MetadataType::*;
-use url2ref::GenerationOptions;
+use url2ref::attribute::{Attribute, AttributeType, Author};
+use url2ref::{GenerationOptions, Reference};
 
 use utils::{compared_attributes_with_expected, get_expected_results};
 
@@ -59,3 +60,70 @@ fn test_priority_schema_over_opengraph_case7() {
 
     check(html_path, expected_results_path, order);
 }
+
+/// The Open Graph author of case 14 is a profile URL, so the
+/// Schema.org author is used despite the lower priority.
+#[test]
+fn test_opengraph_profile_url_author_case14() {
+    let html_path = "./tests/data/case14/fjordpost_example_org_2024-08-12.html";
+    let config = AttributeConfig::new(AttributePriority { priority: vec![OpenGraph, SchemaOrg] });
+
+    let options = GenerationOptions {
+        attribute_config: config,
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    let Reference::NewsArticle { author, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(author, Some(Attribute::Authors(vec![Author::Person("Jane Doe".to_string())])));
+}
+
+/// Case 2 has a title in both formats, but a date in Schema.org only.
+#[test]
+fn test_provenance_case2() {
+    let html_path = "./tests/data/case2/jyllands-posten_dk_2023-12-13.html";
+
+    for (order, title_source) in [(vec![OpenGraph, SchemaOrg], OpenGraph), (vec![SchemaOrg, OpenGraph], SchemaOrg)] {
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority { priority: order }),
+            ..Default::default()
+        };
+
+        let (_, provenance) = url2ref::generate_from_file_with_provenance(html_path, &options).unwrap();
+        assert_eq!(provenance.get(&AttributeType::Title), Some(&title_source));
+        assert_eq!(provenance.get(&AttributeType::Date), Some(&SchemaOrg));
+        assert_eq!(provenance.get(&AttributeType::AccessDate), None);
+    }
+}
+
+/// Open Graph has no author in case 2, so it is filled in from
+/// the fallback, while the other attributes keep their priority.
+#[test]
+fn test_fallback_author_case2() {
+    let html_path = "./tests/data/case2/jyllands-posten_dk_2023-12-13.html";
+    let config = AttributeConfig::new(AttributePriority::new(&[OpenGraph]));
+
+    let options = GenerationOptions { attribute_config: config.clone(), ..Default::default() };
+    let Reference::NewsArticle { author, .. } = url2ref::generate_from_file(html_path, &options).unwrap() else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(author, None);
+
+    let options = GenerationOptions {
+        attribute_config: config.with_fallback(AttributePriority::new(&[HtmlMeta, SchemaOrg])),
+        ..Default::default()
+    };
+    let (reference, provenance) = url2ref::generate_from_file_with_provenance(html_path, &options).unwrap();
+    let Reference::NewsArticle { title, author, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(author, Some(Attribute::Authors(vec![Author::Person("Marie Duedahl".to_string())])));
+    assert_eq!(
+        title,
+        Some(Attribute::Title("»En hyldest til mine afdøde forældre«: Topkok serverer en forret, de færreste nok får juleaften".to_string()))
+    );
+    assert_eq!(provenance.get(&AttributeType::Author), Some(&SchemaOrg));
+    assert_eq!(provenance.get(&AttributeType::Title), Some(&OpenGraph));
+}
@@ -1,5 +1,7 @@
 //! Integration testing suite.
 
+use std::time::Duration;
+
 use url2ref::GenerationOptions;
 use url2ref::generator::*;
 use url2ref::generator::attribute_config::*;
@@ -8,6 +10,8 @@ mod utils;
 use utils::{get_file_pairs, get_expected_results, compared_attributes_with_expected};
 
 const DATA_SAMPLES_PATH: &str = "./tests/data";
+/// Cached DOI lookups of the data samples, so that no requests are sent.
+const DOI_CACHE_PATH: &str = "./tests/cache";
 
 /// Performs testing for all the external HTML data samples by comparing the
 /// reference generation output to the expected values associated with each
@@ -44,10 +48,40 @@ fn check(html_path: &str, expected_results_path: &str) {
                     ..Default::default()
                 }
             },
+            HtmlMeta => {
+                let priorities = AttributePriority { priority: vec!(HtmlMeta)};
+                GenerationOptions {
+                    attribute_config: AttributeConfig::new(priorities),
+                    ..Default::default()
+                }
+            },
+            DublinCore => {
+                let priorities = AttributePriority { priority: vec!(DublinCore)};
+                GenerationOptions {
+                    attribute_config: AttributeConfig::new(priorities),
+                    ..Default::default()
+                }
+            },
+            Rdfa => {
+                let priorities = AttributePriority { priority: vec!(Rdfa)};
+                GenerationOptions {
+                    attribute_config: AttributeConfig::new(priorities),
+                    ..Default::default()
+                }
+            },
+            Zotero => {
+                let priorities = AttributePriority { priority: vec!(Zotero)};
+                GenerationOptions {
+                    attribute_config: AttributeConfig::new(priorities),
+                    response_cache: Some(ResponseCache::new(DOI_CACHE_PATH, Duration::MAX)),
+                    ..Default::default()
+                }
+            },
             Doi => {
                 let priorities = AttributePriority { priority: vec!(Doi)};
                 GenerationOptions {
                     attribute_config: AttributeConfig::new(priorities),
+                    response_cache: Some(ResponseCache::new(DOI_CACHE_PATH, Duration::MAX)),
                     ..Default::default()
                 }
             }
@@ -55,4 +89,334 @@ fn check(html_path: &str, expected_results_path: &str) {
 
         compared_attributes_with_expected(html_path, &expected_attributes, &generation_options);
     }
-}
\ No newline at end of file
+}
+/// The body of case 18 begins with a `WASHINGTON —` dateline.
+#[test]
+fn test_dateline_place_case18() {
+    let html_path = "./tests/data/case18/capitolwire_example_org_2024-05-21.html";
+    let options = GenerationOptions {
+        extract_dateline: true,
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    let url2ref::Reference::NewsArticle { place, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(place, Some(url2ref::attribute::Attribute::Place("Washington".to_string())));
+
+    let reference = url2ref::generate_from_file(html_path, &GenerationOptions::default()).unwrap();
+    let url2ref::Reference::NewsArticle { place, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(place, None);
+}
+
+/// The footer of case 25 holds a copyright notice naming the publisher,
+/// which the metadata of the page does not.
+#[test]
+fn test_copyright_publisher_case25() {
+    let html_path = "./tests/data/case25/harbourpost_example_dk_2024-08-14.html";
+    let options = GenerationOptions {
+        extract_copyright_publisher: true,
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    let url2ref::Reference::NewsArticle { publisher, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(publisher, Some(url2ref::attribute::Attribute::Publisher("Harbour Post Media ApS".to_string())));
+
+    let reference = url2ref::generate_from_file(html_path, &GenerationOptions::default()).unwrap();
+    let url2ref::Reference::NewsArticle { publisher, .. } = reference else {
+        panic!("Expected a news article")
+    };
+    assert_eq!(publisher, None);
+}
+
+/// The DOI of case 26 is only given by the URL the page was saved from,
+/// so the page can only be cited as a scholarly article given the URL.
+#[test]
+fn test_doi_from_url_case26() {
+    use url2ref::attribute::Attribute;
+
+    let html_path = "./tests/data/case26/coastalstudies_example_org_2024-07-08.html";
+    let url = "https://coastalstudies.example.org/doi/10.5555/coastal.2024.17";
+    let options = GenerationOptions {
+        attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::Doi, MetadataType::OpenGraph])),
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        response_cache: Some(ResponseCache::new(DOI_CACHE_PATH, Duration::MAX)),
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file_with_url(html_path, url, &options).unwrap();
+    let url2ref::Reference::ScholarlyArticle { journal, url: cited_url, .. } = reference else {
+        panic!("Expected a scholarly article")
+    };
+    assert_eq!(journal, Some(Attribute::Journal("Journal of Coastal Studies".to_string())));
+    assert_eq!(cited_url, Some(Attribute::Url(url.to_string())));
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert!(!matches!(reference, url2ref::Reference::ScholarlyArticle { .. }));
+    assert_eq!(reference.url(), None);
+}
+
+/// Case 27 is a blank page, which is only cited when not in strict mode.
+#[test]
+fn test_strict_mode_case27() {
+    use url2ref::attribute::AttributeType;
+
+    let html_path = "./tests/data/case27/blank_example_com_2024-09-02.html";
+    let options = GenerationOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    let result = url2ref::generate_from_file(html_path, &options);
+    let Err(ReferenceGenerationError::InsufficientMetadata(missing)) = result else {
+        panic!("Expected insufficient metadata")
+    };
+    assert_eq!(missing, [AttributeType::Title, AttributeType::Url]);
+
+    let reference = url2ref::generate_from_file(html_path, &GenerationOptions::default()).unwrap();
+    assert_eq!((reference.title(), reference.url()), (None, None));
+}
+
+/// The `article:author` tags of case 30 only link to Facebook profiles,
+/// while its `article:section` tag gives the department of the article.
+#[test]
+fn test_article_section_case30() {
+    let html_path = "./tests/data/case30/fjordlys_example_no_2024-09-02.html";
+
+    let reference = url2ref::generate_from_file(html_path, &GenerationOptions::default()).unwrap();
+    assert!(reference.authors().is_empty());
+    assert!(reference.wiki().contains("|department=Kultur"));
+}
+
+/// Case 31 is the empty shell of a single-page application.
+#[test]
+fn test_javascript_required_case31() {
+    let html_path = "./tests/data/case31/app_tidewatch_example_no_2024-10-01.html";
+
+    let result = url2ref::generate_all_from_file(html_path, &GenerationOptions::default()).unwrap();
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("requires JavaScript"));
+
+    let html_path = "./tests/data/case30/fjordlys_example_no_2024-09-02.html";
+    let result = url2ref::generate_all_from_file(html_path, &GenerationOptions::default()).unwrap();
+    assert!(result.warnings.is_empty());
+}
+
+/// Case 32 has no metadata, but was saved from a page of the BBC.
+#[test]
+fn test_site_from_host_case32() {
+    let html_path = "./tests/data/case32/bbc_co_uk_2024-10-02.html";
+    let url = "https://www.bbc.co.uk/news/articles/c0k4ez2y7q1o";
+    let mut options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file_with_url(html_path, url, &options).unwrap();
+    assert_eq!(reference.site(), None);
+
+    options.derive_site_from_host = true;
+    let reference = url2ref::generate_from_file_with_url(html_path, url, &options).unwrap();
+    assert_eq!(reference.site(), Some("BBC"));
+}
+
+/// Case 33 credits its photo in the `creditText` of the Schema.org image,
+/// while Open Graph only gives its alternative text.
+#[test]
+fn test_image_credit_case33() {
+    use url2ref::attribute::AttributeType;
+
+    let html_path = "./tests/data/case33/harbourpost_example_dk_2024-10-03.html";
+    let mut options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.image_credit(), Some("Færgen lægger til i Rønne havn tidligt torsdag morgen."));
+
+    options.attribute_config = options
+        .attribute_config
+        .with_override(AttributeType::ImageCredit, AttributePriority::new(&[MetadataType::SchemaOrg]));
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.image_credit(), Some("Foto: Søren Bech/Ritzau Scanpix"));
+    assert!(!reference.wiki().contains("Scanpix"));
+}
+
+/// Case 34 describes the site, the breadcrumb trail and the article in
+/// separate JSON-LD blocks, the last of which has several types.
+#[test]
+fn test_json_ld_blocks_case34() {
+    let html_path = "./tests/data/case34/northernledger_example_org_2024-10-04.html";
+    let options = GenerationOptions {
+        attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::SchemaOrg])),
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.title(), Some("Cod quotas cut for third year running"));
+    assert_eq!(reference.authors().len(), 2);
+    assert!(reference.wiki().contains("|department=Fisheries"));
+}
+
+/// Case 39 republishes a news agency story, whose original date is given
+/// by Schema.org along with the canonical URL, and Open Graph gives the
+/// date on which it was republished.
+#[test]
+fn test_syndicated_date_case39() {
+    let html_path = "./tests/data/case39/riversidecourier_example_com_2024-10-10.html";
+    let options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    let wiki = reference.wiki();
+    assert!(wiki.contains("|date=2024-10-09"), "{wiki}");
+    assert_eq!(reference.site(), Some("The Riverside Courier"));
+}
+
+/// Case 38 is encoded in windows-1252, declared only by its `<meta>` tag.
+#[test]
+fn test_windows_1252_case38() {
+    let html_path = "./tests/data/case38/soendagsavisen_example_dk_2024-10-08.html";
+    let options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.title(), Some("Færgen til Ærø sejler igen efter måneders pause"));
+    assert_eq!(reference.site(), Some("Søndagsavisen"));
+}
+
+/// Case 36 wraps its JSON-LD in a CDATA section, which is stripped.
+#[test]
+fn test_cdata_json_ld_case36() {
+    let html_path = "./tests/data/case36/fjordlys_example_no_2024-10-06.html";
+    let options = GenerationOptions {
+        attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::SchemaOrg])),
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.title(), Some("Ny bru over Trondheimsfjorden utsatt"));
+    assert_eq!(reference.site(), Some("Fjordlys"));
+}
+
+/// Case 35 is a Stack Overflow question, whose Open Graph metadata
+/// names neither the asker nor the date the question was asked.
+#[test]
+fn test_qa_page_case35() {
+    let html_path = "./tests/data/case35/stackoverflow_com_2024-10-05.html";
+    let options = GenerationOptions {
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(reference.title(), Some("How do I merge two dictionaries in a single expression in Python?"));
+    assert_eq!(reference.authors(), [url2ref::attribute::Author::Person("Carl Meyer".to_string())]);
+    let wiki = reference.wiki();
+    assert!(wiki.contains("|site=Stack Overflow"), "{wiki}");
+    assert!(wiki.contains("|date=2008-09-02"), "{wiki}");
+}
+
+/// The hash of the page is that of the fixture as stored on disk.
+#[test]
+fn test_content_hash_case1() {
+    let html_path = "./tests/data/case1/politiken_dk_2023-12-11.html";
+    let options = GenerationOptions {
+        include_content_hash: true,
+        archive_options: ArchiveOptions { include_archived: false, ..Default::default() },
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert!(reference.bibtex().contains("note = \"SHA-256 of the cited page: \
+        8c38a69a7cdc891d37097ed23847e12b736e2b0d7f8a7a8c05e873fe188e98e4\""));
+    assert!(!reference.wiki().contains("8c38a69a"));
+}
+
+/// Case 23 is a news article with an author, a site and a URL,
+/// each of which has its place in the Harvard style.
+#[test]
+fn test_harvard_case23() {
+    let html_path = "./tests/data/case23/embed_coastaltimes_example_com_2024-06-10.html";
+    let options = GenerationOptions {
+        access_date: Some(url2ref::attribute::Date::YearMonthDay(chrono::NaiveDate::from_ymd_opt(2024, 6, 11).unwrap())),
+        ..Default::default()
+    };
+
+    let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+    assert_eq!(
+        reference.harvard(),
+        "Doe, J. (2024) 'Storm surge closes coastal road', Coastal Times, 10 June. \
+         Available at: https://coastaltimes.example.com/news/storm-surge (Accessed: 11 June 2024)."
+    );
+}
+
+/// The authors of case 24 are affiliated with institutions according to
+/// both its Highwire Press tags and its Schema.org metadata.
+#[test]
+fn test_affiliations_case24() {
+    use url2ref::attribute::{Affiliation, Attribute, AttributeType};
+
+    let html_path = "./tests/data/case24/journal_coastalresearch_example_org_2024-03-01.html";
+    let affiliation = |author: &str, institutions: &[&str]| Affiliation {
+        author: author.to_string(),
+        institutions: institutions.iter().map(|institution| institution.to_string()).collect(),
+    };
+
+    for (metadata_type, jane_doe, maria_lind) in [
+        (MetadataType::HtmlMeta, "Doe, Jane", "Lind, Maria"),
+        (MetadataType::SchemaOrg, "Jane Doe", "Maria Lind"),
+    ] {
+        let options = GenerationOptions {
+            attribute_config: AttributeConfig::new(AttributePriority::new(&[MetadataType::SchemaOrg]))
+                .with_override(AttributeType::Author, AttributePriority::new(&[metadata_type])),
+            ..Default::default()
+        };
+
+        let reference = url2ref::generate_from_file(html_path, &options).unwrap();
+        let url2ref::Reference::ScholarlyArticle { affiliations, .. } = reference else {
+            panic!("Expected a scholarly article")
+        };
+        assert_eq!(
+            affiliations,
+            Some(Attribute::Affiliations(vec![
+                affiliation(jane_doe, &["Department of Oceanography, University of Bergen", "Bjerknes Centre for Climate Research"]),
+                affiliation(maria_lind, &["Swedish Meteorological and Hydrological Institute"]),
+            ]))
+        );
+    }
+}
+
+/// Checks that batch generation returns the references in the order of the
+/// files, along with errors for files which cannot be read.
+#[test]
+fn test_batch_from_files() {
+    let paths = [
+        "./tests/data/case24/journal_coastalresearch_example_org_2024-03-01.html",
+        "./tests/data/case23/missing.html",
+        "./tests/data/case18/capitolwire_example_org_2024-05-21.html",
+    ];
+    let options = GenerationOptions::default();
+
+    let results = url2ref::generate_batch_from_files(&paths, &options);
+    assert_eq!(results.len(), paths.len());
+    assert!(results[1].is_err());
+    for index in [0, 2] {
+        let expected = url2ref::generate_from_file(paths[index], &options).unwrap();
+        let reference = results[index].as_ref().unwrap();
+        assert_eq!((reference.title(), reference.url()), (expected.title(), expected.url()));
+    }
+}
@@ -5,7 +5,7 @@ use serde_yaml::{from_reader, Mapping, Value};
 use std::{collections::HashMap, fs::read_dir, fs::File, path::PathBuf};
 
 use url2ref::{
-    attribute::{Attribute, Author, Date},
+    attribute::{Attribute, AttributeType, Author, Date},
     GenerationOptions, Reference,
     generator::MetadataType
 };
@@ -65,12 +65,18 @@ pub fn string_to_attribute(field: &String, value: &String) -> Attribute {
         "title" => Attribute::Title(value.clone()),
         "author" => Attribute::Authors(vec![Author::Generic(value.clone())]),
         "site" => Attribute::Site(value.clone()),
+        "section" => Attribute::Section(value.clone()),
         "date" => Attribute::Date(parse_date(value.as_str())),
+        "original_date" => Attribute::OriginalDate(parse_date(value.as_str())),
         "language" => Attribute::Language(value.clone()),
         "locale" => Attribute::Locale(value.clone()),
         "url" => Attribute::Url(value.clone()),
         "journal" => Attribute::Journal(value.clone()),
+        "volume" => Attribute::Volume(value.clone()),
+        "issue" => Attribute::Issue(value.clone()),
+        "series" => Attribute::Series(value.clone()),
         "publisher" => Attribute::Publisher(value.clone()),
+        "duration" => Attribute::Duration(value.clone()),
         _ => panic!("Unknown attribute"),
     }
 }
@@ -88,11 +94,7 @@ pub fn seq_to_attribute(seq: &Vec<Value>) -> Attribute {
 }
 
 pub fn string_to_parser(field: &String) -> MetadataType {
-    match field.as_str() {
-        "opengraph" => MetadataType::OpenGraph,
-        "schema_org" => MetadataType::SchemaOrg,
-        _ => panic!("Unknown parser specified"),
-    }
+    field.parse().expect("Unknown parser specified")
 }
 
 /// Collects pairs of .html samples and .yml files describing the expected
@@ -159,66 +161,39 @@ pub fn compared_attributes_with_expected(
     let reference = reference_result.unwrap();
 
     /// Function for comparing two [`Attribute`]s
-    fn compare_attributes(actual_attribute: &Option<Attribute>, expected_attribute: &Attribute) {
-        assert!(actual_attribute.is_some());
-        assert_eq!(
-            actual_attribute.clone().unwrap(),
-            expected_attribute.clone()
-        );
+    fn compare_attributes(actual_attribute: Option<&Attribute>, expected_attribute: &Attribute) {
+        assert_eq!(actual_attribute, Some(expected_attribute));
     }
 
-    // TODO: Resolve this...
     println!("{:?}", reference);
-    assert!(matches!(reference, Reference::NewsArticle { .. }));
-    if let Reference::NewsArticle {
-        title,
-        translated_title,
-        author,
-        date,
-        language,
-        site,
-        url,
-        ..
-    } = reference
-    {
-        for attribute in expected_attributes {
-            match attribute {
-                Attribute::Title(_) => {
-                    compare_attributes(&title, attribute);
-                }
-                Attribute::TranslatedTitle(_) => {
-                    compare_attributes(&translated_title, attribute)
-                },
-                Attribute::Authors(author_list) => {
-                    let authors_cloned = author.clone();
-
-                    // Small hack: .yml files do not discern between author types,
-                    // so all are converted to Author::Generic.
-                    assert!(authors_cloned.is_some());
-                    let to_generic: Vec<Author> = author_list
-                        .iter()
-                        .map(|a| match a {
-                            Author::Person(s) => Author::Generic(s.clone()),
-                            Author::Organization(s) => Author::Generic(s.clone()),
-                            Author::Generic(_) => a.clone(),
-                        })
-                        .collect();
-
-                    assert_eq!(Attribute::Authors(to_generic), attribute.clone());
-                }
-                Attribute::Date(_) => {
-                    compare_attributes(&date, attribute);
-                }
-                Attribute::Language(_) => {
-                    compare_attributes(&language, attribute);
-                }
-                Attribute::Site(_) => {
-                    compare_attributes(&site, attribute);
-                }
-                Attribute::Url(_) => {
-                    compare_attributes(&url, attribute);
-                }
-                _ => panic!("Non-viable test attribute used"),
+    for attribute in expected_attributes {
+        match attribute {
+            Attribute::TranslatedTitle(_) => {
+                let translated_title = reference.fields().into_iter().find(|(name, _)| *name == "translated_title");
+                compare_attributes(translated_title.map(|(_, attribute)| attribute), attribute)
+            },
+            Attribute::Authors(_) => {
+                // Small hack: .yml files do not discern between author types,
+                // so all are converted to Author::Generic.
+                let to_generic: Vec<Author> = reference
+                    .authors()
+                    .iter()
+                    .map(|a| match a {
+                        Author::Person(s) => Author::Generic(s.clone()),
+                        Author::Organization(s) => Author::Generic(s.clone()),
+                        Author::Generic(_) => a.clone(),
+                    })
+                    .collect();
+
+                assert_eq!(Attribute::Authors(to_generic), attribute.clone());
+            }
+            Attribute::Journal(_) => {
+                assert!(matches!(reference, Reference::ScholarlyArticle { .. }));
+                compare_attributes(reference.get(AttributeType::Journal), attribute);
+            }
+            _ => {
+                let attribute_type = attribute.attribute_type().expect("Non-viable test attribute used");
+                compare_attributes(reference.get(attribute_type), attribute);
             }
         }
     }